@@ -1,9 +1,15 @@
+use std::path::Path;
 use std::rc::Rc;
 
+use chrono::{DateTime, Utc};
+
 use crate::{
     cache::{self, MetaDataCache},
-    cli::{EntryFormat, OutputOptions, TimelineFormat},
-    ntds::{self, Computer, DataTable, Group, LinkTable, ObjectType, Person, Schema, SdTable},
+    cli::{EntryFormat, OutputOptions, TimelineFormat, TopologyView},
+    ntds::{
+        self, Computer, DataTable, Group, LinkTable, ObjectType, Person, Schema, SdTable,
+        TimelineFilter, TrustedDomain,
+    },
     object_tree::ObjectTree,
     EntryId, EsedbInfo, SerializationType,
 };
@@ -11,7 +17,7 @@ use crate::{
 pub struct CDatabase<'info, 'db> {
     _esedbinfo: &'info EsedbInfo<'db>,
     data_table: DataTable<'info, 'db>,
-    link_table: Rc<LinkTable>,
+    _link_table: Rc<LinkTable<'info, 'db>>,
     _sd_table: Option<Rc<SdTable>>,
 }
 
@@ -68,7 +74,7 @@ impl<'info, 'db> CDatabase<'info, 'db> {
 
         Ok(Self {
             _esedbinfo: esedbinfo,
-            link_table,
+            _link_table: link_table,
             data_table,
             _sd_table: sd_table,
         })
@@ -89,6 +95,10 @@ impl<'info, 'db> CDatabase<'info, 'db> {
         self.show_typed_objects::<Computer<T>>(options, ObjectType::Computer)
     }
 
+    pub fn show_trusts<T: SerializationType>(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        self.show_typed_objects::<TrustedDomain<T>>(options, ObjectType::TrustedDomain)
+    }
+
     pub fn show_typed_objects<O: ntds::FromDataTable + ntds::IsMemberOf>(
         &self,
         options: &OutputOptions,
@@ -110,9 +120,81 @@ impl<'info, 'db> CDatabase<'info, 'db> {
         options: &OutputOptions,
         include_deleted: bool,
         format: &TimelineFormat,
+        filter: &TimelineFilter,
     ) -> anyhow::Result<()> {
         self.data_table
-            .show_timeline(options, &self.link_table, include_deleted, format)
+            .show_timeline(options, include_deleted, format, filter)
+    }
+
+    pub fn show_password_policies(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        self.data_table.show_password_policies(options)
+    }
+
+    pub fn show_gpos(&self, options: &OutputOptions, links: bool) -> anyhow::Result<()> {
+        self.data_table.show_gpos(options, links)
+    }
+
+    pub fn show_dns_records(
+        &self,
+        options: &OutputOptions,
+        zone_files: Option<&Path>,
+    ) -> anyhow::Result<()> {
+        self.data_table.show_dns_records(options, zone_files)
+    }
+
+    pub fn show_sites(
+        &self,
+        options: &OutputOptions,
+        show: TopologyView,
+        dot: bool,
+    ) -> anyhow::Result<()> {
+        self.data_table.show_sites(options, show, dot)
+    }
+
+    pub fn show_certificate_services(
+        &self,
+        options: &OutputOptions,
+        cas: bool,
+    ) -> anyhow::Result<()> {
+        self.data_table.show_certificate_services(options, cas)
+    }
+
+    pub fn show_rodc_password_policy(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        self.data_table.show_rodc_password_policy(options)
+    }
+
+    pub fn show_deleted_objects(
+        &self,
+        options: &OutputOptions,
+        select: Option<&str>,
+        ldif: Option<&Path>,
+    ) -> anyhow::Result<()> {
+        self.data_table.show_deleted_objects(options, select, ldif)
+    }
+
+    pub fn show_replication_conflicts(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        self.data_table.show_replication_conflicts(options)
+    }
+
+    pub fn show_anomalies(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        self.data_table.show_anomalies(options)
+    }
+
+    pub fn show_diff(&self, baseline: &Self, options: &OutputOptions) -> anyhow::Result<()> {
+        self.data_table.show_diff(&baseline.data_table, options)
+    }
+
+    pub fn show_changes(
+        &self,
+        options: &OutputOptions,
+        since_usn: Option<i64>,
+        since: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()> {
+        self.data_table.show_changes(options, since_usn, since)
+    }
+
+    pub fn show_domain_info(&self, entry_format: EntryFormat) -> anyhow::Result<()> {
+        self.data_table.show_domain_info(entry_format)
     }
 
     pub fn show_entry(
@@ -123,8 +205,52 @@ impl<'info, 'db> CDatabase<'info, 'db> {
         self.data_table.show_entry(entry_id, entry_format)
     }
 
-    pub fn show_tree(&self, max_depth: u8) -> crate::ntds::Result<()> {
-        self.data_table.show_tree(max_depth)
+    pub fn show_tree(
+        &self,
+        max_depth: u8,
+        show_delegations: bool,
+        show_gpo_links: bool,
+    ) -> anyhow::Result<()> {
+        self.data_table
+            .show_tree(max_depth, show_delegations, show_gpo_links)
+    }
+
+    pub fn show_ou_delegations(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        self.data_table.show_ou_delegations(options)
+    }
+
+    pub fn show_owners(&self, options: &OutputOptions, creators: bool) -> anyhow::Result<()> {
+        self.data_table.show_owners(options, creators)
+    }
+
+    pub fn show_kerberos_delegations(
+        &self,
+        options: &OutputOptions,
+        hide_domain_controllers: bool,
+    ) -> anyhow::Result<()> {
+        self.data_table
+            .show_kerberos_delegations(options, hide_domain_controllers)
+    }
+
+    pub fn show_roastable_accounts(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        self.data_table.show_roastable_accounts(options)
+    }
+
+    pub fn show_shadow_credentials(
+        &self,
+        options: &OutputOptions,
+        export_keys: bool,
+    ) -> anyhow::Result<()> {
+        self.data_table
+            .show_shadow_credentials(options, export_keys)
+    }
+
+    pub fn show_sid_history(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        self.data_table.show_sid_history(options)
+    }
+
+    pub fn show_primary_groups(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        self.data_table.show_primary_groups(options)
     }
 
     pub fn search_entries(&self, regex: &str) -> anyhow::Result<()> {
@@ -31,6 +31,12 @@ impl ColumnsOfTable {
     pub fn iter(&self) -> impl Iterator<Item = &Column> {
         self.ids.iter()
     }
+
+    pub fn get(&self, name: &str) -> Option<&Column> {
+        self.names
+            .get(name)
+            .map(|index| self.ids.index(*(index.deref()) as usize))
+    }
 }
 
 impl Index<ColumnIndex> for ColumnsOfTable {
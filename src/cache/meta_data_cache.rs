@@ -52,8 +52,14 @@ pub struct MetaDataCache {
 
     #[getset(skip)]
     record_by_guid: HashMap<Guid, RecordPointer>,
+
+    #[getset(skip)]
+    record_by_sid: HashMap<Sid, RecordPointer>,
     attributes: HashMap<i32, String>,
 
+    /// `oMSyntax` of all attributes, indexed by the attribute id
+    attribute_syntaxes: HashMap<i32, i32>,
+
     #[getset(get = "pub")]
     root: RecordPointer,
 }
@@ -71,6 +77,7 @@ impl TryFrom<&EsedbInfo<'_>> for MetaDataCache {
         let rdn_att_id = NtdsAttributeId::AttRdnAttId.id(info);
         let attribute_id_column = NtdsAttributeId::AttAttributeId.id(info);
         let ldap_display_name_column = NtdsAttributeId::AttLdapDisplayName.id(info);
+        let om_syntax_column = NtdsAttributeId::AttOmSyntax.id(info);
         let sam_account_name_column = NtdsAttributeId::AttSamAccountName.id(info);
         let sd_id_column = NtdsAttributeId::AttNtSecurityDescriptor.id(info);
 
@@ -78,7 +85,9 @@ impl TryFrom<&EsedbInfo<'_>> for MetaDataCache {
         let mut record_rows = HashMap::new();
         let mut children_of: HashMap<RecordId, HashSet<RecordPointer>> = HashMap::new();
         let mut attributes = HashMap::new();
+        let mut attribute_syntaxes = HashMap::new();
         let mut record_by_guid = HashMap::new();
+        let mut record_by_sid = HashMap::new();
         let mut root = None;
         //let mut root_dse = None;
         let count = libesedb_count(|| info.data_table().count_records())?;
@@ -129,6 +138,11 @@ impl TryFrom<&EsedbInfo<'_>> for MetaDataCache {
                                     bail!("unambigious attribute id: {attribute_id} in {record_id}")
                                 }
                             }
+                            if let Some(om_syntax) =
+                                i32::from_record_opt(&record, om_syntax_column)?
+                            {
+                                attribute_syntaxes.insert(attribute_id, om_syntax);
+                            }
                         }
 
                         let rdn_typ_col = i32::from_record_opt(&record, rdn_att_id)?;
@@ -163,6 +177,10 @@ impl TryFrom<&EsedbInfo<'_>> for MetaDataCache {
                             }
                         }
 
+                        if let Some(sid) = &sid {
+                            record_by_sid.entry(sid.clone()).or_insert(record_ptr);
+                        }
+
                         records.push(DataEntryCore {
                             record_ptr,
                             parent,
@@ -214,7 +232,9 @@ impl TryFrom<&EsedbInfo<'_>> for MetaDataCache {
             record_rows,
             children_of,
             attributes,
+            attribute_syntaxes,
             record_by_guid,
+            record_by_sid,
             root: root.expect("no root object found"),
         })
     }
@@ -308,6 +328,46 @@ impl MetaDataCache {
         self.record_by_guid.get(guid)
     }
 
+    /// returns all records which have an `objectGUID`
+    pub fn records_by_guid(&self) -> impl Iterator<Item = (&Guid, &RecordPointer)> {
+        self.record_by_guid.iter()
+    }
+
+    pub fn ptr_from_sid(&self, sid: &Sid) -> Option<&RecordPointer> {
+        self.record_by_sid.get(sid)
+    }
+
+    /// returns the id of the attribute with the given `lDAPDisplayName`
+    pub fn attribute_id_of(&self, ldap_display_name: &str) -> Option<i32> {
+        self.attributes
+            .iter()
+            .find(|(_, name)| name.eq_ignore_ascii_case(ldap_display_name))
+            .map(|(id, _)| *id)
+    }
+
+    /// returns the `lDAPDisplayName` of the attribute with the given id
+    pub fn attribute_name_of(&self, attribute_id: i32) -> Option<&str> {
+        self.attributes.get(&attribute_id).map(String::as_str)
+    }
+
+    /// returns the ids and `lDAPDisplayName`s of all attributes which have
+    /// the given `oMSyntax`
+    pub fn attributes_with_syntax(&self, om_syntax: i32) -> impl Iterator<Item = (i32, &str)> {
+        self.attribute_syntaxes
+            .iter()
+            .filter(move |(_, syntax)| **syntax == om_syntax)
+            .filter_map(|(id, _)| self.attributes.get(id).map(|name| (*id, name.as_str())))
+    }
+
+    /// returns the name of the schema class of this entry (e.g. `Computer`)
+    pub fn object_class_name(&self, entry: &DataEntryCore) -> Option<&str> {
+        entry
+            .object_category()
+            .as_ref()
+            .and_then(|id| self.record(id))
+            .map(|type_entry| type_entry.rdn().name().as_str())
+    }
+
     pub fn rdn(&self, entry: &DataEntryCore) -> String {
         if let Some(type_entry_id) = entry.object_category() {
             if let Some(type_entry) = self.record(type_entry_id) {
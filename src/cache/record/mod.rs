@@ -78,8 +78,12 @@ impl<'info, 'db> WithValue<ColumnIndex> for Record<'info, 'db> {
                             Some(Value::Long(Box::new(x.vec()?)))
                         }
                         libesedb::Value::Multi => {
-                            let v = self.record.multi(*index)?.variant();
-                            Some(v.into())
+                            let v = self.record.multi(*index)?;
+                            let mut values = Vec::new();
+                            for value in v.iter_values()? {
+                                values.push(Value::from(value?));
+                            }
+                            Some(Value::Multi(values))
                         }
                         v => Some(v.into()),
                     })
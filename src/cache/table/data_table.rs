@@ -3,7 +3,7 @@ use getset::Getters;
 use std::rc::Rc;
 
 use crate::{
-    cache::{self, ColumnsOfTable, MetaDataCache},
+    cache::{self, ColumnIndex, ColumnsOfTable, MetaDataCache},
     esedb_mitigation::libesedb_count,
     ntds::DataTableRecord,
     object_tree_entry::ObjectTreeEntry,
@@ -75,6 +75,35 @@ impl<'info, 'db> DataTable<'info, 'db> {
             ptr,
         ))
     }
+    /// returns the column which contains the attribute with the given id,
+    /// if there is one
+    pub fn column_of_attribute_id(&self, attribute_id: i32) -> Option<ColumnIndex> {
+        self.esedbinfo
+            .mapping()
+            .info_by_attribute_id(attribute_id)
+            .map(|info| *info.id())
+    }
+
+    /// finds the column of an attribute which is not known in
+    /// [`crate::ntds::NtdsAttributeId`], using the attribute id which is
+    /// stored in the schema
+    pub fn column_of_attribute(&self, ldap_display_name: &str) -> Option<ColumnIndex> {
+        match self.metadata.attribute_id_of(ldap_display_name) {
+            Some(attribute_id) => match self.esedbinfo.mapping().info_by_attribute_id(attribute_id)
+            {
+                Some(info) => Some(*info.id()),
+                None => {
+                    log::warn!("there is no column for the attribute '{ldap_display_name}' ({attribute_id})");
+                    None
+                }
+            },
+            None => {
+                log::warn!("the schema does not contain the attribute '{ldap_display_name}'");
+                None
+            }
+        }
+    }
+
     pub fn path_to_str(&self, path: &[Rc<ObjectTreeEntry>]) -> String {
         let v: Vec<_> = path.iter().map(|e| e.name().to_string()).collect();
         v.join(",")
@@ -25,6 +25,18 @@ where
     #[getset(get = "pub")]
     link_base_id: ColumnIndex,
 
+    /// the binary part of DN-Binary values
+    #[getset(get = "pub")]
+    link_data_id: Option<ColumnIndex>,
+
+    #[getset(get = "pub")]
+    link_deltime_id: Option<ColumnIndex>,
+
+    /// replication metadata of link values (only available for
+    /// linked value replication)
+    #[getset(get = "pub")]
+    link_metadata_id: Option<ColumnIndex>,
+
     // this is needed for `::all_atributes`
     columns: Rc<ColumnsOfTable>,
 }
@@ -47,27 +59,27 @@ where
             link_dnt_id: *columns["link_DNT"].index(),
             backlink_dnt_id: *columns["backlink_DNT"].index(),
             link_base_id: *columns["link_base"].index(),
+            link_data_id: columns.get("link_data").map(|c| *c.index()),
+            link_deltime_id: columns.get("link_deltime").map(|c| *c.index()),
+            link_metadata_id: columns.get("link_metadata").map(|c| *c.index()),
             columns: Rc::new(ColumnsOfTable::try_from(table)?)
         })
     }
 }
 
 impl<'info, 'db> LinkTable<'info, 'db> {
-    pub fn iter(&'db self) -> impl Iterator<Item = cache::Record<'info, 'db>> {
+    pub fn iter(&self) -> impl Iterator<Item = cache::Record<'info, 'db>> {
+        let table_id = self._table_id;
+        let esedbinfo = self.esedbinfo;
+        let columns = Rc::clone(&self.columns);
         self._table
             .iter_records()
             .unwrap()
             .map(|r| r.unwrap())
             .zip(0..)
-            .map(|(r, row)| {
-                cache::Record::try_from(
-                    r,
-                    self._table_id,
-                    row.into(),
-                    self.esedbinfo,
-                    Rc::clone(&self.columns),
-                )
-                .unwrap()
+            .map(move |(r, row)| {
+                cache::Record::try_from(r, table_id, row.into(), esedbinfo, Rc::clone(&columns))
+                    .unwrap()
             })
     }
 }
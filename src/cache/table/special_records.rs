@@ -7,16 +7,22 @@ use crate::object_tree_entry::ObjectTreeEntry;
 #[derive(Getters)]
 #[getset(get = "pub", set = "pub")]
 pub struct SpecialRecords {
+    domain_root: Rc<ObjectTreeEntry>,
+    configuration: Rc<ObjectTreeEntry>,
     schema: Rc<ObjectTreeEntry>,
     deleted_objects: Rc<ObjectTreeEntry>,
 }
 
 impl SpecialRecords {
     pub fn new(
+        domain_root: Rc<ObjectTreeEntry>,
+        configuration: Rc<ObjectTreeEntry>,
         schema: Rc<ObjectTreeEntry>,
         deleted_objects: Rc<ObjectTreeEntry>,
     ) -> Self {
         Self {
+            domain_root,
+            configuration,
             schema,
             deleted_objects,
         }
@@ -1,7 +1,10 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
 use clap::{Subcommand, ValueEnum};
 use strum::Display;
 
-use super::{EntryFormat, MemberOfAttribute, OutputFormat};
+use super::{parse_timestamp, EntryFormat, MemberOfAttribute, OutputFormat};
 
 #[derive(Subcommand)]
 pub enum Commands {
@@ -95,7 +98,7 @@ pub enum Commands {
         member_of_attribute: MemberOfAttribute,
     },
 
-    /// create a timeline (in flow-record format)
+    /// create a timeline (in flow-record, bodyfile, TLN, l2tcsv or Timesketch format)
     Timeline {
         /// show objects of any type (this might be a lot)
         #[clap(long("all-objects"))]
@@ -108,6 +111,25 @@ pub enum Commands {
         /// output format
         #[clap(short('F'), long("format"), default_value_t=TimelineFormat::Record)]
         format: TimelineFormat,
+
+        /// only show events which happened at or after this time
+        #[clap(long("from"), value_parser = parse_timestamp)]
+        from: Option<DateTime<Utc>>,
+
+        /// only show events which happened at or before this time
+        #[clap(long("to"), value_parser = parse_timestamp)]
+        to: Option<DateTime<Utc>>,
+
+        /// only show objects of this schema class (e.g. Person or
+        /// Group-Policy-Container, see the `types` command). Can be used
+        /// multiple times
+        #[clap(long("type"), conflicts_with("all_objects"))]
+        object_types: Vec<String>,
+
+        /// only show events whose description or attribute name matches
+        /// this regular expression (e.g. 'logon' or 'pwdLastSet')
+        #[clap(long("event"))]
+        event: Option<String>,
     },
 
     /// list all defined types
@@ -122,6 +144,299 @@ pub enum Commands {
         /// maximum recursion depth
         #[clap(long("max-depth"), default_value_t = 4)]
         max_depth: u8,
+
+        /// annotate organizational units and containers with explicit
+        /// delegations (see the `ou-delegation` command)
+        #[clap(long("show-delegations"))]
+        show_delegations: bool,
+
+        /// annotate the domain, sites and organizational units with their
+        /// linked GPOs (see the `gpo` command)
+        #[clap(long("show-gpo-links"))]
+        show_gpo_links: bool,
+    },
+
+    /// display trust relationships to other domains
+    Trusts {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+
+        /// include the distinguished name (DN) in the output.
+        #[clap(short('D'), long("include-dn"))]
+        include_dn: bool,
+    },
+
+    /// list delegations on organizational units and containers
+    ///
+    /// Only explicit (non-inherited) ACEs are shown, which grant
+    /// non-default principals rights over child objects.
+    OuDelegation {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+    },
+
+    /// list objects which are owned by non-default principals
+    ///
+    /// Owners other than Domain Admins, Enterprise Admins, SYSTEM and
+    /// Administrators are reported. Privileged groups, GPOs and computers are
+    /// listed first.
+    Owners {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+
+        /// instead of the owners, list the principals which created computer
+        /// accounts (using the ms-DS-MachineAccountQuota), including the
+        /// number of computers per creator
+        #[clap(long("creators"))]
+        creators: bool,
+    },
+
+    /// list accounts which are configured for Kerberos delegation
+    ///
+    /// This includes unconstrained delegation, constrained delegation (with
+    /// and without protocol transition) and resource-based constrained
+    /// delegation. Domain controllers are listed last.
+    Delegation {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+
+        /// hide domain controllers, which are expected to be trusted for
+        /// unconstrained delegation
+        #[clap(long("hide-dcs"))]
+        hide_domain_controllers: bool,
+    },
+
+    /// list user accounts which are vulnerable to Kerberoasting or AS-REP roasting
+    ///
+    /// Accounts are ranked by their privileges and the age of their passwords
+    Roastable {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+    },
+
+    /// list all key credentials (msDS-KeyCredentialLink)
+    ///
+    /// Key credentials of user and computer accounts which seem not to be
+    /// created by Windows Hello for Business are flagged as suspicious,
+    /// because they might be shadow credentials.
+    KeyCredentials {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+
+        /// include the public keys (in PEM format)
+        #[clap(long("export-keys"))]
+        export_keys: bool,
+    },
+
+    /// list all values of sIDHistory
+    ///
+    /// Historical SIDs which belong to the current domain or which have a
+    /// privileged RID are flagged as suspicious.
+    SidHistory {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+    },
+
+    /// list accounts with a non-default primary group
+    ///
+    /// Membership in the primary group is not stored in the member
+    /// attribute of the group. Accounts whose primary group is privileged
+    /// are listed first.
+    PrimaryGroups {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+    },
+
+    /// list all fine-grained password policies and the default domain policy
+    PasswordPolicies {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+    },
+
+    /// list group policy objects, or where they are linked
+    ///
+    /// With `--links`, the gPLink and gPOptions attributes of the domain,
+    /// sites and organizational units are listed, including the link order,
+    /// enforced and disabled links and blocked inheritance.
+    Gpo {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+
+        /// list the links of GPOs instead of the GPOs themselves
+        #[clap(long("links"))]
+        links: bool,
+    },
+
+    /// list the records of AD-integrated DNS zones
+    ///
+    /// Records of all dnsNode objects in the domain, DomainDnsZones and
+    /// ForestDnsZones partitions are shown, including tombstoned records.
+    /// Wildcard and WPAD records are listed first.
+    Dns {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+
+        /// instead of listing the records, write one zone file per zone
+        /// into this directory
+        #[clap(long("zone-files"))]
+        zone_files: Option<PathBuf>,
+    },
+
+    /// display sites, subnets, servers and the replication topology
+    ///
+    /// All objects are read from the Sites container of the configuration
+    /// partition. Using `--dot`, the replication connections between the
+    /// domain controllers are rendered as Graphviz graph.
+    Sites {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+
+        /// which kind of objects to display
+        #[clap(value_enum, long("show"), default_value_t = TopologyView::Connections)]
+        show: TopologyView,
+
+        /// render the replication graph in Graphviz DOT format
+        #[clap(long("dot"))]
+        dot: bool,
+    },
+
+    /// list certificate templates and flag ESC1 to ESC4 misconfigurations
+    ///
+    /// Templates which allow low-privileged principals to enroll with a
+    /// subject of their choice, to request certificates for any purpose or
+    /// on behalf of other users, or to modify the template are listed
+    /// first.
+    Adcs {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+
+        /// list the certification authorities instead of the templates
+        #[clap(long("cas"))]
+        cas: bool,
+    },
+
+    /// list the password replication policy of all read-only domain controllers
+    ///
+    /// For every RODC, this lists the accounts whose secrets are cached on
+    /// it (msDS-RevealedList), and the principals which are allowed
+    /// (msDS-RevealOnDemandGroup) or denied (msDS-NeverRevealGroup) to be
+    /// cached. Privileged accounts which are revealed or allowed are listed
+    /// first. After an RODC has been compromised, the credentials of all
+    /// revealed accounts must be reset.
+    Rodc {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+    },
+
+    /// list all deleted objects which have not yet been garbage collected
+    ///
+    /// This includes all objects in the Deleted Objects containers and all
+    /// objects whose name contains a DEL: marker. For every object, the
+    /// original distinguished name, the time of deletion, whether it can
+    /// still be restored and when it will be recycled or garbage collected
+    /// is displayed.
+    ///
+    /// Using --ldif, LDIF change records which reanimate the selected
+    /// objects and restore their group memberships are generated instead.
+    Deleted {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+
+        /// only consider objects whose name, sAMAccountName, original DN
+        /// or objectGUID matches this regular expression
+        #[clap(long("select"))]
+        select: Option<String>,
+
+        /// instead of listing the objects, write LDIF change records which
+        /// reanimate them into this file
+        #[clap(long("ldif"))]
+        ldif: Option<PathBuf>,
+    },
+
+    /// list objects which have been renamed due to replication conflicts
+    ///
+    /// If two objects with the same name are created on different domain
+    /// controllers at the same time, one of them is renamed, and a CNF:
+    /// marker is added to its name. For every such object, the object which
+    /// kept the original name and the domain controllers on which both
+    /// objects have been created are displayed.
+    Conflicts {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+    },
+
+    /// list objects with impossible or suspicious timestamps and USNs
+    ///
+    /// This cross-checks whenCreated, whenChanged, the record time, the
+    /// USNs, the replication metadata and the creation times of parent
+    /// objects, which can reveal offline modifications of the database
+    /// (e.g. using DSInternals) and timestomping.
+    Anomalies {
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+    },
+
+    /// compare this database with an older snapshot of it
+    ///
+    /// Objects are matched by their objectGUID. Added, deleted, restored,
+    /// moved and renamed objects, changed attribute values, changed group
+    /// memberships and changed security descriptors are listed. All changes
+    /// are displayed relative to the baseline (e.g. a known-good backup).
+    Diff {
+        /// path of the ntds.dit file which is used as baseline
+        baseline: String,
+
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+    },
+
+    /// list all objects which have been changed after some USN or point in time
+    ///
+    /// Deleted objects are included. For every object, the attributes which
+    /// have been changed since then are taken from the replication metadata.
+    /// If both --since-usn and --since are given, objects which match any of
+    /// them are listed.
+    Changes {
+        /// list objects whose uSNChanged is larger than this
+        #[clap(long("since-usn"), required_unless_present("since"))]
+        since_usn: Option<i64>,
+
+        /// list objects whose whenChanged is later than this (e.g.
+        /// 2024-05-01 or 2024-05-01T13:37:00+02:00)
+        #[clap(long("since"), value_parser = parse_timestamp)]
+        since: Option<DateTime<Utc>>,
+
+        /// Output format
+        #[clap(value_enum, short('F'), long("format"), default_value_t = OutputFormat::Csv)]
+        format: OutputFormat,
+    },
+
+    /// display a summary of the domain
+    ///
+    /// This includes the functional levels, the FSMO role holders, the
+    /// default password and lockout policy and the list of domain
+    /// controllers.
+    Domain {
+        #[clap(short('F'), long("format"), default_value_t = EntryFormat::Simple)]
+        entry_format: EntryFormat,
     },
 
     /// display one single entry from the directory information tree
@@ -208,6 +523,7 @@ impl Commands {
                 include_sd: _,
                 member_of_attribute: _,
             } => *include_dn,
+            Commands::Trusts { include_dn, .. } => *include_dn,
             _ => false,
         }
     }
@@ -235,6 +551,15 @@ impl Commands {
                 include_sd,
                 member_of_attribute: _,
             } => *include_sd,
+            Commands::Tree {
+                max_depth: _,
+                show_delegations,
+                show_gpo_links: _,
+            } => *show_delegations,
+            Commands::OuDelegation { .. } => true,
+            Commands::Diff { .. } => true,
+            Commands::Adcs { cas, .. } => !*cas,
+            Commands::Owners { creators, .. } => !*creators,
             _ => false,
         }
     }
@@ -278,6 +603,9 @@ impl Commands {
             } | Commands::Group {
                 format: OutputFormat::Csv,
                 ..
+            } | Commands::Trusts {
+                format: OutputFormat::Csv,
+                ..
             } | Commands::Timeline { .. }
         )
     }
@@ -288,6 +616,25 @@ impl Commands {
             Commands::Group { format, .. } => Some(*format),
             Commands::Computer { format, .. } => Some(*format),
             Commands::Types { format } => Some(*format),
+            Commands::Trusts { format, .. } => Some(*format),
+            Commands::OuDelegation { format } => Some(*format),
+            Commands::Owners { format, .. } => Some(*format),
+            Commands::Delegation { format, .. } => Some(*format),
+            Commands::Roastable { format } => Some(*format),
+            Commands::KeyCredentials { format, .. } => Some(*format),
+            Commands::SidHistory { format } => Some(*format),
+            Commands::PrimaryGroups { format } => Some(*format),
+            Commands::PasswordPolicies { format } => Some(*format),
+            Commands::Gpo { format, .. } => Some(*format),
+            Commands::Dns { format, .. } => Some(*format),
+            Commands::Sites { format, .. } => Some(*format),
+            Commands::Adcs { format, .. } => Some(*format),
+            Commands::Rodc { format } => Some(*format),
+            Commands::Deleted { format, .. } => Some(*format),
+            Commands::Conflicts { format } => Some(*format),
+            Commands::Anomalies { format } => Some(*format),
+            Commands::Diff { format, .. } => Some(*format),
+            Commands::Changes { format, .. } => Some(*format),
             _ => None,
         }
     }
@@ -302,4 +649,39 @@ pub enum TimelineFormat {
     /// flow record format (<https://docs.rs/flow-record>)
     #[strum(serialize = "record")]
     Record,
+
+    /// TLN format (Time|Source|Host|User|Description)
+    #[strum(serialize = "tln")]
+    Tln,
+
+    /// Plaso's l2tcsv format
+    #[strum(serialize = "l2tcsv")]
+    L2tcsv,
+
+    /// JSON lines, which can be imported into Timesketch
+    #[strum(serialize = "timesketch")]
+    Timesketch,
+}
+
+#[derive(ValueEnum, Clone, Copy, Display)]
+pub enum TopologyView {
+    /// sites, including their subnets and servers
+    #[strum(serialize = "sites")]
+    Sites,
+
+    /// subnets and the sites they are mapped to
+    #[strum(serialize = "subnets")]
+    Subnets,
+
+    /// servers and domain controllers
+    #[strum(serialize = "servers")]
+    Servers,
+
+    /// inbound replication connections of the domain controllers
+    #[strum(serialize = "connections")]
+    Connections,
+
+    /// links between sites, including their costs
+    #[strum(serialize = "site-links")]
+    SiteLinks,
 }
@@ -5,10 +5,12 @@ mod output_options;
 mod entry_format;
 pub mod output;
 mod member_of_attribute;
+mod timestamp_arg;
 
 pub use commands::*;
 pub use args::*;
 pub use output_format::*;
 pub use output_options::*;
 pub use entry_format::*;
-pub use member_of_attribute::*;
\ No newline at end of file
+pub use member_of_attribute::*;
+pub use timestamp_arg::*;
\ No newline at end of file
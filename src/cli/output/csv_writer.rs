@@ -1,3 +1,5 @@
+use serde::Serialize;
+
 use super::Writer;
 
 #[derive(Default)]
@@ -13,4 +15,17 @@ impl Writer for CsvWriter {
             names.map(|name| csv_wtr.serialize(name).map_err(|why| anyhow::anyhow!(why))),
         )
     }
+
+    fn write_records<I, R>(&self, records: I) -> anyhow::Result<()>
+    where
+        I: Iterator<Item = R>,
+        R: Serialize,
+    {
+        let mut csv_wtr = csv::Writer::from_writer(std::io::stdout());
+        for record in records {
+            csv_wtr.serialize(record)?;
+        }
+        csv_wtr.flush()?;
+        Ok(())
+    }
 }
@@ -1,3 +1,5 @@
+use serde::Serialize;
+
 use super::Writer;
 
 #[derive(Default)]
@@ -16,4 +18,15 @@ impl Writer for JsonLinesWriter {
                 })
         }))
     }
+
+    fn write_records<I, R>(&self, records: I) -> anyhow::Result<()>
+    where
+        I: Iterator<Item = R>,
+        R: Serialize,
+    {
+        for record in records {
+            println!("{}", serde_json::to_string(&record)?);
+        }
+        Ok(())
+    }
 }
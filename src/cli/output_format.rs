@@ -1,3 +1,4 @@
+use serde::Serialize;
 use strum::Display;
 
 use crate::cli::output::{CsvWriter, JsonLinesWriter, JsonWriter, Writer};
@@ -26,4 +27,16 @@ impl Writer for OutputFormat {
             OutputFormat::JsonLines => JsonLinesWriter.write_typenames(names),
         }
     }
+
+    fn write_records<I, R>(&self, records: I) -> anyhow::Result<()>
+    where
+        I: Iterator<Item = R>,
+        R: Serialize,
+    {
+        match self {
+            OutputFormat::Csv => CsvWriter.write_records(records),
+            OutputFormat::Json => JsonWriter.write_records(records),
+            OutputFormat::JsonLines => JsonLinesWriter.write_records(records),
+        }
+    }
 }
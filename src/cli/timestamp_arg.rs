@@ -0,0 +1,42 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+/// parses a timestamp which has been passed as command line argument. Both
+/// RFC 3339 timestamps and timestamps without timezone (which are
+/// interpreted as UTC) are accepted, e.g. `2024-05-01`,
+/// `2024-05-01T13:37:00` or `2024-05-01T13:37:00+02:00`
+pub fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        Ok(ts.with_timezone(&Utc))
+    } else if let Ok(ts) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S") {
+        Ok(ts.and_utc())
+    } else if let Ok(ts) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+        Ok(ts.and_utc())
+    } else if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc())
+    } else {
+        Err(format!("invalid timestamp: '{value}'"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_timestamp;
+
+    #[test]
+    fn test_parse_timestamp() {
+        let expected = "2024-05-01T13:37:00+00:00";
+        for value in [
+            "2024-05-01T13:37:00Z",
+            "2024-05-01T15:37:00+02:00",
+            "2024-05-01T13:37:00",
+            "2024-05-01 13:37:00",
+        ] {
+            assert_eq!(parse_timestamp(value).unwrap().to_rfc3339(), expected);
+        }
+        assert_eq!(
+            parse_timestamp("2024-05-01").unwrap().to_rfc3339(),
+            "2024-05-01T00:00:00+00:00"
+        );
+        assert!(parse_timestamp("yesterday").is_err());
+    }
+}
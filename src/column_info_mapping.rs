@@ -4,11 +4,16 @@ use crate::{
     column_information::ColumnInformation, esedb_mitigation::libesedb_count, ntds::NtdsAttributeId,
 };
 use anyhow::Result;
+use lazy_regex::regex_captures;
 use libesedb::Table;
 
 pub struct ColumnInfoMapping {
     mapping: HashMap<NtdsAttributeId, ColumnInformation>,
     str_mapping: HashMap<String, ColumnInformation>,
+
+    /// maps the numeric attribute id to the column. This is needed for
+    /// attributes which are not known in [`NtdsAttributeId`]
+    id_mapping: HashMap<i32, ColumnInformation>,
 }
 
 impl Index<NtdsAttributeId> for ColumnInfoMapping {
@@ -23,6 +28,10 @@ impl ColumnInfoMapping {
     pub fn info_by_name(&self, index: &str) -> Option<&ColumnInformation> {
         self.str_mapping.get(index)
     }
+
+    pub fn info_by_attribute_id(&self, attribute_id: i32) -> Option<&ColumnInformation> {
+        self.id_mapping.get(&attribute_id)
+    }
 }
 
 impl TryFrom<&Table<'_>> for ColumnInfoMapping {
@@ -30,6 +39,7 @@ impl TryFrom<&Table<'_>> for ColumnInfoMapping {
     fn try_from(data_table: &Table) -> Result<Self, Self::Error> {
         let mut mapping = HashMap::new();
         let mut str_mapping = HashMap::new();
+        let mut id_mapping = HashMap::new();
 
         for index in 0..libesedb_count(|| data_table.count_columns())? {
             let column = data_table.column(index)?;
@@ -38,12 +48,20 @@ impl TryFrom<&Table<'_>> for ColumnInfoMapping {
                 mapping.insert(column_id, col_info);
             }
 
-            str_mapping.insert(column.name()?.to_string(), col_info);
+            let column_name = column.name()?;
+            if let Some((_, attribute_id)) = regex_captures!(r#"^ATT[a-z](\d+)$"#, &column_name) {
+                if let Ok(attribute_id) = attribute_id.parse() {
+                    id_mapping.insert(attribute_id, col_info);
+                }
+            }
+
+            str_mapping.insert(column_name.to_string(), col_info);
         }
 
         Ok(Self {
             mapping,
             str_mapping,
+            id_mapping,
         })
     }
 }
@@ -4,6 +4,7 @@ use anyhow::Result;
 use clap::Parser;
 use libesedb::EseDb;
 use libntdsextract2::cli::{Args, Commands, OutputOptions};
+use libntdsextract2::ntds::TimelineFilter;
 use libntdsextract2::{use_member_of_attribute, CDatabase, CsvSerialization, EntryId, EsedbInfo, JsonSerialization};
 use simplelog::{Config, TermLogger};
 
@@ -64,18 +65,65 @@ fn main() -> Result<()> {
         Commands::Computer { .. } => {
             do_with_serialization!(cli.command(), database, show_computers, &options)
         }
+        Commands::Trusts { .. } => {
+            do_with_serialization!(cli.command(), database, show_trusts, &options)
+        }
         Commands::Types { .. } => {
             do_with_serialization!(cli.command(), database, show_type_names, &options)
         }
         Commands::Timeline {
             all_objects,
             include_deleted,
-            format
+            format,
+            from,
+            to,
+            object_types,
+            event,
         } => {
             options.set_show_all_objects(*all_objects);
-            database.show_timeline(&options, *include_deleted, format)
+            let filter = TimelineFilter::new(*from, *to, object_types.clone(), event.as_deref())?;
+            database.show_timeline(&options, *include_deleted, format, &filter)
+        }
+        Commands::Tree {
+            max_depth,
+            show_delegations,
+            show_gpo_links,
+        } => database.show_tree(*max_depth, *show_delegations, *show_gpo_links),
+        Commands::OuDelegation { .. } => database.show_ou_delegations(&options),
+        Commands::Owners { creators, .. } => database.show_owners(&options, *creators),
+        Commands::Delegation {
+            hide_domain_controllers,
+            ..
+        } => database.show_kerberos_delegations(&options, *hide_domain_controllers),
+        Commands::Roastable { .. } => database.show_roastable_accounts(&options),
+        Commands::KeyCredentials { export_keys, .. } => {
+            database.show_shadow_credentials(&options, *export_keys)
+        }
+        Commands::SidHistory { .. } => database.show_sid_history(&options),
+        Commands::PrimaryGroups { .. } => database.show_primary_groups(&options),
+        Commands::PasswordPolicies { .. } => database.show_password_policies(&options),
+        Commands::Gpo { links, .. } => database.show_gpos(&options, *links),
+        Commands::Dns { zone_files, .. } => {
+            database.show_dns_records(&options, zone_files.as_deref())
+        }
+        Commands::Sites { show, dot, .. } => database.show_sites(&options, *show, *dot),
+        Commands::Adcs { cas, .. } => database.show_certificate_services(&options, *cas),
+        Commands::Rodc { .. } => database.show_rodc_password_policy(&options),
+        Commands::Deleted { select, ldif, .. } => {
+            database.show_deleted_objects(&options, select.as_deref(), ldif.as_deref())
+        }
+        Commands::Conflicts { .. } => database.show_replication_conflicts(&options),
+        Commands::Anomalies { .. } => database.show_anomalies(&options),
+        Commands::Diff { baseline, .. } => {
+            let baseline_esedb = EseDb::open(baseline)?;
+            let baseline_info = EsedbInfo::try_from(&baseline_esedb)?;
+            let baseline_database = CDatabase::new(&baseline_info, true)?;
+            database.show_diff(&baseline_database, &options)
         }
-        Commands::Tree { max_depth } => Ok(database.show_tree(*max_depth)?),
+        Commands::Changes {
+            since_usn, since, ..
+        } => database.show_changes(&options, *since_usn, *since),
+        Commands::Domain { entry_format } => database.show_domain_info(*entry_format),
         Commands::Entry {
             entry_id,
             use_sid,
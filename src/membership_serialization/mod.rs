@@ -8,4 +8,6 @@ mod csv_serialization;
 pub use csv_serialization::*;
 
 mod json_serialization;
-pub use json_serialization::*;
\ No newline at end of file
+pub use json_serialization::*;
+mod string_list;
+pub use string_list::*;
@@ -0,0 +1,145 @@
+use std::marker::PhantomData;
+
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+
+use crate::SerializationType;
+
+/// list of values of a multi-valued string attribute, which is serialized
+/// as a comma separated string (for CSV) or as array (for JSON)
+pub struct StringList<T: SerializationType>(Vec<String>, PhantomData<T>);
+
+impl<T> StringList<T>
+where
+    T: SerializationType,
+{
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<T> From<Vec<String>> for StringList<T>
+where
+    T: SerializationType,
+{
+    fn from(values: Vec<String>) -> Self {
+        Self(values, PhantomData)
+    }
+}
+
+impl<T> Serialize for StringList<T>
+where
+    T: SerializationType,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        T::serialize_list(self.0.iter().cloned().map(Some), serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for StringList<T>
+where
+    T: SerializationType,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(StringListVisitor(PhantomData))
+    }
+}
+
+struct StringListVisitor<T: SerializationType>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for StringListVisitor<T>
+where
+    T: SerializationType,
+{
+    type Value = StringList<T>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a comma separated string or a list of strings")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(StringList::from(
+            v.split(',')
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(StringList::from(Vec::new()))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = Vec::new();
+        while let Some(value) = seq.next_element::<String>()? {
+            values.push(value);
+        }
+        Ok(StringList::from(values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{CsvSerialization, JsonSerialization, SerializationType};
+
+    use super::StringList;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(bound = "T: SerializationType")]
+    struct SampleRecord<T: SerializationType> {
+        data: StringList<T>,
+    }
+
+    fn test_data<T: SerializationType>() -> SampleRecord<T> {
+        SampleRecord {
+            data: StringList::from(vec!["a/b".to_owned(), "c/d".to_owned()]),
+        }
+    }
+
+    #[test]
+    fn test_csv_roundtrip() {
+        let mut wtr = csv::Writer::from_writer(vec![]);
+        wtr.serialize(test_data::<CsvSerialization>()).unwrap();
+        let result = String::from_utf8(wtr.into_inner().unwrap()).unwrap();
+        assert_eq!(result, "data\n\"a/b,c/d\"\n");
+
+        let mut rdr = csv::Reader::from_reader(result.as_bytes());
+        let record: SampleRecord<CsvSerialization> = rdr.deserialize().next().unwrap().unwrap();
+        assert_eq!(record.data.len(), 2);
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let result = serde_json::to_string(&test_data::<JsonSerialization>()).unwrap();
+        assert_eq!(result, r#"{"data":["a/b","c/d"]}"#);
+
+        let record: SampleRecord<JsonSerialization> = serde_json::from_str(&result).unwrap();
+        assert_eq!(record.data.iter().last().unwrap(), "c/d");
+    }
+}
@@ -0,0 +1,432 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use getset::Getters;
+use serde::Serialize;
+use strum::Display;
+
+use crate::cache::{RecordId, RecordPointer};
+use crate::win32_types::{
+    ReplicationMetaData, TruncatedWindowsFileTime, UnixTimestamp, TIMESTAMP_FORMAT,
+};
+
+use super::{DataTable, NtdsAttributeId};
+
+/// differences between timestamps which are smaller than this are ignored
+const TOLERANCE_SECONDS: i64 = 60;
+
+#[derive(Display, Serialize, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+pub enum AnomalyCheck {
+    /// `whenChanged` is older than `whenCreated`
+    #[strum(serialize = "changed-before-created")]
+    #[serde(rename = "changed-before-created")]
+    ChangedBeforeCreated,
+
+    /// `whenCreated` or `whenChanged` is newer than the time when the
+    /// record has last been written (`DsRecordTime`)
+    #[strum(serialize = "record-time")]
+    #[serde(rename = "record-time")]
+    RecordTime,
+
+    /// `uSNChanged` is smaller than `uSNCreated`
+    #[strum(serialize = "usn-order")]
+    #[serde(rename = "usn-order")]
+    UsnOrder,
+
+    /// the creation time does not fit to the creation times of the objects
+    /// which have been created before and after this object on the same
+    /// domain controller
+    #[strum(serialize = "usn-sequence")]
+    #[serde(rename = "usn-sequence")]
+    UsnSequence,
+
+    /// the replication metadata contradicts `whenCreated` or `whenChanged`
+    #[strum(serialize = "metadata-time")]
+    #[serde(rename = "metadata-time")]
+    MetadataTime,
+
+    /// `uSNChanged` is higher than the local USN of every change which has
+    /// been recorded in the replication metadata
+    #[strum(serialize = "unrecorded-change")]
+    #[serde(rename = "unrecorded-change")]
+    UnrecordedChange,
+
+    /// the object has been created before its parent, but never been moved
+    #[strum(serialize = "created-before-parent")]
+    #[serde(rename = "created-before-parent")]
+    CreatedBeforeParent,
+}
+
+/// an object whose timestamps, USNs or replication metadata are impossible
+/// or suspicious, which can be caused by offline modifications of the
+/// database
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct Anomaly {
+    check: AnomalyCheck,
+    name: String,
+    object_class: Option<String>,
+    distinguished_name: Option<String>,
+    when_created: Option<TruncatedWindowsFileTime>,
+    when_changed: Option<TruncatedWindowsFileTime>,
+    details: String,
+}
+
+/// an object which has been created on the domain controller this database
+/// belongs to
+struct LocalCreation {
+    usn_created: i64,
+    when_created: i64,
+    ptr: RecordPointer,
+}
+
+impl Anomaly {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let metadata = data_table.data_table().metadata();
+
+        // changes of linked values are not stored in `replPropertyMetaData`
+        let link_holders: HashSet<RecordId> = data_table
+            .link_table()
+            .all_links()
+            .map(|link| *link.link_dnt())
+            .collect();
+
+        let mut creation_times = HashMap::new();
+        let mut local_creations = Vec::new();
+        let mut anomalies = Vec::new();
+
+        for entry in metadata.iter() {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+            let when_created = record.att_when_created_opt()?;
+            let when_changed = record.att_when_changed_opt()?;
+            let record_time = record.ds_record_time_opt()?;
+            let usn_created = record.att_usn_created_opt()?;
+            let usn_changed = record.att_usn_changed_opt()?;
+            let repl_metadata = match record.att_repl_property_meta_data_opt() {
+                Ok(repl_metadata) => repl_metadata,
+                Err(why) => {
+                    log::warn!("unable to parse the replication metadata of '{entry}': {why}");
+                    None
+                }
+            };
+
+            let mut findings = Vec::new();
+            let created = when_created.as_ref().map(UnixTimestamp::timestamp);
+            let changed = when_changed.as_ref().map(UnixTimestamp::timestamp);
+
+            if let (Some(created), Some(changed)) = (created, changed) {
+                if changed + TOLERANCE_SECONDS < created {
+                    findings.push((
+                        AnomalyCheck::ChangedBeforeCreated,
+                        format!(
+                            "whenChanged is {} seconds older than whenCreated",
+                            created - changed
+                        ),
+                    ));
+                }
+            }
+
+            if let Some(record_time) = &record_time {
+                for (attribute, value) in [
+                    ("whenCreated", &when_created),
+                    ("whenChanged", &when_changed),
+                ] {
+                    if let Some(value) = value {
+                        if value.timestamp() > record_time.timestamp() + TOLERANCE_SECONDS {
+                            findings.push((
+                                AnomalyCheck::RecordTime,
+                                format!(
+                                    "{attribute} is newer than the record time ({})",
+                                    format_timestamp(record_time)
+                                ),
+                            ));
+                        }
+                    }
+                }
+            }
+
+            if let (Some(usn_created), Some(usn_changed)) = (usn_created, usn_changed) {
+                if usn_changed < usn_created {
+                    findings.push((
+                        AnomalyCheck::UsnOrder,
+                        format!(
+                            "uSNChanged ({usn_changed}) is smaller than uSNCreated ({usn_created})"
+                        ),
+                    ));
+                }
+            }
+
+            if let (Some(repl_metadata), Some(created), Some(changed)) =
+                (&repl_metadata, created, changed)
+            {
+                for m in repl_metadata.entries() {
+                    let attribute = i32::from_ne_bytes(m.attribute_id().to_ne_bytes());
+                    let attribute = metadata
+                        .attribute_name_of(attribute)
+                        .map(str::to_string)
+                        .unwrap_or_else(|| format!("{:#x}", m.attribute_id()));
+                    let time_changed = m.time_changed().timestamp();
+
+                    if time_changed + TOLERANCE_SECONDS < created {
+                        findings.push((
+                            AnomalyCheck::MetadataTime,
+                            format!(
+                                "{attribute} has been changed at {}, before the object has been created",
+                                format_timestamp(m.time_changed())
+                            ),
+                        ));
+                    } else if time_changed > changed + TOLERANCE_SECONDS {
+                        findings.push((
+                            AnomalyCheck::MetadataTime,
+                            format!(
+                                "{attribute} has been changed at {}, after whenChanged",
+                                format_timestamp(m.time_changed())
+                            ),
+                        ));
+                    }
+                }
+
+                if let Some(m) = repl_metadata.of(NtdsAttributeId::AttWhenCreated as u32) {
+                    if (m.time_changed().timestamp() - created).abs() > TOLERANCE_SECONDS {
+                        findings.push((
+                            AnomalyCheck::MetadataTime,
+                            format!(
+                                "whenCreated differs from its originating time ({})",
+                                format_timestamp(m.time_changed())
+                            ),
+                        ));
+                    }
+                    if m.originating_usn() == m.local_usn() {
+                        local_creations.push(LocalCreation {
+                            usn_created: *m.local_usn(),
+                            when_created: created,
+                            ptr: *entry.record_ptr(),
+                        });
+                    }
+                }
+            }
+
+            // changes of replicated attributes update `uSNChanged` and the
+            // local USN in the replication metadata at the same time
+            if let (Some(repl_metadata), Some(usn_changed)) = (&repl_metadata, usn_changed) {
+                if !link_holders.contains(entry.record_ptr().ds_record_id()) {
+                    if let Some(details) = unrecorded_change(usn_changed, repl_metadata) {
+                        findings.push((AnomalyCheck::UnrecordedChange, details));
+                    }
+                }
+            }
+
+            if let Some(created) = created {
+                // objects which have been moved or renamed are allowed to be
+                // older than their parent
+                let never_moved = repl_metadata
+                    .as_ref()
+                    .and_then(|m| m.of(NtdsAttributeId::AttRdn as u32))
+                    .map_or(true, |m| *m.version() == 1);
+                creation_times.insert(*entry.record_ptr().ds_record_id(), (created, never_moved));
+            }
+
+            for (check, details) in findings {
+                anomalies.push(Self {
+                    check,
+                    name: entry.rdn().name().to_string(),
+                    object_class: metadata.object_class_name(entry).map(str::to_string),
+                    distinguished_name: data_table.object_tree().dn_of(entry.record_ptr()),
+                    when_created: when_created.clone(),
+                    when_changed: when_changed.clone(),
+                    details,
+                });
+            }
+        }
+
+        for entry in metadata.iter() {
+            let (created, never_moved) = match creation_times.get(entry.record_ptr().ds_record_id())
+            {
+                Some(times) => *times,
+                None => continue,
+            };
+            if let Some((parent_created, _)) = creation_times.get(entry.parent()) {
+                if never_moved && created + TOLERANCE_SECONDS < *parent_created {
+                    anomalies.push(Self::from_entry(
+                        data_table,
+                        entry.record_ptr(),
+                        AnomalyCheck::CreatedBeforeParent,
+                        format!(
+                            "the object has been created {} seconds before its parent",
+                            parent_created - created
+                        ),
+                    )?);
+                }
+            }
+        }
+
+        // on a single domain controller, objects with a higher USN must have
+        // been created later. Objects which are older than both of their
+        // neighbours (or newer than both of them) are suspicious
+        local_creations.sort_by_key(|c| c.usn_created);
+        for (creation, details) in usn_sequence_outliers(&local_creations) {
+            anomalies.push(Self::from_entry(
+                data_table,
+                &creation.ptr,
+                AnomalyCheck::UsnSequence,
+                format!("{details} (uSNCreated: {})", creation.usn_created),
+            )?);
+        }
+
+        anomalies.sort_by(|lhs, rhs| {
+            (lhs.check, &lhs.distinguished_name).cmp(&(rhs.check, &rhs.distinguished_name))
+        });
+        Ok(anomalies)
+    }
+
+    fn from_entry(
+        data_table: &DataTable,
+        ptr: &RecordPointer,
+        check: AnomalyCheck,
+        details: String,
+    ) -> crate::ntds::Result<Self> {
+        let metadata = data_table.data_table().metadata();
+        let record = data_table.data_table().data_table_record_from(*ptr)?;
+        let entry = &metadata[ptr];
+        Ok(Self {
+            check,
+            name: entry.rdn().name().to_string(),
+            object_class: metadata.object_class_name(entry).map(str::to_string),
+            distinguished_name: data_table.object_tree().dn_of(ptr),
+            when_created: record.att_when_created_opt()?,
+            when_changed: record.att_when_changed_opt()?,
+            details,
+        })
+    }
+}
+
+fn format_timestamp(timestamp: &TruncatedWindowsFileTime) -> String {
+    DateTime::<Utc>::from(timestamp.clone())
+        .format(&TIMESTAMP_FORMAT)
+        .to_string()
+}
+
+/// returns a description of the anomaly if `uSNChanged` is higher than the
+/// local USNs in the replication metadata
+fn unrecorded_change(usn_changed: i64, repl_metadata: &ReplicationMetaData) -> Option<String> {
+    let last_usn = repl_metadata
+        .entries()
+        .iter()
+        .map(|m| *m.local_usn())
+        .max()?;
+    if usn_changed > last_usn {
+        Some(format!(
+            "uSNChanged ({usn_changed}) is higher than the local USN of the last recorded change ({last_usn})"
+        ))
+    } else {
+        None
+    }
+}
+
+/// returns the objects, ordered by their `uSNCreated`, which are older or
+/// newer than both of their neighbours
+fn usn_sequence_outliers(creations: &[LocalCreation]) -> Vec<(&LocalCreation, &'static str)> {
+    creations
+        .windows(3)
+        .filter_map(|window| {
+            let (previous, current, next) = (&window[0], &window[1], &window[2]);
+            if current.when_created + TOLERANCE_SECONDS < previous.when_created
+                && current.when_created + TOLERANCE_SECONDS < next.when_created
+            {
+                Some((
+                    current,
+                    "the object is older than the objects with lower and higher USNs",
+                ))
+            } else if current.when_created > previous.when_created + TOLERANCE_SECONDS
+                && current.when_created > next.when_created + TOLERANCE_SECONDS
+            {
+                Some((
+                    current,
+                    "the object is newer than the objects with lower and higher USNs",
+                ))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::RecordPointer;
+    use crate::win32_types::ReplicationMetaData;
+
+    use super::{unrecorded_change, usn_sequence_outliers, LocalCreation};
+
+    /// creates replication metadata with one entry per local USN
+    fn repl_metadata(local_usns: &[i64]) -> ReplicationMetaData {
+        let mut value = Vec::new();
+        for v in [1u32, 0, local_usns.len() as u32, 0] {
+            value.extend_from_slice(&v.to_le_bytes());
+        }
+        for (idx, usn) in local_usns.iter().enumerate() {
+            value.extend_from_slice(&(idx as u32).to_le_bytes());
+            value.extend_from_slice(&1u32.to_le_bytes());
+            value.extend_from_slice(&13390472401u64.to_le_bytes());
+            value.extend_from_slice(&[0; 16]);
+            value.extend_from_slice(&usn.to_le_bytes());
+            value.extend_from_slice(&usn.to_le_bytes());
+        }
+        ReplicationMetaData::try_from(&value[..]).unwrap()
+    }
+
+    fn creation(usn_created: i64, when_created: i64) -> LocalCreation {
+        LocalCreation {
+            usn_created,
+            when_created,
+            ptr: RecordPointer::default(),
+        }
+    }
+
+    #[test]
+    fn test_unrecorded_change() {
+        let metadata = repl_metadata(&[4711, 4800, 4750]);
+        assert!(unrecorded_change(4800, &metadata).is_none());
+        assert_eq!(
+            unrecorded_change(4801, &metadata).unwrap(),
+            "uSNChanged (4801) is higher than the local USN of the last recorded change (4800)"
+        );
+        assert!(unrecorded_change(4801, &repl_metadata(&[])).is_none());
+    }
+
+    #[test]
+    fn test_usn_sequence_outliers() {
+        let creations = [
+            creation(100, 1_000),
+            creation(101, 600),
+            creation(102, 100),
+            creation(103, 3_000),
+            creation(104, 9_000),
+            creation(105, 4_000),
+            creation(106, 4_030),
+            creation(107, 4_010),
+        ];
+        let outliers: Vec<_> = usn_sequence_outliers(&creations)
+            .into_iter()
+            .map(|(c, details)| (c.usn_created, details))
+            .collect();
+        assert_eq!(
+            outliers,
+            [
+                (
+                    102,
+                    "the object is older than the objects with lower and higher USNs"
+                ),
+                (
+                    104,
+                    "the object is newer than the objects with lower and higher USNs"
+                ),
+            ]
+        );
+
+        // there are no neighbours to compare with
+        assert!(usn_sequence_outliers(&creations[..2]).is_empty());
+    }
+}
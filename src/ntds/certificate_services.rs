@@ -0,0 +1,637 @@
+use std::collections::{BTreeSet, HashMap};
+use std::rc::Rc;
+use std::str::FromStr;
+
+use getset::Getters;
+use lazy_static::lazy_static;
+use sddl::AccessMask;
+use serde::Serialize;
+
+use crate::object_tree_entry::ObjectTreeEntry;
+use crate::win32_types::{
+    allows_client_authentication, eku_name, AccessControlEntry, AccessControlType,
+    CertificateNameFlags, EnrollmentFlags, Guid, Sid, TruncatedWindowsFileTime, EKU_ANY_PURPOSE,
+    EKU_CERTIFICATE_REQUEST_AGENT,
+};
+
+use super::domain_info::find_by_path;
+use super::{DataTable, DataTableRecord};
+
+/// domain RIDs of groups which contain (nearly) all users or computers
+const LOW_PRIVILEGED_DOMAIN_RIDS: [u32; 4] = [513, 514, 515, 501];
+
+/// builtin RIDs (`S-1-5-32-*`) of groups which contain (nearly) all users
+const LOW_PRIVILEGED_BUILTIN_RIDS: [u32; 2] = [545, 546];
+
+lazy_static! {
+    /// extended right `Certificate-Enrollment`
+    static ref CERTIFICATE_ENROLLMENT: Guid =
+        Guid::from_str("0e10c968-78fb-11d2-90d4-00c04f79dc55").unwrap();
+
+    /// extended right `Certificate-AutoEnrollment`
+    static ref CERTIFICATE_AUTO_ENROLLMENT: Guid =
+        Guid::from_str("a05b8cc2-17bc-4802-a710-e7c15ab866a2").unwrap();
+}
+
+/// a certificate template (`pKICertificateTemplate`)
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct CertificateTemplate {
+    name: String,
+    display_name: Option<String>,
+    distinguished_name: String,
+    schema_version: Option<i32>,
+    template_oid: Option<String>,
+
+    /// names of the enrollment services which publish this template
+    published_by: String,
+    name_flags: String,
+    enrollment_flags: String,
+    extended_key_usages: String,
+    requires_manager_approval: bool,
+    authorized_signatures: i32,
+    enrollment_rights: String,
+    write_rights: String,
+
+    /// misconfigurations which allow low-privileged principals to obtain a
+    /// certificate for another principal
+    vulnerabilities: String,
+    when_created: Option<TruncatedWindowsFileTime>,
+    when_changed: Option<TruncatedWindowsFileTime>,
+}
+
+/// a certification authority, which is stored below `CN=Public Key Services`
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct CertificationAuthority {
+    name: String,
+    kind: &'static str,
+    distinguished_name: String,
+    dns_host_name: Option<String>,
+    certificate_templates: String,
+    certificates: usize,
+    when_created: Option<TruncatedWindowsFileTime>,
+    when_changed: Option<TruncatedWindowsFileTime>,
+}
+
+/// principals which have been granted some rights on a template
+#[derive(Default)]
+struct TemplatePermissions {
+    enrollees: BTreeSet<String>,
+    writers: BTreeSet<String>,
+    low_privileged_enrollment: bool,
+    low_privileged_write: bool,
+}
+
+fn public_key_services(data_table: &DataTable) -> Option<Rc<ObjectTreeEntry>> {
+    find_by_path(
+        data_table.special_records().configuration(),
+        &["Services", "Public Key Services"],
+    )
+}
+
+fn sorted_children(entry: &ObjectTreeEntry) -> Vec<Rc<ObjectTreeEntry>> {
+    let mut children: Vec<_> = entry
+        .children()
+        .borrow()
+        .iter()
+        .filter(|c| c.name().deleted_from_container().is_none())
+        .cloned()
+        .collect();
+    children.sort_by(|lhs, rhs| lhs.name().name().cmp(rhs.name().name()));
+    children
+}
+
+impl CertificateTemplate {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let pks = match public_key_services(data_table) {
+            Some(pks) => pks,
+            None => {
+                log::error!("the configuration partition has no 'Public Key Services' container");
+                return Ok(Vec::new());
+            }
+        };
+
+        // maps the names of templates to the enrollment services which publish them
+        let mut published_by: HashMap<String, Vec<String>> = HashMap::new();
+        if let Some(enrollment_services) = pks.find_child_by_name("Enrollment Services") {
+            for ca in sorted_children(&enrollment_services) {
+                let record = data_table
+                    .data_table()
+                    .data_table_record_from(*ca.record_ptr())?;
+                for template in record.att_certificate_templates_opt()?.unwrap_or_default() {
+                    published_by
+                        .entry(template.to_lowercase())
+                        .or_default()
+                        .push(ca.name().name().to_string());
+                }
+            }
+        }
+
+        let templates = match pks.find_child_by_name("Certificate Templates") {
+            Some(templates) => templates,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut result = Vec::new();
+        for template in sorted_children(&templates) {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*template.record_ptr())?;
+            result.push(Self::from_record(
+                data_table,
+                &template,
+                &record,
+                published_by.get(&template.name().name().to_lowercase()),
+            )?);
+        }
+
+        result.sort_by_key(|t| (t.vulnerabilities.is_empty(), t.published_by.is_empty()));
+        Ok(result)
+    }
+
+    fn from_record(
+        data_table: &DataTable,
+        template: &ObjectTreeEntry,
+        record: &DataTableRecord,
+        published_by: Option<&Vec<String>>,
+    ) -> crate::ntds::Result<Self> {
+        let name_flags = record
+            .att_pki_certificate_name_flag_opt()?
+            .unwrap_or(CertificateNameFlags::empty());
+        let enrollment_flags = record
+            .att_pki_enrollment_flag_opt()?
+            .unwrap_or(EnrollmentFlags::empty());
+        let authorized_signatures = record.att_pki_ra_signature_opt()?.unwrap_or(0);
+
+        // the application policies take precedence over the extended key
+        // usages in templates of schema version 2 and later
+        let ekus = match record.att_pki_certificate_application_policy_opt()? {
+            Some(policies) if !policies.is_empty() => policies,
+            _ => record.att_pki_extended_key_usage_opt()?.unwrap_or_default(),
+        };
+
+        let permissions = Self::permissions_of(data_table, template);
+        let requires_manager_approval =
+            enrollment_flags.contains(EnrollmentFlags::CT_FLAG_PEND_ALL_REQUESTS);
+        let vulnerabilities = Self::vulnerabilities_of(
+            &permissions,
+            name_flags,
+            enrollment_flags,
+            authorized_signatures,
+            &ekus,
+        );
+
+        Ok(Self {
+            name: template.name().name().to_string(),
+            display_name: record.att_display_name_opt()?,
+            distinguished_name: template.distinguished_name().clone(),
+            schema_version: record.att_pki_template_schema_version_opt()?,
+            template_oid: record.att_pki_cert_template_oid_opt()?,
+            published_by: published_by.map(|p| p.join(", ")).unwrap_or_default(),
+            name_flags: name_flags
+                .iter_names()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+                .join("|"),
+            enrollment_flags: enrollment_flags
+                .iter_names()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+                .join("|"),
+            extended_key_usages: ekus
+                .iter()
+                .map(|eku| eku_name(eku))
+                .collect::<Vec<_>>()
+                .join(", "),
+            requires_manager_approval,
+            authorized_signatures,
+            enrollment_rights: Vec::from_iter(permissions.enrollees).join(", "),
+            write_rights: Vec::from_iter(permissions.writers).join(", "),
+            vulnerabilities: vulnerabilities.join(", "),
+            when_created: record.att_when_created_opt()?,
+            when_changed: record.att_when_changed_opt()?,
+        })
+    }
+
+    /// checks the template for the misconfigurations ESC1 to ESC4
+    fn vulnerabilities_of(
+        permissions: &TemplatePermissions,
+        name_flags: CertificateNameFlags,
+        enrollment_flags: EnrollmentFlags,
+        authorized_signatures: i32,
+        ekus: &[String],
+    ) -> Vec<&'static str> {
+        let enrollable = permissions.low_privileged_enrollment
+            && !enrollment_flags.contains(EnrollmentFlags::CT_FLAG_PEND_ALL_REQUESTS)
+            && authorized_signatures == 0;
+
+        let mut vulnerabilities = Vec::new();
+        if enrollable
+            && name_flags.contains(CertificateNameFlags::CT_FLAG_ENROLLEE_SUPPLIES_SUBJECT)
+            && allows_client_authentication(ekus)
+        {
+            vulnerabilities.push("ESC1");
+        }
+        if enrollable && (ekus.is_empty() || ekus.iter().any(|e| e == EKU_ANY_PURPOSE)) {
+            vulnerabilities.push("ESC2");
+        }
+        if enrollable && ekus.iter().any(|e| e == EKU_CERTIFICATE_REQUEST_AGENT) {
+            vulnerabilities.push("ESC3");
+        }
+        if permissions.low_privileged_write {
+            vulnerabilities.push("ESC4");
+        }
+        vulnerabilities
+    }
+
+    /// reads the principals which are allowed to enroll or to modify the
+    /// template from its security descriptor. Deny ACEs are not considered.
+    fn permissions_of(data_table: &DataTable, template: &ObjectTreeEntry) -> TemplatePermissions {
+        let mut permissions = TemplatePermissions::default();
+        let metadata = data_table.data_table().metadata();
+        let sd = match (
+            data_table.sd_table(),
+            metadata
+                .record(template.record_ptr().ds_record_id())
+                .and_then(|e| *e.sd_id()),
+        ) {
+            (Some(sd_table), Some(sd_id)) => match sd_table.descriptor(&sd_id) {
+                Some(Ok(sd)) => sd,
+                Some(Err(why)) => {
+                    log::warn!("unable to read the security descriptor of {template}: {why}");
+                    return permissions;
+                }
+                None => return permissions,
+            },
+            _ => return permissions,
+        };
+
+        for ace in sd.dacl_entries() {
+            if *ace.access_type() != AccessControlType::Allow || ace.is_inherit_only() {
+                continue;
+            }
+            let trustee = data_table
+                .principal_name(ace.trustee())
+                .or(ace.well_known_name().map(String::from))
+                .unwrap_or_else(|| ace.trustee().to_string());
+            let is_low_privileged = Self::is_low_privileged(ace.trustee());
+
+            if Self::allows_enrollment(&ace) {
+                permissions.low_privileged_enrollment |= is_low_privileged;
+                permissions.enrollees.insert(trustee.clone());
+            }
+            if Self::allows_modification(&ace) {
+                permissions.low_privileged_write |= is_low_privileged;
+                permissions.writers.insert(trustee);
+            }
+        }
+        permissions
+    }
+
+    fn allows_enrollment(ace: &AccessControlEntry) -> bool {
+        ace.mask().contains(AccessMask::GENERIC_ALL)
+            || (ace.mask().contains(AccessMask::CONTROL_ACCESS)
+                && ace.object_type().as_ref().map_or(true, |guid| {
+                    *guid == *CERTIFICATE_ENROLLMENT || *guid == *CERTIFICATE_AUTO_ENROLLMENT
+                }))
+    }
+
+    fn allows_modification(ace: &AccessControlEntry) -> bool {
+        ace.mask().intersects(
+            AccessMask::GENERIC_ALL
+                | AccessMask::GENERIC_WRITE
+                | AccessMask::WRITE_DACL
+                | AccessMask::WRITE_OWNER,
+        ) || (ace.mask().contains(AccessMask::WRITE_PROPERTY) && ace.object_type().is_none())
+    }
+
+    fn is_low_privileged(sid: &Sid) -> bool {
+        match (sid.authority(), sid.sub_authorities()) {
+            // Everyone
+            (1, [0]) => true,
+
+            // Anonymous Logon, Authenticated Users
+            (5, [7 | 11]) => true,
+
+            (5, [32, rid]) => LOW_PRIVILEGED_BUILTIN_RIDS.contains(rid),
+            (5, [21, _, _, _, rid]) => LOW_PRIVILEGED_DOMAIN_RIDS.contains(rid),
+            _ => false,
+        }
+    }
+}
+
+impl CertificationAuthority {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let pks = match public_key_services(data_table) {
+            Some(pks) => pks,
+            None => {
+                log::error!("the configuration partition has no 'Public Key Services' container");
+                return Ok(Vec::new());
+            }
+        };
+
+        let mut result = Vec::new();
+        for (container, kind) in [
+            ("Enrollment Services", "enrollment service"),
+            ("Certification Authorities", "root CA"),
+            ("AIA", "intermediate CA"),
+        ] {
+            if let Some(container) = pks.find_child_by_name(container) {
+                for ca in sorted_children(&container) {
+                    result.push(Self::from_entry(data_table, &ca, kind)?);
+                }
+            }
+        }
+
+        // CAs whose certificates are stored in NTAuthCertificates can issue
+        // certificates for smart card logon
+        if let Some(nt_auth) = pks.find_child_by_name("NTAuthCertificates") {
+            result.push(Self::from_entry(data_table, &nt_auth, "NTAuth store")?);
+        }
+        Ok(result)
+    }
+
+    fn from_entry(
+        data_table: &DataTable,
+        entry: &ObjectTreeEntry,
+        kind: &'static str,
+    ) -> crate::ntds::Result<Self> {
+        let record = data_table
+            .data_table()
+            .data_table_record_from(*entry.record_ptr())?;
+        let mut templates = record.att_certificate_templates_opt()?.unwrap_or_default();
+        templates.sort();
+
+        Ok(Self {
+            name: entry.name().name().to_string(),
+            kind,
+            distinguished_name: entry.distinguished_name().clone(),
+            dns_host_name: record.att_dns_host_name_opt()?,
+            certificate_templates: templates.join(", "),
+            certificates: record.att_ca_certificate_opt()?.map_or(0, |c| c.len()),
+            when_created: record.att_when_created_opt()?,
+            when_changed: record.att_when_changed_opt()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sddl::{AccessMask, Ace, AceHeaderFlags};
+
+    use crate::win32_types::{
+        AccessControlEntry, CertificateNameFlags, EnrollmentFlags, Sid,
+        EKU_CERTIFICATE_REQUEST_AGENT, EKU_CLIENT_AUTHENTICATION,
+    };
+
+    use super::{CertificateTemplate, TemplatePermissions};
+
+    const DOMAIN_USERS: &str = "S-1-5-21-1-2-3-513";
+    const DOMAIN_ADMINS: &str = "S-1-5-21-1-2-3-512";
+
+    /// `Code Signing`
+    const EKU_CODE_SIGNING: &str = "1.3.6.1.5.5.7.3.3";
+
+    fn sid(sid: &str) -> Sid {
+        serde_json::from_str(&format!("\"{sid}\"")).unwrap()
+    }
+
+    fn ace(mask: AccessMask, object_type: Option<&str>) -> AccessControlEntry {
+        let trustee = sddl::Sid::try_from(DOMAIN_USERS).unwrap();
+        let ace = match object_type {
+            Some(guid) => Ace::access_allowed_object(
+                AceHeaderFlags::empty(),
+                mask,
+                Some(sddl::Guid::try_from(guid).unwrap()),
+                None,
+                trustee,
+            ),
+            None => Ace::access_allowed(AceHeaderFlags::empty(), mask, trustee),
+        };
+        AccessControlEntry::from_ace(&ace).unwrap()
+    }
+
+    fn vulnerabilities(
+        enrollment: bool,
+        name_flags: CertificateNameFlags,
+        enrollment_flags: EnrollmentFlags,
+        authorized_signatures: i32,
+        ekus: &[&str],
+    ) -> Vec<&'static str> {
+        let permissions = TemplatePermissions {
+            low_privileged_enrollment: enrollment,
+            ..Default::default()
+        };
+        let ekus: Vec<_> = ekus.iter().map(|eku| eku.to_string()).collect();
+        CertificateTemplate::vulnerabilities_of(
+            &permissions,
+            name_flags,
+            enrollment_flags,
+            authorized_signatures,
+            &ekus,
+        )
+    }
+
+    #[test]
+    fn test_is_low_privileged() {
+        for trustee in [
+            "S-1-1-0",
+            "S-1-5-7",
+            "S-1-5-11",
+            "S-1-5-32-545",
+            DOMAIN_USERS,
+            "S-1-5-21-1-2-3-515",
+        ] {
+            assert!(
+                CertificateTemplate::is_low_privileged(&sid(trustee)),
+                "{trustee}"
+            );
+        }
+        for trustee in [
+            "S-1-5-18",
+            "S-1-5-32-544",
+            DOMAIN_ADMINS,
+            "S-1-5-21-1-2-3-1104",
+        ] {
+            assert!(
+                !CertificateTemplate::is_low_privileged(&sid(trustee)),
+                "{trustee}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_allows_enrollment() {
+        assert!(CertificateTemplate::allows_enrollment(&ace(
+            AccessMask::GENERIC_ALL,
+            None
+        )));
+        assert!(CertificateTemplate::allows_enrollment(&ace(
+            AccessMask::CONTROL_ACCESS,
+            None
+        )));
+        assert!(CertificateTemplate::allows_enrollment(&ace(
+            AccessMask::CONTROL_ACCESS,
+            Some("0e10c968-78fb-11d2-90d4-00c04f79dc55")
+        )));
+        assert!(CertificateTemplate::allows_enrollment(&ace(
+            AccessMask::CONTROL_ACCESS,
+            Some("a05b8cc2-17bc-4802-a710-e7c15ab866a2")
+        )));
+
+        // another extended right
+        assert!(!CertificateTemplate::allows_enrollment(&ace(
+            AccessMask::CONTROL_ACCESS,
+            Some("00299570-246d-11d0-a768-00aa006e0529")
+        )));
+        assert!(!CertificateTemplate::allows_enrollment(&ace(
+            AccessMask::READ_PROPERTY,
+            None
+        )));
+    }
+
+    #[test]
+    fn test_allows_modification() {
+        for mask in [
+            AccessMask::GENERIC_ALL,
+            AccessMask::GENERIC_WRITE,
+            AccessMask::WRITE_DACL,
+            AccessMask::WRITE_OWNER,
+            AccessMask::WRITE_PROPERTY,
+        ] {
+            assert!(CertificateTemplate::allows_modification(&ace(mask, None)));
+        }
+
+        // write access to a single attribute
+        assert!(!CertificateTemplate::allows_modification(&ace(
+            AccessMask::WRITE_PROPERTY,
+            Some("bf967a06-0de6-11d0-a285-00aa003049e2")
+        )));
+        assert!(!CertificateTemplate::allows_modification(&ace(
+            AccessMask::READ_PROPERTY | AccessMask::CONTROL_ACCESS,
+            None
+        )));
+    }
+
+    #[test]
+    fn test_esc1() {
+        let supplies_subject = CertificateNameFlags::CT_FLAG_ENROLLEE_SUPPLIES_SUBJECT;
+        let client_auth = [EKU_CLIENT_AUTHENTICATION];
+        assert_eq!(
+            vulnerabilities(
+                true,
+                supplies_subject,
+                EnrollmentFlags::empty(),
+                0,
+                &client_auth
+            ),
+            ["ESC1"]
+        );
+
+        // not enrollable by low-privileged principals
+        assert!(vulnerabilities(
+            false,
+            supplies_subject,
+            EnrollmentFlags::empty(),
+            0,
+            &client_auth
+        )
+        .is_empty());
+
+        // manager approval
+        assert!(vulnerabilities(
+            true,
+            supplies_subject,
+            EnrollmentFlags::CT_FLAG_PEND_ALL_REQUESTS,
+            0,
+            &client_auth
+        )
+        .is_empty());
+
+        // authorized signatures required
+        assert!(vulnerabilities(
+            true,
+            supplies_subject,
+            EnrollmentFlags::empty(),
+            1,
+            &client_auth
+        )
+        .is_empty());
+
+        // no client authentication EKU
+        assert!(vulnerabilities(
+            true,
+            supplies_subject,
+            EnrollmentFlags::empty(),
+            0,
+            &[EKU_CODE_SIGNING]
+        )
+        .is_empty());
+
+        // the subject is built from Active Directory
+        assert!(vulnerabilities(
+            true,
+            CertificateNameFlags::empty(),
+            EnrollmentFlags::empty(),
+            0,
+            &client_auth
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_esc2() {
+        let flags = CertificateNameFlags::empty();
+        assert_eq!(
+            vulnerabilities(true, flags, EnrollmentFlags::empty(), 0, &["2.5.29.37.0"]),
+            ["ESC2"]
+        );
+
+        // no EKUs at all mean that the certificate can be used for any purpose
+        assert_eq!(
+            vulnerabilities(true, flags, EnrollmentFlags::empty(), 0, &[]),
+            ["ESC2"]
+        );
+        assert!(vulnerabilities(
+            true,
+            flags,
+            EnrollmentFlags::CT_FLAG_PEND_ALL_REQUESTS,
+            0,
+            &[]
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_esc3() {
+        let flags = CertificateNameFlags::empty();
+        let agent = [EKU_CERTIFICATE_REQUEST_AGENT];
+        assert_eq!(
+            vulnerabilities(true, flags, EnrollmentFlags::empty(), 0, &agent),
+            ["ESC3"]
+        );
+        assert!(vulnerabilities(true, flags, EnrollmentFlags::empty(), 1, &agent).is_empty());
+        assert!(vulnerabilities(false, flags, EnrollmentFlags::empty(), 0, &agent).is_empty());
+    }
+
+    #[test]
+    fn test_esc4() {
+        let permissions = TemplatePermissions {
+            low_privileged_write: true,
+            ..Default::default()
+        };
+
+        // write access is sufficient, even if enrollment requires approval
+        assert_eq!(
+            CertificateTemplate::vulnerabilities_of(
+                &permissions,
+                CertificateNameFlags::empty(),
+                EnrollmentFlags::CT_FLAG_PEND_ALL_REQUESTS,
+                1,
+                &[EKU_CODE_SIGNING.to_string()],
+            ),
+            ["ESC4"]
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use getset::Getters;
+use serde::Serialize;
+
+use crate::win32_types::{
+    Guid, PropertyMetaData, ReplicationMetaData, TruncatedWindowsFileTime, UnixTimestamp,
+};
+
+use super::{domain_controller_names, DataTable};
+
+/// an object which has been changed after a given USN or point in time
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct ChangedObject {
+    name: String,
+    object_class: Option<String>,
+    distinguished_name: Option<String>,
+    is_deleted: bool,
+
+    /// the object has been created within the window
+    is_new: bool,
+    usn_created: Option<i64>,
+    usn_changed: Option<i64>,
+    when_created: Option<TruncatedWindowsFileTime>,
+    when_changed: Option<TruncatedWindowsFileTime>,
+
+    /// attributes which have been changed within the window, according to
+    /// the replication metadata
+    changed_attributes: String,
+
+    /// domain controllers on which these changes originated
+    originating_dcs: String,
+}
+
+impl ChangedObject {
+    /// returns all objects whose `uSNChanged` is larger than `since_usn`, or
+    /// whose `whenChanged` is later than `since`
+    pub fn collect(
+        data_table: &DataTable,
+        since_usn: Option<i64>,
+        since: Option<DateTime<Utc>>,
+    ) -> crate::ntds::Result<Vec<Self>> {
+        let metadata = data_table.data_table().metadata();
+        let domain_controllers = domain_controller_names(data_table)?;
+        let since = since.map(|ts| ts.timestamp());
+
+        let mut objects = Vec::new();
+        for entry in metadata.iter() {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+            let usn_changed = record.att_usn_changed_opt()?;
+            let when_changed = record.att_when_changed_opt()?;
+            if !is_in_window(
+                since_usn,
+                since,
+                usn_changed,
+                when_changed.as_ref().map(UnixTimestamp::timestamp),
+            ) {
+                continue;
+            }
+
+            let usn_created = record.att_usn_created_opt()?;
+            let when_created = record.att_when_created_opt()?;
+            let is_new = is_in_window(
+                since_usn,
+                since,
+                usn_created,
+                when_created.as_ref().map(UnixTimestamp::timestamp),
+            );
+
+            let changes: Vec<PropertyMetaData> = match record.att_repl_property_meta_data_opt() {
+                Ok(Some(repl_metadata)) => changes_in_window(&repl_metadata, since_usn, since),
+                Ok(None) => Vec::new(),
+                Err(why) => {
+                    log::warn!("unable to parse the replication metadata of '{entry}': {why}");
+                    Vec::new()
+                }
+            };
+
+            let mut changed_attributes: Vec<_> = changes
+                .iter()
+                .map(|m| {
+                    let attribute = i32::from_ne_bytes(m.attribute_id().to_ne_bytes());
+                    metadata
+                        .attribute_name_of(attribute)
+                        .map(str::to_string)
+                        .unwrap_or_else(|| format!("{:#x}", m.attribute_id()))
+                })
+                .collect();
+            changed_attributes.sort();
+
+            objects.push(Self {
+                name: entry.rdn().name().to_string(),
+                object_class: metadata.object_class_name(entry).map(str::to_string),
+                distinguished_name: data_table.object_tree().dn_of(entry.record_ptr()),
+                is_deleted: entry.rdn().deleted_from_container().is_some(),
+                is_new,
+                usn_created,
+                usn_changed,
+                when_created,
+                when_changed,
+                changed_attributes: changed_attributes.join(", "),
+                originating_dcs: Self::dc_names(&domain_controllers, &changes),
+            });
+        }
+
+        objects.sort_by_key(|o| o.usn_changed);
+        Ok(objects)
+    }
+
+    /// returns the names of the domain controllers on which the changes
+    /// originated, or their invocation IDs if they are unknown
+    fn dc_names(
+        domain_controllers: &HashMap<Guid, String>,
+        changes: &[PropertyMetaData],
+    ) -> String {
+        changes
+            .iter()
+            .map(|m| {
+                domain_controllers
+                    .get(m.originating_dsa())
+                    .cloned()
+                    .unwrap_or_else(|| m.originating_dsa().to_string())
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// `true` if a change with the given USN or timestamp (in seconds since the
+/// Unix epoch) happened after `since_usn` or `since`
+fn is_in_window(
+    since_usn: Option<i64>,
+    since: Option<i64>,
+    usn: Option<i64>,
+    timestamp: Option<i64>,
+) -> bool {
+    since_usn.is_some_and(|since_usn| usn.is_some_and(|usn| usn > since_usn))
+        || since.is_some_and(|since| timestamp.is_some_and(|ts| ts > since))
+}
+
+/// returns the entries of the replication metadata which have been changed
+/// within the window. The local USN is used, because the originating USN
+/// belongs to another domain controller
+fn changes_in_window(
+    repl_metadata: &ReplicationMetaData,
+    since_usn: Option<i64>,
+    since: Option<i64>,
+) -> Vec<PropertyMetaData> {
+    repl_metadata
+        .entries()
+        .iter()
+        .filter(|m| {
+            is_in_window(
+                since_usn,
+                since,
+                Some(*m.local_usn()),
+                Some(m.time_changed().timestamp()),
+            )
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::win32_types::{Guid, PropertyMetaData, ReplicationMetaData, UnixTimestamp};
+
+    use super::{changes_in_window, is_in_window, ChangedObject};
+
+    const DC1: [u8; 16] = [0x11; 16];
+    const DC2: [u8; 16] = [0x22; 16];
+
+    /// creates replication metadata with entries of
+    /// `(attribute_id, time_changed, originating_usn, local_usn, dc)`
+    fn repl_metadata(entries: &[(u32, u64, i64, i64, [u8; 16])]) -> ReplicationMetaData {
+        let mut value = Vec::new();
+        for v in [1u32, 0, entries.len() as u32, 0] {
+            value.extend_from_slice(&v.to_le_bytes());
+        }
+        for (attribute_id, time_changed, originating_usn, local_usn, dc) in entries {
+            value.extend_from_slice(&attribute_id.to_le_bytes());
+            value.extend_from_slice(&1u32.to_le_bytes());
+            value.extend_from_slice(&time_changed.to_le_bytes());
+            value.extend_from_slice(dc);
+            value.extend_from_slice(&originating_usn.to_le_bytes());
+            value.extend_from_slice(&local_usn.to_le_bytes());
+        }
+        ReplicationMetaData::try_from(&value[..]).unwrap()
+    }
+
+    #[test]
+    fn test_is_in_window() {
+        assert!(!is_in_window(None, None, Some(5000), Some(1_700_000_000)));
+
+        assert!(is_in_window(Some(4711), None, Some(4712), None));
+        assert!(!is_in_window(Some(4711), None, Some(4711), None));
+        assert!(!is_in_window(Some(4711), None, None, Some(1_700_000_000)));
+
+        assert!(is_in_window(
+            None,
+            Some(1_700_000_000),
+            None,
+            Some(1_700_000_001)
+        ));
+        assert!(!is_in_window(
+            None,
+            Some(1_700_000_000),
+            None,
+            Some(1_700_000_000)
+        ));
+
+        // either of both criteria is sufficient
+        assert!(is_in_window(
+            Some(4711),
+            Some(1_700_000_000),
+            Some(100),
+            Some(1_700_000_001)
+        ));
+        assert!(is_in_window(
+            Some(4711),
+            Some(1_700_000_000),
+            Some(4712),
+            Some(0)
+        ));
+    }
+
+    #[test]
+    fn test_changes_in_window() {
+        // 13390472401 is 2025-04-30T07:40:01Z
+        let metadata = repl_metadata(&[
+            (0x20030, 13390472401, 1000, 1000, DC1),
+            (0x90092, 13390472401, 9000, 5000, DC2),
+            (0x9005a, 13390558801, 2000, 2000, DC1),
+        ]);
+        let attributes = |changes: Vec<PropertyMetaData>| -> Vec<u32> {
+            changes.iter().map(|m| *m.attribute_id()).collect()
+        };
+
+        // the originating USN of a change on another DC is not relevant
+        assert_eq!(
+            attributes(changes_in_window(&metadata, Some(4711), None)),
+            [0x90092]
+        );
+
+        let since = metadata.entries()[0].time_changed().timestamp();
+        assert_eq!(
+            attributes(changes_in_window(&metadata, None, Some(since))),
+            [0x9005a]
+        );
+        assert_eq!(
+            attributes(changes_in_window(&metadata, Some(4711), Some(since))),
+            [0x90092, 0x9005a]
+        );
+
+        let changes = changes_in_window(&metadata, Some(0), None);
+        let dc1 = Guid::try_from(&DC1[..]).unwrap();
+        let dc2 = Guid::try_from(&DC2[..]).unwrap();
+        let domain_controllers = HashMap::from([(dc1, "DC01".to_string())]);
+        assert_eq!(
+            ChangedObject::dc_names(&domain_controllers, &changes),
+            format!("{dc2}, DC01")
+        );
+    }
+}
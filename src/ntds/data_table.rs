@@ -1,28 +1,40 @@
+use std::cell::OnceCell;
 use std::collections::{HashMap, HashSet};
-use std::io::{stdout, Write};
+use std::io::{stdout, Stdout, Write};
+use std::path::Path;
 use std::rc::Rc;
 
-use crate::cache::{RecordPointer, SpecialRecords};
+use crate::cache::{RecordId, RecordPointer, SpecialRecords};
 use crate::cli::output::Writer;
-use crate::cli::{EntryFormat, MemberOfAttribute, OutputFormat, OutputOptions, TimelineFormat};
-use crate::membership_serialization::{CsvSerialization, SerializationType};
+use crate::cli::{
+    EntryFormat, MemberOfAttribute, OutputFormat, OutputOptions, TimelineFormat, TopologyView,
+};
+use crate::membership_serialization::SerializationType;
 use crate::ntds::DataTableRecord;
-use crate::ntds::FromDataTable;
 use crate::ntds::LinkTable;
 use crate::ntds::NtdsAttributeId;
 use crate::ntds::Result;
 use crate::object_tree::ObjectTree;
 use crate::progress_bar::create_progressbar;
+use crate::win32_types::Sid;
 use crate::{cache, member_of_attribute, EntryId};
 use crate::{ntds, FormattedValue};
-use bodyfile::Bodyfile3Line;
+use chrono::{DateTime, Utc};
 use flow_record::prelude::Serializer;
 use getset::Getters;
 use maplit::hashset;
 use regex::Regex;
 use serde_json::json;
 
-use super::{Computer, Group, ObjectType, Person, Schema, SdTable};
+use super::primary_group::transitive_members;
+use super::{
+    Anomaly, CertificateTemplate, CertificationAuthority, ChangedObject, Computer, DeletedObject,
+    DnsRecordEntry, DomainInfo, GpoLink, Group, GroupPolicy, KerberosDelegation, MachineCreator,
+    NonDefaultOwner, ObjectType, OuDelegation, PasswordSettings, Person, PrimaryGroupFinding,
+    ReplicationConflict, ReplicationTopology, RoastableAccount, RodcPasswordPolicyEntry, Schema,
+    SdTable, ShadowCredential, SidHistoryEntry, SnapshotDiff, TimelineContext, TimelineFilter,
+    TimelineObject, TrustedDomain,
+};
 
 /// wraps a ESEDB Table.
 /// This class assumes the a NTDS datatable is being wrapped
@@ -33,10 +45,17 @@ pub struct DataTable<'info, 'db> {
     //database: Option<Weak<CDatabase<'r>>>,
     schema_record_id: RecordPointer,
     object_tree: Rc<ObjectTree>,
-    link_table: Rc<LinkTable>,
+    link_table: Rc<LinkTable<'info, 'db>>,
     sd_table: Option<Rc<SdTable>>,
     schema: Schema,
     special_records: SpecialRecords,
+
+    #[getset(skip)]
+    password_settings: OnceCell<Vec<PasswordSettings>>,
+
+    /// objects indexed by their `primaryGroupID`
+    #[getset(skip)]
+    primary_group_members: OnceCell<HashMap<u32, Vec<RecordPointer>>>,
 }
 
 impl<'info, 'db> DataTable<'info, 'db> {
@@ -45,7 +64,7 @@ impl<'info, 'db> DataTable<'info, 'db> {
         data_table: cache::DataTable<'info, 'db>,
         object_tree: Rc<ObjectTree>,
         schema_record_id: RecordPointer,
-        link_table: Rc<LinkTable>,
+        link_table: Rc<LinkTable<'info, 'db>>,
         sd_table: Option<Rc<SdTable>>,
         schema: Schema,
         special_records: SpecialRecords,
@@ -58,6 +77,8 @@ impl<'info, 'db> DataTable<'info, 'db> {
             sd_table,
             schema,
             special_records,
+            password_settings: OnceCell::new(),
+            primary_group_members: OnceCell::new(),
         })
     }
 
@@ -127,6 +148,11 @@ impl<'info, 'db> DataTable<'info, 'db> {
         self.show_typed_objects::<Computer<T>>(options, ObjectType::Computer)
     }
 
+    pub fn show_trusts<T: SerializationType>(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        log::debug!("show_trusts()");
+        self.show_typed_objects::<TrustedDomain<T>>(options, ObjectType::TrustedDomain)
+    }
+
     pub fn show_type_names<T>(&self, options: &OutputOptions) -> anyhow::Result<()>
     where
         T: SerializationType,
@@ -153,12 +179,383 @@ impl<'info, 'db> DataTable<'info, 'db> {
         options.format().unwrap().write_typenames(names)
     }
 
-    pub fn show_tree(&self, max_depth: u8) -> Result<()> {
-        let tree = self.object_tree.to_termtree(max_depth);
-        println!("{}", tree);
+    pub fn show_tree(
+        &self,
+        max_depth: u8,
+        show_delegations: bool,
+        show_gpo_links: bool,
+    ) -> anyhow::Result<()> {
+        if show_delegations || show_gpo_links {
+            let mut annotations: HashMap<RecordPointer, Vec<String>> = HashMap::new();
+            if show_delegations {
+                for delegation in OuDelegation::collect(self)? {
+                    annotations
+                        .entry(*delegation.ptr())
+                        .or_default()
+                        .push(delegation.summary());
+                }
+            }
+            if show_gpo_links {
+                for link in GpoLink::collect(self)? {
+                    annotations
+                        .entry(*link.ptr())
+                        .or_default()
+                        .push(link.summary());
+                }
+            }
+            let tree = self
+                .object_tree
+                .to_annotated_termtree(max_depth, &annotations);
+            println!("{}", tree);
+        } else {
+            let tree = self.object_tree.to_termtree(max_depth);
+            println!("{}", tree);
+        }
         Ok(())
     }
 
+    pub fn show_ou_delegations(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        let delegations = OuDelegation::collect(self)?;
+        options
+            .format()
+            .unwrap()
+            .write_records(delegations.into_iter())
+    }
+
+    pub fn show_owners(&self, options: &OutputOptions, creators: bool) -> anyhow::Result<()> {
+        let format = options.format().unwrap();
+        if creators {
+            format.write_records(MachineCreator::collect(self)?.into_iter())
+        } else {
+            format.write_records(NonDefaultOwner::collect(self)?.into_iter())
+        }
+    }
+
+    pub fn show_kerberos_delegations(
+        &self,
+        options: &OutputOptions,
+        hide_domain_controllers: bool,
+    ) -> anyhow::Result<()> {
+        let delegations = KerberosDelegation::collect(self)?;
+        options.format().unwrap().write_records(
+            delegations
+                .into_iter()
+                .filter(|d| !(hide_domain_controllers && *d.is_domain_controller())),
+        )
+    }
+
+    pub fn show_roastable_accounts(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        let accounts = RoastableAccount::collect(self)?;
+        options
+            .format()
+            .unwrap()
+            .write_records(accounts.into_iter())
+    }
+
+    pub fn show_shadow_credentials(
+        &self,
+        options: &OutputOptions,
+        export_keys: bool,
+    ) -> anyhow::Result<()> {
+        let credentials = ShadowCredential::collect(self, export_keys)?;
+        options
+            .format()
+            .unwrap()
+            .write_records(credentials.into_iter())
+    }
+
+    pub fn show_sid_history(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        let entries = SidHistoryEntry::collect(self)?;
+        options.format().unwrap().write_records(entries.into_iter())
+    }
+
+    pub fn show_primary_groups(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        let findings = PrimaryGroupFinding::collect(self)?;
+        options
+            .format()
+            .unwrap()
+            .write_records(findings.into_iter())
+    }
+
+    pub fn show_gpos(&self, options: &OutputOptions, links: bool) -> anyhow::Result<()> {
+        let format = options.format().unwrap();
+        if links {
+            format.write_records(GpoLink::collect(self)?.into_iter())
+        } else {
+            format.write_records(GroupPolicy::collect(self)?.into_iter())
+        }
+    }
+
+    pub fn show_dns_records(
+        &self,
+        options: &OutputOptions,
+        zone_files: Option<&Path>,
+    ) -> anyhow::Result<()> {
+        let records = DnsRecordEntry::collect(self)?;
+        match zone_files {
+            Some(directory) => DnsRecordEntry::write_zone_files(&records, directory),
+            None => options.format().unwrap().write_records(records.into_iter()),
+        }
+    }
+
+    pub fn show_sites(
+        &self,
+        options: &OutputOptions,
+        show: TopologyView,
+        dot: bool,
+    ) -> anyhow::Result<()> {
+        let topology = ReplicationTopology::collect(self)?;
+        if dot {
+            println!("{}", topology.to_dot());
+            return Ok(());
+        }
+
+        let format = options.format().unwrap();
+        match show {
+            TopologyView::Sites => format.write_records(topology.sites().iter()),
+            TopologyView::Subnets => format.write_records(topology.subnets().iter()),
+            TopologyView::Servers => format.write_records(topology.servers().iter()),
+            TopologyView::Connections => format.write_records(topology.connections().iter()),
+            TopologyView::SiteLinks => format.write_records(topology.site_links().iter()),
+        }
+    }
+
+    pub fn show_certificate_services(
+        &self,
+        options: &OutputOptions,
+        cas: bool,
+    ) -> anyhow::Result<()> {
+        let format = options.format().unwrap();
+        if cas {
+            format.write_records(CertificationAuthority::collect(self)?.into_iter())
+        } else {
+            format.write_records(CertificateTemplate::collect(self)?.into_iter())
+        }
+    }
+
+    pub fn show_rodc_password_policy(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        options
+            .format()
+            .unwrap()
+            .write_records(RodcPasswordPolicyEntry::collect(self)?.into_iter())
+    }
+
+    pub fn show_deleted_objects(
+        &self,
+        options: &OutputOptions,
+        select: Option<&str>,
+        ldif: Option<&Path>,
+    ) -> anyhow::Result<()> {
+        let mut objects = DeletedObject::collect(self)?;
+        if let Some(select) = select {
+            let regex = Regex::new(select)?;
+            objects.retain(|object| object.matches(&regex));
+        }
+        match ldif {
+            Some(path) => DeletedObject::write_ldif(self, &objects, path),
+            None => options.format().unwrap().write_records(objects.into_iter()),
+        }
+    }
+
+    pub fn show_replication_conflicts(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        options
+            .format()
+            .unwrap()
+            .write_records(ReplicationConflict::collect(self)?.into_iter())
+    }
+
+    pub fn show_anomalies(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        options
+            .format()
+            .unwrap()
+            .write_records(Anomaly::collect(self)?.into_iter())
+    }
+
+    pub fn show_diff(&self, baseline: &Self, options: &OutputOptions) -> anyhow::Result<()> {
+        options
+            .format()
+            .unwrap()
+            .write_records(SnapshotDiff::collect(baseline, self)?.into_iter())
+    }
+
+    pub fn show_changes(
+        &self,
+        options: &OutputOptions,
+        since_usn: Option<i64>,
+        since: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<()> {
+        options
+            .format()
+            .unwrap()
+            .write_records(ChangedObject::collect(self, since_usn, since)?.into_iter())
+    }
+
+    pub fn show_password_policies(&self, options: &OutputOptions) -> anyhow::Result<()> {
+        options
+            .format()
+            .unwrap()
+            .write_records(self.password_settings()?.iter())
+    }
+
+    /// returns all password settings objects and the default password
+    /// policy of the domain, ordered by their precedence
+    pub fn password_settings(&self) -> Result<&[PasswordSettings]> {
+        if self.password_settings.get().is_none() {
+            let _ = self.password_settings.set(PasswordSettings::collect(self)?);
+        }
+        Ok(self.password_settings.get().unwrap())
+    }
+
+    /// returns the SID of the domain which is stored in this database
+    pub fn domain_sid(&self) -> Option<Sid> {
+        let metadata = self.data_table.metadata();
+        metadata
+            .record(
+                self.special_records
+                    .domain_root()
+                    .record_ptr()
+                    .ds_record_id(),
+            )
+            .and_then(|entry| entry.sid().clone())
+    }
+
+    /// returns the group of the current domain which has the given RID.
+    /// This is used to resolve the `primaryGroupID` of an account
+    pub fn group_with_rid(&self, rid: u32) -> Option<&cache::DataEntryCore> {
+        let metadata = self.data_table.metadata();
+        match self.domain_sid() {
+            Some(domain_sid) => metadata
+                .ptr_from_sid(&domain_sid.with_rid(rid))
+                .and_then(|ptr| metadata.record(ptr.ds_record_id())),
+            None => metadata.entries_with_rid(rid).next(),
+        }
+    }
+
+    /// returns all groups an object is a member of, including nested
+    /// memberships and the primary group, which is not stored in the link
+    /// table
+    pub fn groups_of(
+        &self,
+        object: &RecordPointer,
+        primary_group_id: Option<i32>,
+    ) -> HashSet<RecordPointer> {
+        let mut groups = self.link_table.transitive_member_of(object.ds_record_id());
+        if let Some(primary_group) = primary_group_id
+            .and_then(|rid| u32::try_from(rid).ok())
+            .and_then(|rid| self.group_with_rid(rid))
+        {
+            groups.insert(*primary_group.record_ptr());
+            groups.extend(
+                self.link_table
+                    .transitive_member_of(primary_group.record_ptr().ds_record_id()),
+            );
+        }
+        groups
+    }
+
+    /// returns all members of a group, including members of nested groups
+    /// and objects which have the group (or one of the nested groups) as
+    /// their primary group
+    pub fn members_of(&self, group: &RecordPointer) -> Result<HashSet<RecordPointer>> {
+        let metadata = self.data_table.metadata();
+        let primary_group_members = self.primary_group_members()?;
+        Ok(transitive_members(group, |group| {
+            let mut members: Vec<_> = self
+                .link_table
+                .members(group.ds_record_id())
+                .into_iter()
+                .flatten()
+                .copied()
+                .collect();
+
+            // only groups of the current domain can be primary groups
+            if let Some(rid) = metadata[group].sid().as_ref().map(Sid::get_rid) {
+                if self
+                    .group_with_rid(*rid)
+                    .is_some_and(|g| g.record_ptr() == group)
+                {
+                    members.extend(primary_group_members.get(rid).into_iter().flatten());
+                }
+            }
+            members
+        }))
+    }
+
+    fn primary_group_members(&self) -> Result<&HashMap<u32, Vec<RecordPointer>>> {
+        if self.primary_group_members.get().is_none() {
+            let mut members: HashMap<u32, Vec<RecordPointer>> = HashMap::new();
+            for entry in self
+                .data_table
+                .metadata()
+                .iter()
+                .filter(|e| e.sid().is_some())
+            {
+                if let Some(rid) = self
+                    .data_table
+                    .data_table_record_from(*entry.record_ptr())?
+                    .att_primary_group_id_opt()?
+                    .and_then(|id| u32::try_from(id).ok())
+                {
+                    members.entry(rid).or_default().push(*entry.record_ptr());
+                }
+            }
+            let _ = self.primary_group_members.set(members);
+        }
+        Ok(self.primary_group_members.get().unwrap())
+    }
+
+    /// returns the names of all privileged groups this account is a
+    /// (direct, nested or primary) member of
+    pub(crate) fn privileged_groups_of(
+        &self,
+        entry: &cache::DataEntryCore,
+        primary_group_id: Option<i32>,
+    ) -> Vec<String> {
+        let metadata = self.data_table.metadata();
+        let groups = self.groups_of(entry.record_ptr(), primary_group_id);
+
+        let mut names: Vec<_> = groups
+            .iter()
+            .map(|ptr| &metadata[ptr])
+            .filter(|group| group.sid().as_ref().is_some_and(Sid::is_privileged))
+            .map(|group| {
+                group
+                    .sam_account_name()
+                    .clone()
+                    .unwrap_or_else(|| group.rdn().name().to_string())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// returns the LinkID of a linked attribute, using the common name of
+    /// its schema entry (e.g. `Member`)
+    pub fn link_id_of(&self, attribute_name: &str) -> Result<Option<u32>> {
+        match self.schema.type_entry(attribute_name) {
+            Some(ptr) => self
+                .data_table
+                .data_table_record_from(*ptr)?
+                .att_link_id_opt(),
+            None => Ok(None),
+        }
+    }
+
+    /// returns the name of the principal with the given SID, if it is
+    /// stored in this database
+    pub fn principal_name(&self, sid: &Sid) -> Option<String> {
+        let metadata = self.data_table.metadata();
+        metadata
+            .ptr_from_sid(sid)
+            .and_then(|ptr| metadata.record(ptr.ds_record_id()))
+            .map(|entry| {
+                entry
+                    .sam_account_name()
+                    .clone()
+                    .unwrap_or_else(|| entry.rdn().name().to_string())
+            })
+    }
+
     pub fn show_entry(&self, entry_id: EntryId, entry_format: EntryFormat) -> Result<()> {
         let record = match entry_id {
             EntryId::Id(id) => self.data_table.metadata().record(&id),
@@ -205,11 +602,7 @@ impl<'info, 'db> DataTable<'info, 'db> {
 
                         if let Some(size) = termsize::get() {
                             let attrib_size = 20;
-                            let value_size = if size.cols > (attrib_size + 2) {
-                                size.cols - (attrib_size + 2)
-                            } else {
-                                0
-                            };
+                            let value_size = size.cols.saturating_sub(attrib_size + 2);
                             table.set_max_column_widths(vec![
                                 (0, attrib_size.into()),
                                 (1, value_size.into()),
@@ -223,6 +616,39 @@ impl<'info, 'db> DataTable<'info, 'db> {
         Ok(())
     }
 
+    pub fn show_domain_info(&self, entry_format: EntryFormat) -> anyhow::Result<()> {
+        let domain_info = DomainInfo::collect(self)?;
+
+        match entry_format {
+            EntryFormat::Simple => {
+                let properties = domain_info.properties();
+                let header_width = properties.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+                for (key, value) in properties {
+                    println!("{key: <header_width$}: {value}");
+                }
+            }
+            EntryFormat::Json => {
+                serde_json::to_writer_pretty(stdout(), &domain_info)?;
+                println!();
+            }
+            EntryFormat::Table => {
+                let mut table = term_table::Table::new();
+                table.add_row(term_table::row::Row::new(vec![
+                    term_table::table_cell::TableCell::new("Property"),
+                    term_table::table_cell::TableCell::new("Value"),
+                ]));
+                for (key, value) in domain_info.properties() {
+                    table.add_row(term_table::row::Row::new(vec![
+                        term_table::table_cell::TableCell::new(key),
+                        term_table::table_cell::TableCell::new(value),
+                    ]));
+                }
+                println!("{}", table.render())
+            }
+        }
+        Ok(())
+    }
+
     pub fn search_entries(&self, regex: &str) -> anyhow::Result<()> {
         let re = Regex::new(regex)?;
         let mut table_columns = vec![
@@ -362,91 +788,65 @@ impl<'info, 'db> DataTable<'info, 'db> {
         Ok(())
     }
 
-    fn timelines_from_supported_type(
-        &self,
-        record: DataTableRecord,
-        record_type: &ObjectType,
-        options: &OutputOptions,
-        link_table: &LinkTable,
-        distinguished_name: FormattedValue<String>,
-    ) -> anyhow::Result<Vec<Bodyfile3Line>> {
-        Ok(match record_type {
-            ObjectType::Person => Vec::<Bodyfile3Line>::from(Person::<CsvSerialization>::new(
-                record,
-                options,
-                self,
-                link_table,
-                distinguished_name,
-                None,
-            )?),
-            ObjectType::Group => Vec::<Bodyfile3Line>::from(Group::<CsvSerialization>::new(
-                record,
-                options,
-                self,
-                link_table,
-                distinguished_name,
-                None,
-            )?),
-            ObjectType::Computer => Vec::<Bodyfile3Line>::from(Computer::<CsvSerialization>::new(
-                record,
-                options,
-                self,
-                link_table,
-                distinguished_name,
-                None,
-            )?),
-        })
-    }
-
     fn show_timeline_for_records<'a, W>(
         &self,
-        options: &OutputOptions,
         format: &TimelineFormat,
         ser: &mut Serializer<W>,
-        link_table: &LinkTable,
+        csv_wtr: &mut csv::Writer<Stdout>,
+        context: &TimelineContext,
+        filter: &TimelineFilter,
         records: impl Iterator<Item = &'a RecordPointer>,
     ) -> anyhow::Result<()>
     where
         W: Write,
     {
-        let known_types: HashMap<_, _> = self
-            .schema
-            .supported_type_entries()
-            .iter()
-            .map(|(ot, ptr)| (ptr.ds_record_id(), ot))
-            .collect();
-
+        let metadata = self.data_table().metadata();
         records
-            .map(|ptr| &self.data_table().metadata()[ptr])
+            .map(|ptr| &metadata[ptr])
             .map(|e| self.data_table().data_table_record_from(*e.record_ptr()))
             .try_for_each(|r| {
                 let record = r?;
+
+                // objects which have been created after the end of the time
+                // range are skipped before their timestamps are read
+                if !filter.matches_object(record.att_when_created_opt()?.map(DateTime::<Utc>::from))
+                {
+                    return Ok(());
+                }
+
+                let mut object = match TimelineObject::from_record(&record, metadata, context) {
+                    Ok(Some(object)) => object,
+                    Ok(None) => return Ok(()),
+                    Err(why) => {
+                        log::warn!("{why}");
+                        return Ok(());
+                    }
+                };
+                object.retain_events(filter);
                 match format {
                     TimelineFormat::Bodyfile => {
-                        let lines = if let Some(object_type) = record.att_object_type_id_opt()? {
-                            if let Some(record_type) = known_types.get(&object_type) {
-                                self.timelines_from_supported_type(
-                                    record,
-                                    record_type,
-                                    options,
-                                    link_table,
-                                    FormattedValue::Hide,
-                                )?
-                            } else {
-                                record.to_bodyfile(self.data_table().metadata())?
-                            }
-                        } else {
-                            record.to_bodyfile(self.data_table().metadata())?
-                        };
-
-                        for line in lines.into_iter() {
+                        for line in object.to_bodyfile() {
                             println!("{line}");
                         }
                     }
                     TimelineFormat::Record => {
-                        match record.to_flow_record(self.data_table().metadata()) {
-                            Ok(r) => ser.serialize(r)?,
-                            Err(why) => log::warn!("{why}"),
+                        for r in object.to_flow_records() {
+                            ser.serialize(r)?
+                        }
+                    }
+                    TimelineFormat::Tln => {
+                        for line in object.to_tln() {
+                            println!("{line}");
+                        }
+                    }
+                    TimelineFormat::L2tcsv => {
+                        for line in object.to_l2tcsv() {
+                            csv_wtr.serialize(line)?;
+                        }
+                    }
+                    TimelineFormat::Timesketch => {
+                        for event in object.to_timesketch() {
+                            println!("{}", serde_json::to_string(&event)?);
                         }
                     }
                 }
@@ -454,14 +854,42 @@ impl<'info, 'db> DataTable<'info, 'db> {
             })
     }
 
+    /// returns the `governsID` of the given schema classes, which is used
+    /// in `objectClass`
+    fn governs_ids_of(&self, types: &HashSet<RecordId>) -> crate::ntds::Result<HashSet<i32>> {
+        let metadata = self.data_table().metadata();
+        let mut class_ids = HashSet::new();
+        for ptr in types.iter().filter_map(|id| metadata.ptr_from_id(id)) {
+            if let Some(class_id) = self
+                .data_table()
+                .data_table_record_from(*ptr)?
+                .att_governs_id_opt()?
+            {
+                class_ids.insert(class_id);
+            }
+        }
+        Ok(class_ids)
+    }
+
     pub fn show_timeline(
         &self,
         options: &OutputOptions,
-        link_table: &LinkTable,
         include_deleted: bool,
         format: &TimelineFormat,
+        filter: &TimelineFilter,
     ) -> anyhow::Result<()> {
-        let types = if *options.show_all_objects() {
+        let types: HashSet<_> = if !filter.object_types().is_empty() {
+            let mut types = HashSet::new();
+            for name in filter.object_types() {
+                match self.schema.type_entry(name) {
+                    Some(ptr) => types.insert(*ptr.ds_record_id()),
+                    None => anyhow::bail!(
+                        "the schema does not contain the class '{name}' (see the `types` command)"
+                    ),
+                };
+            }
+            types
+        } else if *options.show_all_objects() {
             self.schema
                 .all_type_entries()
                 .iter()
@@ -476,15 +904,18 @@ impl<'info, 'db> DataTable<'info, 'db> {
         };
 
         let mut serializer = Serializer::new(stdout());
+        let mut csv_wtr = csv::Writer::from_writer(stdout());
+        let context = TimelineContext::new(self, filter)?;
 
         self.show_timeline_for_records(
-            options,
             format,
             &mut serializer,
-            link_table,
+            &mut csv_wtr,
+            &context,
+            filter,
             self.data_table()
                 .metadata()
-                .entries_of_types(types)
+                .entries_of_types(types.clone())
                 .map(|e| e.record_ptr()),
         )?;
 
@@ -500,18 +931,38 @@ impl<'info, 'db> DataTable<'info, 'db> {
                     .metadata()
                     .entries_with_deleted_from_container_guid(),
             );
-            let records = deleted_objects_records.union(&records_with_deleted_from_container_guid);
+            // `objectCategory` is removed from tombstones, but `objectClass`
+            // is kept
+            let class_ids = self.governs_ids_of(&types)?;
+            let records = deleted_objects_records
+                .union(&records_with_deleted_from_container_guid)
+                .filter(|ptr| {
+                    if filter.object_types().is_empty() {
+                        return true;
+                    }
+                    match self.data_table().metadata()[**ptr].object_category() {
+                        Some(category) => types.contains(category),
+                        None => self
+                            .data_table()
+                            .data_table_record_from(***ptr)
+                            .ok()
+                            .and_then(|record| record.att_object_class_opt().ok().flatten())
+                            .is_some_and(|classes| classes.iter().any(|c| class_ids.contains(c))),
+                    }
+                });
 
             self.show_timeline_for_records(
-                options,
                 format,
                 &mut serializer,
-                link_table,
+                &mut csv_wtr,
+                &context,
+                filter,
                 records.copied(),
             )
             .unwrap();
         }
 
+        csv_wtr.flush()?;
         Ok(())
     }
 }
@@ -2,16 +2,13 @@ use crate::cache::{self, MetaDataCache, RecordId, RecordPointer};
 use crate::cache::{ColumnIndex, WithValue};
 use crate::ntds::{Error, NtdsAttributeId};
 use crate::value::FromValue;
-use crate::win32_types::TimelineEntry;
 use crate::win32_types::{
-    Rdn, SamAccountType, Sid, TruncatedWindowsFileTime, UserAccountControl, WindowsFileTime,
+    CertificateNameFlags, DnsRecord, EnrollmentFlags, Guid, Rdn, ReplicationMetaData,
+    ReplicationSchedule, SamAccountType, Sid, TruncatedWindowsFileTime, TrustAttributes,
+    TrustDirection, TrustType, UserAccountControl, WindowsFileTime,
 };
 use crate::ColumnInfoMapping;
-use bodyfile::Bodyfile3Line;
-use chrono::{DateTime, Utc};
 use concat_idents::concat_idents;
-use flow_record::derive::*;
-use flow_record::prelude::*;
 use getset::Getters;
 use serde::ser::SerializeStruct;
 use serde::Serialize;
@@ -27,6 +24,10 @@ pub struct EntryAttribute {
     column: String,
     attribute: AttributeName,
     value: AttributeValue,
+
+    /// the individual values of a multi-valued attribute
+    #[serde(skip)]
+    values: Vec<String>,
 }
 
 #[derive(Getters)]
@@ -80,6 +81,19 @@ impl<'info, 'db> DataTableRecord<'info, 'db> {
             Some(v) => Ok(Some(<T>::from_value(v)?)),
         })
     }
+
+    /// reads the value of a column which has been discovered using the
+    /// schema (see [`cache::DataTable::column_of_attribute`])
+    pub fn get_value_of_column_opt<T>(&self, column: ColumnIndex) -> crate::ntds::Result<Option<T>>
+    where
+        T: FromValue,
+    {
+        self.inner.with_value(column, |v| match v {
+            None => Ok(None),
+            Some(v) => Ok(<T>::from_value_opt(v)?),
+        })
+    }
+
     fn has_value<T>(&self, column: NtdsAttributeId, other: &T) -> crate::ntds::Result<bool>
     where
         T: FromValue + Eq,
@@ -98,13 +112,21 @@ impl<'info, 'db> DataTableRecord<'info, 'db> {
     record_attribute!(att_object_sid, AttObjectSid, Sid);
     record_attribute!(att_when_created, AttWhenCreated, TruncatedWindowsFileTime);
     record_attribute!(att_when_changed, AttWhenChanged, TruncatedWindowsFileTime);
+    record_attribute!(att_usn_created, AttUsnCreated, i64);
+    record_attribute!(att_usn_changed, AttUsnChanged, i64);
     record_attribute!(att_object_type_id, AttObjectCategory, RecordId);
+    record_attribute!(att_object_class, AttObjectClass, Vec<i32>);
+    record_attribute!(att_governs_id, AttGovernsId, i32);
     record_attribute!(att_object_name, AttCommonName, Rdn);
     record_attribute!(att_object_name2, AttRdn, Rdn);
     record_attribute!(att_sam_account_name, AttSamAccountName, String);
     record_attribute!(att_sam_account_type, AttSamAccountType, SamAccountType);
     record_attribute!(att_user_principal_name, AttUserPrincipalName, String);
-    record_attribute!(att_service_principal_name, AttServicePrincipalName, String);
+    record_attribute!(
+        att_service_principal_name,
+        AttServicePrincipalName,
+        Vec<String>
+    );
     record_attribute!(
         att_user_account_control,
         AttUserAccountControl,
@@ -132,8 +154,102 @@ impl<'info, 'db> DataTableRecord<'info, 'db> {
     record_attribute!(att_creator_sid, AttMsDsCreatorSid, Sid);
     record_attribute!(att_admin_count, AttAdminCount, i32);
     record_attribute!(att_is_deleted, AttIsDeleted, bool);
+    record_attribute!(
+        att_repl_property_meta_data,
+        AttReplPropertyMetaData,
+        ReplicationMetaData
+    );
     record_attribute!(att_last_known_parent, AttLastKnownParent, RecordId);
     record_attribute!(att_nt_security_descriptor, AttNtSecurityDescriptor, i64);
+    record_attribute!(att_object_guid, AttObjectGuid, Guid);
+    record_attribute!(att_schema_id_guid, AttSchemaIdGuid, Guid);
+    record_attribute!(att_rights_guid, AttRightsGuid, String);
+    record_attribute!(att_display_name, AttDisplayName, String);
+    record_attribute!(att_sid_history, AttSidHistory, Vec<Sid>);
+    record_attribute!(att_behavior_version, AttMsDsBehaviorVersion, i32);
+    record_attribute!(att_fsmo_role_owner, AttFsmoRoleOwner, RecordId);
+    record_attribute!(att_tombstone_lifetime, AttTombstoneLifetime, i32);
+    record_attribute!(att_machine_account_quota, AttMsDsMachineAccountQuota, i32);
+    record_attribute!(att_min_pwd_length, AttMinPwdLength, i32);
+    record_attribute!(att_min_pwd_age, AttMinPwdAge, i64);
+    record_attribute!(att_max_pwd_age, AttMaxPwdAge, i64);
+    record_attribute!(att_pwd_history_length, AttPwdHistoryLength, i32);
+    record_attribute!(att_pwd_properties, AttPwdProperties, i32);
+    record_attribute!(att_lockout_threshold, AttLockoutThreshold, i32);
+    record_attribute!(att_lockout_duration, AttLockoutDuration, i64);
+    record_attribute!(
+        att_lockout_observation_window,
+        AttLockOutObservationWindow,
+        i64
+    );
+    record_attribute!(att_netbios_name, AttNetbiosName, String);
+    record_attribute!(att_dns_root, AttDnsRoot, String);
+    record_attribute!(att_nc_name, AttNcName, RecordId);
+    record_attribute!(att_invocation_id, AttInvocationId, Guid);
+    record_attribute!(att_trust_partner, AttTrustPartner, String);
+    record_attribute!(att_flat_name, AttFlatName, String);
+    record_attribute!(att_security_identifier, AttSecurityIdentifier, Sid);
+    record_attribute!(att_trust_direction, AttTrustDirection, TrustDirection);
+    record_attribute!(att_trust_type, AttTrustType, TrustType);
+    record_attribute!(att_trust_attributes, AttTrustAttributes, TrustAttributes);
+    record_attribute!(att_gp_link, AttGpLink, String);
+    record_attribute!(att_gp_options, AttGpOptions, i32);
+    record_attribute!(att_gpc_file_sys_path, AttGpcFileSysPath, String);
+    record_attribute!(att_gpc_wql_filter, AttGpcWqlFilter, String);
+    record_attribute!(att_version_number, AttVersionNumber, i32);
+    record_attribute!(att_flags, AttFlags, i32);
+    record_attribute!(att_dns_record, AttDnsRecord, Vec<DnsRecord>);
+    record_attribute!(att_dns_tombstoned, AttDnsTombstoned, bool);
+    record_attribute!(att_description, AttDescription, String);
+    record_attribute!(att_location, AttLocation, String);
+    record_attribute!(att_site_object, AttSiteObject, RecordId);
+    record_attribute!(att_site_list, AttSiteList, Vec<RecordId>);
+    record_attribute!(att_cost, AttCost, i32);
+    record_attribute!(att_repl_interval, AttReplInterval, i32);
+    record_attribute!(att_schedule, AttSchedule, ReplicationSchedule);
+    record_attribute!(att_options, AttOptions, i32);
+    record_attribute!(att_enabled_connection, AttEnabledConnection, bool);
+    record_attribute!(att_from_server, AttFromServer, RecordId);
+    record_attribute!(att_server_reference, AttServerReference, RecordId);
+    record_attribute!(att_transport_type, AttTransportType, RecordId);
+    record_attribute!(
+        att_certificate_templates,
+        AttCertificateTemplates,
+        Vec<String>
+    );
+    record_attribute!(att_ca_certificate, AttCaCertificate, Vec<String>);
+    record_attribute!(
+        att_pki_extended_key_usage,
+        AttPkiExtendedKeyUsage,
+        Vec<String>
+    );
+    record_attribute!(
+        att_pki_certificate_application_policy,
+        AttMsPkiCertificateApplicationPolicy,
+        Vec<String>
+    );
+    record_attribute!(
+        att_pki_certificate_name_flag,
+        AttMsPkiCertificateNameFlag,
+        CertificateNameFlags
+    );
+    record_attribute!(
+        att_pki_enrollment_flag,
+        AttMsPkiEnrollmentFlag,
+        EnrollmentFlags
+    );
+    record_attribute!(att_pki_ra_signature, AttMsPkiRaSignature, i32);
+    record_attribute!(
+        att_pki_template_schema_version,
+        AttMsPkiTemplateSchemaVersion,
+        i32
+    );
+    record_attribute!(att_pki_cert_template_oid, AttMsPkiCertTemplateOid, String);
+    record_attribute!(
+        att_allowed_to_delegate_to,
+        AttMsDsAllowedToDelegateTo,
+        Vec<String>
+    );
 
     pub fn mapping(&self) -> &ColumnInfoMapping {
         self.inner.esedbinfo().mapping()
@@ -162,6 +278,14 @@ impl<'info, 'db> DataTableRecord<'info, 'db> {
                                     .cloned()
                                     .unwrap_or(AttributeName::from(column.name().to_string())),
                                 value: AttributeValue::from(x.to_string()),
+                                values: match x {
+                                    cache::Value::Multi(values) => values
+                                        .iter()
+                                        .filter(|v| !matches!(v, cache::Value::Null(())))
+                                        .map(ToString::to_string)
+                                        .collect(),
+                                    value => vec![value.to_string()],
+                                },
                             },
                         )
                     }))
@@ -182,94 +306,6 @@ impl<'info, 'db> DataTableRecord<'info, 'db> {
             "Object".to_string()
         })
     }
-
-    pub fn to_bodyfile(&self, metadata: &MetaDataCache) -> anyhow::Result<Vec<Bodyfile3Line>> {
-        let my_name = self
-            .att_sam_account_name()
-            .or(self.att_object_name().map(|s| s.name().to_string()));
-
-        let object_type_name = self.object_type_name(metadata)?;
-        let object_type_caption =
-            if let Some(last_known_parent) = self.att_last_known_parent_opt()? {
-                metadata
-                    .record(&last_known_parent)
-                    .and_then(|entry| metadata.dn(entry))
-                    .map(|e| format!("{object_type_name}, deleted from {e}"))
-                    .unwrap_or(format!("deleted {object_type_name}"))
-            } else if self.att_is_deleted_opt()?.unwrap_or(false) {
-                format!("deleted {object_type_name}")
-            } else {
-                object_type_name
-            };
-
-        let inode = self.ptr.ds_record_id().to_string();
-        if let Ok(upn) = &my_name {
-            Ok(vec![
-                self.ds_record_time().map(|ts| {
-                    ts.cr_entry(upn, "record creation time", &object_type_caption)
-                        .with_inode(&inode)
-                }),
-                self.att_when_created().map(|ts| {
-                    ts.cr_entry(upn, "object created", &object_type_caption)
-                        .with_inode(&inode)
-                }),
-                self.att_when_changed().map(|ts| {
-                    ts.cr_entry(upn, "object changed", &object_type_caption)
-                        .with_inode(&inode)
-                }),
-                self.att_last_logon().map(|ts| {
-                    ts.c_entry(upn, "last logon on this DC", &object_type_caption)
-                        .with_inode(&inode)
-                }),
-                self.att_last_logon_time_stamp().map(|ts| {
-                    ts.c_entry(upn, "last logon on any DC", &object_type_caption)
-                        .with_inode(&inode)
-                }),
-                self.att_bad_pwd_time().map(|ts| {
-                    ts.c_entry(upn, "bad pwd time", &object_type_caption)
-                        .with_inode(&inode)
-                }),
-                self.att_password_last_set().map(|ts| {
-                    ts.c_entry(upn, "password last set", object_type_caption)
-                        .with_inode(&inode)
-                }),
-            ]
-            .into_iter()
-            .flatten()
-            .collect())
-        } else {
-            Ok(Vec::new())
-        }
-    }
-    pub fn to_flow_record(&self, metadata: &MetaDataCache) -> anyhow::Result<NtdsEntry> {
-        let name = self
-            .att_sam_account_name()
-            .or(self.att_object_name().map(|s| s.name().to_string()))?;
-
-        let object_type = self.object_type_name(metadata)?;
-        let deleted_from = self
-            .att_last_known_parent_opt()?
-            .and_then(|last_known_parent| {
-                metadata
-                    .record(&last_known_parent)
-                    .and_then(|entry| metadata.dn(entry))
-            });
-
-        Ok(NtdsEntry {
-            name,
-            object_type,
-            record_id: self.ptr.ds_record_id().inner(),
-            is_deleted: self.att_is_deleted_opt()?.unwrap_or(false),
-            deleted_from,
-            record_time: self.ds_record_time_opt()?.map(|ts| ts.into()),
-            when_created: self.att_when_created_opt()?.map(|ts| ts.into()),
-            when_changed: self.att_when_changed_opt()?.map(|ts| ts.into()),
-            last_logon: self.att_last_logon_opt()?.map(|ts| ts.into()),
-            last_logon_timestamp: self.att_last_logon_time_stamp_opt()?.map(|ts| ts.into()),
-            bad_pwd_time: self.att_bad_pwd_time_opt()?.map(|ts| ts.into()),
-            password_last_set: self.att_password_last_set_opt()?.map(|ts| ts.into()),
-        })
-    }
 }
 
 impl<'info, 'db> WithValue<NtdsAttributeId> for DataTableRecord<'info, 'db> {
@@ -335,20 +371,3 @@ impl<'info, 'db> Serialize for DataTableRecord<'info, 'db> {
         ser.end()
     }
 }
-
-#[derive(FlowRecord)]
-#[flow_record(version = 1, source = "ntdsextract2", classification = "ntds")]
-pub struct NtdsEntry {
-    name: String,
-    object_type: String,
-    record_id: i32,
-    record_time: Option<DateTime<Utc>>,
-    when_created: Option<DateTime<Utc>>,
-    when_changed: Option<DateTime<Utc>>,
-    last_logon: Option<DateTime<Utc>>,
-    last_logon_timestamp: Option<DateTime<Utc>>,
-    bad_pwd_time: Option<DateTime<Utc>>,
-    password_last_set: Option<DateTime<Utc>>,
-    is_deleted: bool,
-    deleted_from: Option<String>,
-}
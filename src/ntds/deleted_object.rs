@@ -0,0 +1,541 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use base64::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+use getset::Getters;
+use regex::Regex;
+use serde::Serialize;
+use strum::Display;
+
+use crate::cache::{RecordId, RecordPointer};
+use crate::win32_types::{Guid, Sid, TruncatedWindowsFileTime, TIMESTAMP_FORMAT};
+
+use super::{domain_controller_names, find_by_path, DataTable, DomainInfo, NtdsAttributeId};
+
+/// name of the containers which hold deleted objects
+const DELETED_OBJECTS: &str = "Deleted Objects";
+
+/// value of `tombstoneLifetime` which is used if the attribute is not set
+const DEFAULT_TOMBSTONE_LIFETIME_DAYS: i64 = 60;
+
+/// `lDAPDisplayName` of the attribute which contains the number of days
+/// after which a deleted object is recycled. This attribute is not
+/// contained in `attids.h`
+const DELETED_OBJECT_LIFETIME_ATTRIBUTE: &str = "msDS-DeletedObjectLifetime";
+
+/// `lDAPDisplayName` of the attribute which is set when a deleted object
+/// has been recycled
+const IS_RECYCLED_ATTRIBUTE: &str = "isRecycled";
+
+/// `lDAPDisplayName` of the attribute which contains the original RDN of a
+/// deleted object
+const LAST_KNOWN_RDN_ATTRIBUTE: &str = "msDS-LastKnownRDN";
+
+/// maximum number of seconds between the deletion of an object and the
+/// removal of one of its links, if the link has been removed by the deletion
+const LINK_DELETION_TOLERANCE_SECONDS: i64 = 5;
+
+/// OID of the LDAP control which is required to modify deleted objects
+/// (`LDAP_SERVER_SHOW_DELETED_OID`)
+const SHOW_DELETED_OID: &str = "1.2.840.113556.1.4.417";
+
+#[derive(Display, Serialize, Clone, Copy, Eq, PartialEq)]
+pub enum DeletionState {
+    /// the object has been deleted while the Recycle Bin was enabled. It
+    /// can be restored with all of its attributes
+    #[strum(serialize = "deleted")]
+    #[serde(rename = "deleted")]
+    Deleted,
+
+    /// the object has been deleted while the Recycle Bin was disabled. It
+    /// can be reanimated, but most of its attributes are lost
+    #[strum(serialize = "tombstone")]
+    #[serde(rename = "tombstone")]
+    Tombstone,
+
+    /// the object has been recycled and cannot be restored anymore
+    #[strum(serialize = "recycled")]
+    #[serde(rename = "recycled")]
+    Recycled,
+}
+
+/// an object which has been deleted, but not yet been garbage collected
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct DeletedObject {
+    name: String,
+    object_class: Option<String>,
+    object_guid: Option<Guid>,
+    sam_account_name: Option<String>,
+    sid: Option<Sid>,
+    last_known_parent: Option<String>,
+    last_known_rdn: Option<String>,
+
+    /// the distinguished name of the object before it has been deleted
+    original_dn: Option<String>,
+    deletion_time: Option<TruncatedWindowsFileTime>,
+
+    /// the domain controller on which the object has been deleted
+    deleted_on: Option<String>,
+    state: DeletionState,
+    is_reanimatable: bool,
+
+    /// when the object will be recycled (if it is `deleted`) or garbage
+    /// collected (otherwise)
+    expires: Option<TruncatedWindowsFileTime>,
+    surviving_attributes: String,
+
+    #[serde(skip)]
+    ptr: RecordPointer,
+}
+
+impl DeletedObject {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let metadata = data_table.data_table().metadata();
+        let domain_info = DomainInfo::collect(data_table)?;
+        let tombstone_lifetime = domain_info
+            .tombstone_lifetime_days()
+            .map(i64::from)
+            .unwrap_or(DEFAULT_TOMBSTONE_LIFETIME_DAYS);
+        let deleted_object_lifetime =
+            Self::deleted_object_lifetime(data_table)?.unwrap_or(tombstone_lifetime);
+        let domain_controllers = domain_controller_names(data_table)?;
+
+        let is_recycled_column = data_table
+            .data_table()
+            .column_of_attribute(IS_RECYCLED_ATTRIBUTE);
+        let is_recycled_id = metadata.attribute_id_of(IS_RECYCLED_ATTRIBUTE);
+        let last_known_rdn_column = data_table
+            .data_table()
+            .column_of_attribute(LAST_KNOWN_RDN_ATTRIBUTE);
+
+        let containers: Vec<RecordId> = metadata
+            .iter()
+            .filter(|entry| {
+                entry.rdn().deleted_from_container().is_none()
+                    && entry.rdn().name() == DELETED_OBJECTS
+            })
+            .map(|entry| *entry.record_ptr().ds_record_id())
+            .collect();
+
+        let mut objects = Vec::new();
+        for entry in metadata.iter().filter(|entry| {
+            entry.rdn().deleted_from_container().is_some() || containers.contains(entry.parent())
+        }) {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+
+            let repl_metadata = match record.att_repl_property_meta_data_opt() {
+                Ok(repl_metadata) => repl_metadata,
+                Err(why) => {
+                    log::warn!("unable to parse the replication metadata of '{entry}': {why}");
+                    None
+                }
+            };
+            let is_deleted_metadata = repl_metadata
+                .as_ref()
+                .and_then(|m| m.of(NtdsAttributeId::AttIsDeleted as u32));
+            let is_recycled_metadata = repl_metadata.as_ref().and_then(|m| {
+                is_recycled_id.and_then(|id| m.of(u32::from_ne_bytes(id.to_ne_bytes())))
+            });
+
+            let deletion_time = match is_deleted_metadata {
+                Some(m) => Some(m.time_changed().clone()),
+                None => record.att_when_changed_opt()?,
+            };
+            let deleted_on = is_deleted_metadata
+                .and_then(|m| domain_controllers.get(m.originating_dsa()))
+                .cloned();
+
+            let is_recycled = match is_recycled_column {
+                Some(column) => record.get_value_of_column_opt(column)?.unwrap_or(false),
+                None => false,
+            };
+            let state = if is_recycled {
+                DeletionState::Recycled
+            } else if *domain_info.recycle_bin_enabled() {
+                DeletionState::Deleted
+            } else {
+                DeletionState::Tombstone
+            };
+
+            let expires = match state {
+                DeletionState::Deleted => add_days(deletion_time.as_ref(), deleted_object_lifetime),
+                DeletionState::Tombstone => add_days(deletion_time.as_ref(), tombstone_lifetime),
+                DeletionState::Recycled => match is_recycled_metadata {
+                    Some(m) => add_days(Some(m.time_changed()), tombstone_lifetime),
+                    None => add_days(
+                        deletion_time.as_ref(),
+                        deleted_object_lifetime + tombstone_lifetime,
+                    ),
+                },
+            };
+
+            let last_known_parent = record
+                .att_last_known_parent_opt()?
+                .and_then(|id| metadata.ptr_from_id(&id))
+                .and_then(|ptr| data_table.object_tree().dn_of(ptr));
+            let last_known_rdn: Option<String> = match last_known_rdn_column {
+                Some(column) => record.get_value_of_column_opt(column)?,
+                None => None,
+            };
+            let name = entry.rdn().name().to_string();
+            let original_dn = last_known_parent.as_ref().map(|parent| {
+                let rdn = last_known_rdn.as_ref().unwrap_or(&name);
+                let attribute = metadata
+                    .object_class_name(entry)
+                    .map(Self::rdn_attribute_of)
+                    .unwrap_or("CN");
+                format!("{attribute}={},{parent}", escape_rdn_value(rdn))
+            });
+
+            let mut surviving_attributes: Vec<_> = record
+                .all_attributes()
+                .values()
+                .map(|a| a.attribute().to_string())
+                .collect();
+            surviving_attributes.sort();
+
+            objects.push(Self {
+                name,
+                object_class: metadata.object_class_name(entry).map(str::to_string),
+                object_guid: record.att_object_guid_opt()?,
+                sam_account_name: entry.sam_account_name().clone(),
+                sid: entry.sid().clone(),
+                last_known_parent,
+                last_known_rdn,
+                original_dn,
+                deletion_time,
+                deleted_on,
+                is_reanimatable: state != DeletionState::Recycled,
+                state,
+                expires,
+                surviving_attributes: surviving_attributes.join(", "),
+                ptr: *entry.record_ptr(),
+            });
+        }
+
+        // show the most recent deletions first
+        objects
+            .sort_by_key(|o| std::cmp::Reverse(o.deletion_time.clone().map(DateTime::<Utc>::from)));
+        Ok(objects)
+    }
+
+    /// `true` if the name, `sAMAccountName`, original DN or `objectGUID` of
+    /// this object matches the regular expression
+    pub fn matches(&self, regex: &Regex) -> bool {
+        regex.is_match(&self.name)
+            || self
+                .sam_account_name
+                .as_ref()
+                .is_some_and(|n| regex.is_match(n))
+            || self
+                .original_dn
+                .as_ref()
+                .is_some_and(|dn| regex.is_match(dn))
+            || self
+                .object_guid
+                .as_ref()
+                .is_some_and(|guid| regex.is_match(&guid.to_string()))
+    }
+
+    /// writes LDIF change records which reanimate the given objects. Group
+    /// memberships which have been removed by the deletion are restored as
+    /// well, using the deleted values of `member` whose deletion time matches
+    /// the deletion of the object. Memberships which had been removed earlier
+    /// and objects which have been recycled are only mentioned as comments.
+    pub fn write_ldif(data_table: &DataTable, objects: &[Self], path: &Path) -> anyhow::Result<()> {
+        let metadata = data_table.data_table().metadata();
+        let member_link = data_table.link_id_of("Member")?;
+
+        log::info!("writing LDIF change records to '{}'", path.display());
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "version: 1")?;
+
+        for object in objects {
+            writeln!(writer)?;
+            let deletion_time = object
+                .deletion_time
+                .clone()
+                .map(|ts| {
+                    DateTime::<Utc>::from(ts)
+                        .format(&TIMESTAMP_FORMAT)
+                        .to_string()
+                })
+                .unwrap_or_else(|| "at an unknown time".to_string());
+            writeln!(
+                writer,
+                "# {} ({}), deleted {deletion_time}",
+                ldif_comment(&object.name),
+                ldif_comment(object.object_class.as_deref().unwrap_or("unknown class")),
+            )?;
+
+            let (guid, original_dn) = match (&object.object_guid, &object.original_dn) {
+                _ if !object.is_reanimatable => {
+                    writeln!(
+                        writer,
+                        "# the object has been recycled and cannot be reanimated"
+                    )?;
+                    continue;
+                }
+                (Some(guid), Some(original_dn)) => (guid, original_dn),
+                _ => {
+                    writeln!(writer, "# the original name of the object is unknown")?;
+                    continue;
+                }
+            };
+
+            // attributes which have been stripped from tombstones are not
+            // restored, so they must be reviewed before the reanimation
+            let mut surviving_attributes: Vec<_> = data_table
+                .data_table()
+                .data_table_record_from(object.ptr)?
+                .all_attributes()
+                .into_values()
+                .map(|a| (a.attribute().to_string(), a.value().to_string()))
+                .collect();
+            surviving_attributes.sort();
+            writeln!(writer, "# surviving attributes:")?;
+            for (attribute, value) in surviving_attributes {
+                writeln!(writer, "#   {}", ldif_value(&attribute, &value))?;
+            }
+
+            writeln!(writer, "dn: <GUID={guid}>")?;
+            writeln!(writer, "control: {SHOW_DELETED_OID} true")?;
+            writeln!(writer, "changetype: modify")?;
+            writeln!(writer, "delete: isDeleted")?;
+            writeln!(writer, "-")?;
+            writeln!(writer, "replace: distinguishedName")?;
+            writeln!(writer, "{}", ldif_value("distinguishedName", original_dn))?;
+            writeln!(writer, "-")?;
+
+            let mut groups = Vec::new();
+            let mut removed_earlier = Vec::new();
+            if let Some(link_id) = member_link {
+                for link in data_table.link_table().links_of(link_id).filter(|link| {
+                    *link.is_deleted() && link.backlink_dnt() == object.ptr.ds_record_id()
+                }) {
+                    let group = match metadata
+                        .record(link.link_dnt())
+                        .filter(|group| group.rdn().deleted_from_container().is_none())
+                        .and_then(|group| data_table.object_tree().dn_of(group.record_ptr()))
+                    {
+                        Some(group) => group,
+                        None => continue,
+                    };
+                    if is_removed_by_deletion(
+                        link.deletion_time().as_ref(),
+                        object.deletion_time.as_ref(),
+                    ) {
+                        groups.push(group);
+                    } else {
+                        removed_earlier.push(group);
+                    }
+                }
+            }
+            groups.sort();
+            removed_earlier.sort();
+
+            // memberships which had been removed before the object was
+            // deleted are not restored
+            for group in removed_earlier {
+                writeln!(
+                    writer,
+                    "# membership in {} has been removed before the deletion",
+                    ldif_comment(&group)
+                )?;
+            }
+
+            // deactivated links are restored together with the object, if
+            // the Recycle Bin is enabled
+            let prefix = if object.state == DeletionState::Deleted {
+                writeln!(writer, "# group memberships are restored automatically")?;
+                "# "
+            } else {
+                ""
+            };
+            for group in groups {
+                writeln!(writer)?;
+                writeln!(writer, "{prefix}{}", ldif_value("dn", &group))?;
+                writeln!(writer, "{prefix}changetype: modify")?;
+                writeln!(writer, "{prefix}add: member")?;
+                writeln!(writer, "{prefix}{}", ldif_value("member", original_dn))?;
+                writeln!(writer, "{prefix}-")?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// reads `msDS-DeletedObjectLifetime`, which defaults to the tombstone
+    /// lifetime
+    fn deleted_object_lifetime(data_table: &DataTable) -> crate::ntds::Result<Option<i64>> {
+        let column = match data_table
+            .data_table()
+            .column_of_attribute(DELETED_OBJECT_LIFETIME_ATTRIBUTE)
+        {
+            Some(column) => column,
+            None => return Ok(None),
+        };
+        match find_by_path(
+            data_table.special_records().configuration(),
+            &["Services", "Windows NT", "Directory Service"],
+        ) {
+            Some(ds) => Ok(data_table
+                .data_table()
+                .data_table_record_from(*ds.record_ptr())?
+                .get_value_of_column_opt::<i32>(column)?
+                .map(i64::from)),
+            None => Ok(None),
+        }
+    }
+
+    /// returns the naming attribute of the given schema class
+    fn rdn_attribute_of(class_name: &str) -> &'static str {
+        match class_name {
+            "Organizational-Unit" => "OU",
+            "Domain-DNS" => "DC",
+            _ => "CN",
+        }
+    }
+}
+
+fn add_days(
+    timestamp: Option<&TruncatedWindowsFileTime>,
+    days: i64,
+) -> Option<TruncatedWindowsFileTime> {
+    timestamp.map(|ts| (DateTime::<Utc>::from(ts.clone()) + Duration::days(days)).into())
+}
+
+/// `true` if a link has been removed together with the object, i.e. if the
+/// deletion times of both are (almost) equal. Links which had been removed
+/// earlier are not restored
+fn is_removed_by_deletion(
+    link_deletion: Option<&TruncatedWindowsFileTime>,
+    object_deletion: Option<&TruncatedWindowsFileTime>,
+) -> bool {
+    match (link_deletion, object_deletion) {
+        (Some(link_deletion), Some(object_deletion)) => {
+            let difference = DateTime::<Utc>::from(link_deletion.clone())
+                - DateTime::<Utc>::from(object_deletion.clone());
+            difference.num_seconds().abs() <= LINK_DELETION_TOLERANCE_SECONDS
+        }
+        _ => false,
+    }
+}
+
+/// escapes a value so that it can be used as RDN in a distinguished name
+/// (RFC 4514)
+fn escape_rdn_value(value: &str) -> String {
+    let last = value.chars().count().saturating_sub(1);
+    let mut result = String::with_capacity(value.len());
+    for (idx, c) in value.chars().enumerate() {
+        match c {
+            ',' | '+' | '"' | '\\' | '<' | '>' | ';' | '=' => {
+                result.push('\\');
+                result.push(c);
+            }
+            '#' | ' ' if idx == 0 => {
+                result.push('\\');
+                result.push(c);
+            }
+            ' ' if idx == last => result.push_str("\\ "),
+            '\0' => result.push_str("\\00"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// removes control characters from a value which is written into an LDIF
+/// comment, so that it cannot add lines to the file
+fn ldif_comment(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c.is_control() { '?' } else { c })
+        .collect()
+}
+
+/// formats an LDIF attribute value, using base64 encoding if the value is not
+/// a safe string (RFC 2849)
+fn ldif_value(attribute: &str, value: &str) -> String {
+    let is_safe = value.is_ascii()
+        && !value.starts_with([' ', ':', '<'])
+        && !value.ends_with(' ')
+        && !value.contains(['\0', '\r', '\n']);
+    if is_safe {
+        format!("{attribute}: {value}")
+    } else {
+        format!("{attribute}:: {}", BASE64_STANDARD.encode(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::win32_types::TruncatedWindowsFileTime;
+
+    use super::{escape_rdn_value, is_removed_by_deletion, ldif_comment, ldif_value};
+
+    fn ts(seconds: u64) -> TruncatedWindowsFileTime {
+        TruncatedWindowsFileTime::try_from(seconds).unwrap()
+    }
+
+    #[test]
+    fn test_is_removed_by_deletion() {
+        let deleted = ts(13390472401);
+        assert!(is_removed_by_deletion(Some(&deleted), Some(&deleted)));
+        assert!(is_removed_by_deletion(
+            Some(&ts(13390472402)),
+            Some(&deleted)
+        ));
+        assert!(is_removed_by_deletion(
+            Some(&ts(13390472399)),
+            Some(&deleted)
+        ));
+
+        // the membership had been removed a day before the deletion
+        assert!(!is_removed_by_deletion(
+            Some(&ts(13390472401 - 86400)),
+            Some(&deleted)
+        ));
+        assert!(!is_removed_by_deletion(None, Some(&deleted)));
+        assert!(!is_removed_by_deletion(Some(&deleted), None));
+    }
+
+    #[test]
+    fn test_ldif_value() {
+        assert_eq!(
+            ldif_value("member", "CN=Alice,CN=Users,DC=example,DC=com"),
+            "member: CN=Alice,CN=Users,DC=example,DC=com"
+        );
+        assert_eq!(ldif_value("cn", " leading"), "cn:: IGxlYWRpbmc=");
+        assert_eq!(ldif_value("cn", "Jürgen"), "cn:: SsO8cmdlbg==");
+    }
+
+    #[test]
+    fn test_escape_rdn_value() {
+        assert_eq!(escape_rdn_value("Alice"), "Alice");
+        assert_eq!(escape_rdn_value("Doe, John"), "Doe\\, John");
+        assert_eq!(
+            escape_rdn_value(r#"a+b"c\d<e>f;g=h"#),
+            r#"a\+b\"c\\d\<e\>f\;g\=h"#
+        );
+        assert_eq!(escape_rdn_value("#admins"), "\\#admins");
+        assert_eq!(escape_rdn_value("admins#"), "admins#");
+        assert_eq!(escape_rdn_value(" admins "), "\\ admins\\ ");
+        assert_eq!(escape_rdn_value(" "), "\\ ");
+        assert_eq!(escape_rdn_value(""), "");
+    }
+
+    #[test]
+    fn test_ldif_comment() {
+        assert_eq!(ldif_comment("Alice"), "Alice");
+        assert_eq!(
+            ldif_comment("Alice\ndn: CN=Admin\r\n"),
+            "Alice?dn: CN=Admin??"
+        );
+    }
+}
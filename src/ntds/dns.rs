@@ -0,0 +1,339 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use getset::Getters;
+use serde::Serialize;
+
+use crate::cache::RecordId;
+use crate::win32_types::{DnsRecord, TruncatedWindowsFileTime, WindowsFileTime};
+
+use super::DataTable;
+
+/// common name of the schema class of DNS zones
+const DNS_ZONE_CLASS: &str = "Dns-Zone";
+
+/// common name of the schema class of DNS nodes
+const DNS_NODE_CLASS: &str = "Dns-Node";
+
+/// names of nodes which are commonly abused to intercept traffic
+const SUSPICIOUS_NODE_NAMES: [&str; 2] = ["*", "wpad"];
+
+/// a single DNS record (a value of `dnsRecord`), which is stored in a
+/// `dnsNode` object
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct DnsRecordEntry {
+    zone: Option<String>,
+    partition: Option<String>,
+    name: String,
+    fqdn: String,
+    record_type: String,
+    data: String,
+    ttl: u32,
+
+    /// time of the last refresh of a dynamic record
+    timestamp: Option<WindowsFileTime>,
+    is_static: bool,
+
+    /// the record has been deleted by the DNS server, but the node has not
+    /// yet been removed (`dNSTombstoned`)
+    is_tombstoned: bool,
+    when_created: Option<TruncatedWindowsFileTime>,
+    when_changed: Option<TruncatedWindowsFileTime>,
+
+    /// wildcard and WPAD records can be used to intercept traffic
+    is_suspicious: bool,
+
+    #[serde(skip)]
+    record: DnsRecord,
+}
+
+/// a DNS zone (`dnsZone`)
+struct DnsZone {
+    name: String,
+    partition: Option<String>,
+}
+
+impl DnsZone {
+    fn collect(data_table: &DataTable) -> HashMap<RecordId, Self> {
+        let class = match data_table.schema().type_entry(DNS_ZONE_CLASS) {
+            Some(class) => class,
+            None => {
+                log::error!("the schema has no class named '{DNS_ZONE_CLASS}'");
+                return HashMap::new();
+            }
+        };
+
+        data_table
+            .data_table()
+            .metadata()
+            .entries_of_type(class.ds_record_id())
+            .map(|entry| {
+                let partition = data_table
+                    .object_tree()
+                    .dn_of(entry.record_ptr())
+                    .map(|dn| Self::partition_of(&dn).to_string());
+                (
+                    *entry.record_ptr().ds_record_id(),
+                    Self {
+                        name: entry.rdn().name().to_string(),
+                        partition,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// AD-integrated zones can be stored in the domain partition (legacy)
+    /// or in one of the application partitions
+    fn partition_of(dn: &str) -> &'static str {
+        let dn = dn.to_lowercase();
+        if dn.contains("dc=domaindnszones,") {
+            "DomainDnsZones"
+        } else if dn.contains("dc=forestdnszones,") {
+            "ForestDnsZones"
+        } else {
+            "Domain"
+        }
+    }
+}
+
+impl DnsRecordEntry {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let class = match data_table.schema().type_entry(DNS_NODE_CLASS) {
+            Some(class) => class,
+            None => {
+                log::error!("the schema has no class named '{DNS_NODE_CLASS}'");
+                return Ok(Vec::new());
+            }
+        };
+
+        let zones = DnsZone::collect(data_table);
+        let mut entries = Vec::new();
+
+        for entry in data_table
+            .data_table()
+            .metadata()
+            .entries_of_type(class.ds_record_id())
+        {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+
+            let dns_records = match record.att_dns_record_opt() {
+                Ok(Some(dns_records)) => dns_records,
+                Ok(None) => continue,
+                Err(why) => {
+                    log::warn!("unable to parse the DNS records of '{entry}': {why}");
+                    continue;
+                }
+            };
+
+            // deleted nodes have been moved to the Deleted Objects container
+            let zone = match zones.get(entry.parent()) {
+                Some(zone) => Some(zone),
+                None => record
+                    .att_last_known_parent_opt()?
+                    .and_then(|parent| zones.get(&parent)),
+            };
+
+            let name = entry.rdn().name().to_string();
+            let fqdn = fqdn_of(&name, zone.map(|z| z.name.as_str()));
+            let is_suspicious = SUSPICIOUS_NODE_NAMES
+                .iter()
+                .any(|n| n.eq_ignore_ascii_case(&name));
+            let is_tombstoned = record.att_dns_tombstoned_opt()?.unwrap_or(false);
+            let when_created = record.att_when_created_opt()?;
+            let when_changed = record.att_when_changed_opt()?;
+
+            for dns_record in dns_records {
+                entries.push(Self {
+                    zone: zone.map(|z| z.name.clone()),
+                    partition: zone.and_then(|z| z.partition.clone()),
+                    name: name.clone(),
+                    fqdn: fqdn.clone(),
+                    record_type: dns_record.record_type(),
+                    data: dns_record.rdata(),
+                    ttl: *dns_record.ttl(),
+                    timestamp: dns_record.timestamp().clone(),
+                    is_static: dns_record.timestamp().is_none(),
+                    is_tombstoned: is_tombstoned || dns_record.is_tombstone(),
+                    when_created: when_created.clone(),
+                    when_changed: when_changed.clone(),
+                    is_suspicious,
+                    record: dns_record,
+                });
+            }
+        }
+
+        entries.sort_by(|lhs, rhs| {
+            (!lhs.is_suspicious, &lhs.zone, &lhs.fqdn).cmp(&(
+                !rhs.is_suspicious,
+                &rhs.zone,
+                &rhs.fqdn,
+            ))
+        });
+        Ok(entries)
+    }
+
+    /// writes one zone file (RFC 1035) per zone into `directory`. Records
+    /// which have been tombstoned are written as comments.
+    pub fn write_zone_files(entries: &[Self], directory: &Path) -> anyhow::Result<()> {
+        let mut zones: BTreeMap<&str, Vec<&Self>> = BTreeMap::new();
+        for entry in entries {
+            if let Some(zone) = &entry.zone {
+                zones.entry(zone).or_default().push(entry);
+            }
+        }
+
+        std::fs::create_dir_all(directory)?;
+        for (zone, records) in zones {
+            let filename = format!("{}.zone", zone.replace(['/', '\\'], "_"));
+            let path = directory.join(filename);
+            log::info!("writing zone file '{}'", path.display());
+
+            let mut writer = BufWriter::new(File::create(path)?);
+            Self::write_zone(&mut writer, zone, records)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// writes the records of a single zone, starting with its SOA record
+    fn write_zone(
+        writer: &mut impl Write,
+        zone: &str,
+        mut records: Vec<&Self>,
+    ) -> std::io::Result<()> {
+        // the SOA record must be the first record of a zone file
+        records.sort_by_key(|r| (r.record_type != "SOA", &r.fqdn));
+
+        writeln!(writer, "$ORIGIN {}.", zone.trim_end_matches('.'))?;
+        for record in records {
+            let owner = format!("{}.", record.fqdn.trim_end_matches('.'));
+            writeln!(writer, "{}", record.record.to_zone_file_line(&owner))?;
+        }
+        Ok(())
+    }
+}
+
+/// returns the fully qualified name of a node. The node `@` refers to the
+/// zone itself
+fn fqdn_of(name: &str, zone: Option<&str>) -> String {
+    match zone {
+        Some(zone) if name == "@" => zone.to_string(),
+        Some(zone) => format!("{name}.{zone}"),
+        None => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::win32_types::DnsRecord;
+
+    use super::{fqdn_of, DnsRecordEntry, DnsZone};
+
+    impl DnsRecordEntry {
+        fn with(zone: &str, name: &str, record: DnsRecord) -> Self {
+            Self {
+                zone: Some(zone.to_string()),
+                partition: None,
+                name: name.to_string(),
+                fqdn: fqdn_of(name, Some(zone)),
+                record_type: record.record_type(),
+                data: record.rdata(),
+                ttl: *record.ttl(),
+                timestamp: record.timestamp().clone(),
+                is_static: record.timestamp().is_none(),
+                is_tombstoned: record.is_tombstone(),
+                when_created: None,
+                when_changed: None,
+                is_suspicious: false,
+                record,
+            }
+        }
+    }
+
+    /// creates a `DnsRecord` with a TTL of 600 seconds
+    fn record(record_type: u16, data: &[u8]) -> DnsRecord {
+        let mut blob = Vec::from((data.len() as u16).to_le_bytes());
+        blob.extend_from_slice(&record_type.to_le_bytes());
+        blob.extend_from_slice(&[5, 0xf0, 0, 0]);
+        blob.extend_from_slice(&1u32.to_le_bytes());
+        blob.extend_from_slice(&600u32.to_be_bytes());
+        blob.extend_from_slice(&0u32.to_le_bytes());
+        blob.extend_from_slice(&0u32.to_le_bytes());
+        blob.extend_from_slice(data);
+        DnsRecord::try_from(&blob[..]).unwrap()
+    }
+
+    fn count_name(labels: &[&str]) -> Vec<u8> {
+        let mut result = vec![0, labels.len() as u8 + 1];
+        for label in labels {
+            result.push(label.len() as u8);
+            result.extend_from_slice(label.as_bytes());
+        }
+        result.push(0);
+        result[0] = (result.len() - 2) as u8;
+        result
+    }
+
+    #[test]
+    fn test_partition_of() {
+        assert_eq!(
+            DnsZone::partition_of(
+                "DC=example.com,CN=MicrosoftDNS,DC=DomainDnsZones,DC=example,DC=com"
+            ),
+            "DomainDnsZones"
+        );
+        assert_eq!(
+            DnsZone::partition_of(
+                "DC=_msdcs.example.com,CN=MicrosoftDNS,DC=ForestDnsZones,DC=example,DC=com"
+            ),
+            "ForestDnsZones"
+        );
+        assert_eq!(
+            DnsZone::partition_of("DC=example.com,CN=MicrosoftDNS,CN=System,DC=example,DC=com"),
+            "Domain"
+        );
+    }
+
+    #[test]
+    fn test_fqdn_of() {
+        assert_eq!(fqdn_of("@", Some("example.com")), "example.com");
+        assert_eq!(fqdn_of("wpad", Some("example.com")), "wpad.example.com");
+        assert_eq!(fqdn_of("wpad", None), "wpad");
+    }
+
+    #[test]
+    fn test_write_zone() {
+        let mut soa = Vec::new();
+        for v in [42u32, 900, 600, 86400, 3600] {
+            soa.extend_from_slice(&v.to_be_bytes());
+        }
+        soa.extend(count_name(&["dc01", "example", "com"]));
+        soa.extend(count_name(&["hostmaster", "example", "com"]));
+
+        let entries = [
+            DnsRecordEntry::with("example.com", "dc01", record(0x0001, &[10, 0, 0, 1])),
+            DnsRecordEntry::with("example.com", "old", record(0x0000, &[])),
+            DnsRecordEntry::with("example.com", "@", record(0x0006, &soa)),
+        ];
+
+        let mut zone_file = Vec::new();
+        DnsRecordEntry::write_zone(&mut zone_file, "example.com", entries.iter().collect())
+            .unwrap();
+        let zone_file = String::from_utf8(zone_file).unwrap();
+        assert_eq!(
+            zone_file.lines().collect::<Vec<_>>(),
+            [
+                "$ORIGIN example.com.",
+                "example.com.\t600\tIN\tSOA\tdc01.example.com. hostmaster.example.com. 42 900 600 86400 3600",
+                "dc01.example.com.\t600\tIN\tA\t10.0.0.1",
+                "; old.example.com.\t600\tIN\tZERO\t",
+            ]
+        );
+    }
+}
@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use getset::Getters;
+use serde::{Serialize, Serializer};
+
+use crate::cache::RecordId;
+use crate::object_tree_entry::ObjectTreeEntry;
+use crate::win32_types::{Guid, Sid, TruncatedWindowsFileTime};
+
+use super::{DataTable, DataTableRecord};
+
+/// `pwdProperties` flag which enables password complexity
+const DOMAIN_PASSWORD_COMPLEX: i32 = 0x01;
+
+/// `pwdProperties` flag which stores passwords using reversible encryption
+const DOMAIN_PASSWORD_STORE_CLEARTEXT: i32 = 0x10;
+
+/// number of 100ns intervals per minute
+const INTERVALS_PER_MINUTE: i64 = 60 * 10_000_000;
+
+/// a limit of the password or lockout policy, such as the maximum password
+/// age. The unit is given by the name of the field which holds the limit
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TimeLimit {
+    /// there is no limit, e.g. passwords never expire or locked out
+    /// accounts must be unlocked by an administrator
+    Never,
+    Value(i64),
+}
+
+impl TimeLimit {
+    /// converts a limit in minutes into days
+    pub(crate) fn minutes_to_days(self) -> Self {
+        match self {
+            Self::Never => Self::Never,
+            Self::Value(minutes) => Self::Value(minutes / (60 * 24)),
+        }
+    }
+}
+
+impl Serialize for TimeLimit {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Never => serializer.serialize_str("never"),
+            Self::Value(value) => serializer.serialize_i64(*value),
+        }
+    }
+}
+
+/// a summary of the domain which is stored in the database
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct DomainInfo {
+    dns_name: Option<String>,
+    netbios_name: Option<String>,
+    distinguished_name: Option<String>,
+    domain_sid: Option<Sid>,
+    created: Option<TruncatedWindowsFileTime>,
+    domain_functional_level: Option<String>,
+    forest_functional_level: Option<String>,
+    fsmo_roles: FsmoRoles,
+    tombstone_lifetime_days: Option<i32>,
+    machine_account_quota: Option<i32>,
+    password_policy: PasswordPolicy,
+    lockout_policy: LockoutPolicy,
+    recycle_bin_enabled: bool,
+    domain_controllers: Vec<DomainController>,
+}
+
+/// names of the servers which hold the FSMO roles
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct FsmoRoles {
+    schema_master: Option<String>,
+    domain_naming_master: Option<String>,
+    pdc_emulator: Option<String>,
+    rid_master: Option<String>,
+    infrastructure_master: Option<String>,
+}
+
+/// default password policy of the domain
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct PasswordPolicy {
+    min_length: Option<i32>,
+    history_length: Option<i32>,
+    min_age_days: Option<i64>,
+    max_age_days: Option<TimeLimit>,
+    complexity_enabled: Option<bool>,
+    reversible_encryption: Option<bool>,
+}
+
+/// default lockout policy of the domain
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct LockoutPolicy {
+    threshold: Option<i32>,
+    duration_minutes: Option<TimeLimit>,
+    observation_window_minutes: Option<i64>,
+}
+
+/// a domain controller, which is represented by its `nTDSDSA` object
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct DomainController {
+    name: String,
+    site: Option<String>,
+    invocation_id: Option<Guid>,
+    is_read_only: bool,
+}
+
+impl PasswordPolicy {
+    /// reads the password policy from the domain root object
+    pub(crate) fn from_record(domain: &DataTableRecord) -> crate::ntds::Result<Self> {
+        let pwd_properties = domain.att_pwd_properties_opt()?;
+        Ok(Self {
+            min_length: domain.att_min_pwd_length_opt()?,
+            history_length: domain.att_pwd_history_length_opt()?,
+            min_age_days: domain
+                .att_min_pwd_age_opt()?
+                .and_then(interval_to_minutes)
+                .map(|m| m / (60 * 24)),
+            max_age_days: domain
+                .att_max_pwd_age_opt()?
+                .map(limit_to_minutes)
+                .map(TimeLimit::minutes_to_days),
+            complexity_enabled: pwd_properties.map(|p| p & DOMAIN_PASSWORD_COMPLEX != 0),
+            reversible_encryption: pwd_properties.map(|p| p & DOMAIN_PASSWORD_STORE_CLEARTEXT != 0),
+        })
+    }
+}
+
+impl LockoutPolicy {
+    /// reads the lockout policy from the domain root object
+    pub(crate) fn from_record(domain: &DataTableRecord) -> crate::ntds::Result<Self> {
+        Ok(Self {
+            threshold: domain.att_lockout_threshold_opt()?,
+            duration_minutes: domain.att_lockout_duration_opt()?.map(limit_to_minutes),
+            observation_window_minutes: domain
+                .att_lockout_observation_window_opt()?
+                .and_then(interval_to_minutes),
+        })
+    }
+}
+
+impl DomainInfo {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Self> {
+        let special_records = data_table.special_records();
+        let domain_root = special_records.domain_root();
+        let configuration = special_records.configuration();
+        let partitions = configuration.find_child_by_name("Partitions");
+        let directory_service = find_by_path(
+            configuration,
+            &["Services", "Windows NT", "Directory Service"],
+        );
+
+        let domain = data_table
+            .data_table()
+            .data_table_record_from(*domain_root.record_ptr())?;
+        let partitions_record = match &partitions {
+            Some(p) => Some(
+                data_table
+                    .data_table()
+                    .data_table_record_from(*p.record_ptr())?,
+            ),
+            None => None,
+        };
+
+        let (dns_name, netbios_name) = match &partitions {
+            Some(partitions) => Self::names_of(data_table, partitions, domain_root)?,
+            None => (None, None),
+        };
+
+        let fsmo_role_owner = |entry: Option<Rc<ObjectTreeEntry>>| -> crate::ntds::Result<_> {
+            match entry {
+                Some(entry) => Ok(data_table
+                    .data_table()
+                    .data_table_record_from(*entry.record_ptr())?
+                    .att_fsmo_role_owner_opt()?
+                    .and_then(|id| Self::server_name_of(data_table, &id))),
+                None => Ok(None),
+            }
+        };
+
+        let fsmo_roles = FsmoRoles {
+            schema_master: fsmo_role_owner(Some(Rc::clone(special_records.schema())))?,
+            domain_naming_master: fsmo_role_owner(partitions.clone())?,
+            pdc_emulator: fsmo_role_owner(Some(Rc::clone(domain_root)))?,
+            rid_master: fsmo_role_owner(find_by_path(domain_root, &["System", "RID Manager$"]))?,
+            infrastructure_master: fsmo_role_owner(
+                domain_root.find_child_by_name("Infrastructure"),
+            )?,
+        };
+
+        let tombstone_lifetime_days = match &directory_service {
+            Some(ds) => data_table
+                .data_table()
+                .data_table_record_from(*ds.record_ptr())?
+                .att_tombstone_lifetime_opt()?,
+            None => None,
+        };
+
+        let password_policy = PasswordPolicy::from_record(&domain)?;
+        let lockout_policy = LockoutPolicy::from_record(&domain)?;
+
+        Ok(Self {
+            dns_name,
+            netbios_name,
+            distinguished_name: Some(domain_root.distinguished_name().clone()),
+            domain_sid: data_table.domain_sid(),
+            created: domain.att_when_created_opt()?,
+            domain_functional_level: domain
+                .att_behavior_version_opt()?
+                .map(functional_level_name),
+            forest_functional_level: match &partitions_record {
+                Some(p) => p.att_behavior_version_opt()?.map(functional_level_name),
+                None => None,
+            },
+            fsmo_roles,
+            tombstone_lifetime_days,
+            machine_account_quota: domain.att_machine_account_quota_opt()?,
+            password_policy,
+            lockout_policy,
+            recycle_bin_enabled: Self::is_recycle_bin_enabled(
+                data_table,
+                partitions.as_ref(),
+                directory_service.as_ref(),
+            )?,
+            domain_controllers: Self::find_domain_controllers(data_table)?,
+        })
+    }
+
+    /// reads the DNS and NetBIOS name of the domain from its `crossRef`
+    /// object
+    fn names_of(
+        data_table: &DataTable,
+        partitions: &ObjectTreeEntry,
+        domain_root: &ObjectTreeEntry,
+    ) -> crate::ntds::Result<(Option<String>, Option<String>)> {
+        for cross_ref in partitions.children().borrow().iter() {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*cross_ref.record_ptr())?;
+            if record.att_nc_name_opt()?.as_ref() == Some(domain_root.record_ptr().ds_record_id()) {
+                return Ok((record.att_dns_root_opt()?, record.att_netbios_name_opt()?));
+            }
+        }
+        Ok((None, None))
+    }
+
+    /// `fSMORoleOwner` refers to the `nTDSDSA` object of a domain
+    /// controller, whose parent is the server object
+    fn server_name_of(data_table: &DataTable, ntds_settings: &RecordId) -> Option<String> {
+        let metadata = data_table.data_table().metadata();
+        metadata
+            .record(ntds_settings)
+            .and_then(|entry| metadata.record(entry.parent()))
+            .map(|server| server.rdn().name().to_string())
+    }
+
+    /// the Recycle Bin is enabled if the `Partitions` container refers to
+    /// the `Recycle Bin Feature` in its `msDS-EnabledFeature` attribute
+    fn is_recycle_bin_enabled(
+        data_table: &DataTable,
+        partitions: Option<&Rc<ObjectTreeEntry>>,
+        directory_service: Option<&Rc<ObjectTreeEntry>>,
+    ) -> crate::ntds::Result<bool> {
+        let feature = directory_service
+            .and_then(|ds| find_by_path(ds, &["Optional Features", "Recycle Bin Feature"]));
+        let (partitions, feature) = match (partitions, feature) {
+            (Some(partitions), Some(feature)) => (partitions, feature),
+            _ => return Ok(false),
+        };
+        let link_id = match data_table.link_id_of("ms-DS-Enabled-Feature")? {
+            Some(link_id) => link_id,
+            None => return Ok(false),
+        };
+
+        Ok(data_table.link_table().links_of(link_id).any(|link| {
+            !link.is_deleted()
+                && link.link_dnt() == partitions.record_ptr().ds_record_id()
+                && link.backlink_dnt() == feature.record_ptr().ds_record_id()
+        }))
+    }
+
+    fn find_domain_controllers(
+        data_table: &DataTable,
+    ) -> crate::ntds::Result<Vec<DomainController>> {
+        let metadata = data_table.data_table().metadata();
+        let mut domain_controllers = Vec::new();
+
+        for (class_name, is_read_only) in [("NTDS-DSA", false), ("NTDS-DSA-RO", true)] {
+            let class = match data_table.schema().type_entry(class_name) {
+                Some(class) => class,
+                None => continue,
+            };
+
+            for ntds_settings in metadata.entries_of_type(class.ds_record_id()) {
+                if ntds_settings.rdn().deleted_from_container().is_some() {
+                    continue;
+                }
+
+                let server = metadata.record(ntds_settings.parent());
+                let site = server
+                    .and_then(|server| metadata.record(server.parent()))
+                    .and_then(|servers| metadata.record(servers.parent()))
+                    .map(|site| site.rdn().name().to_string());
+                let record = data_table
+                    .data_table()
+                    .data_table_record_from(*ntds_settings.record_ptr())?;
+
+                domain_controllers.push(DomainController {
+                    name: server
+                        .map(|server| server.rdn().name().to_string())
+                        .unwrap_or_else(|| ntds_settings.rdn().name().to_string()),
+                    site,
+                    invocation_id: record.att_invocation_id_opt()?,
+                    is_read_only,
+                });
+            }
+        }
+
+        domain_controllers.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+        Ok(domain_controllers)
+    }
+
+    /// returns all values as a list of key/value pairs, where the keys of
+    /// nested values are separated by dots
+    pub fn properties(&self) -> Vec<(String, String)> {
+        let mut properties = Vec::new();
+        flatten(&serde_json::json!(self), String::new(), &mut properties);
+        properties
+    }
+}
+
+/// returns the names of all domain controllers, indexed by their invocation
+/// ID (which is used in the replication metadata)
+pub(crate) fn domain_controller_names(
+    data_table: &DataTable,
+) -> crate::ntds::Result<HashMap<Guid, String>> {
+    Ok(DomainInfo::find_domain_controllers(data_table)?
+        .into_iter()
+        .filter_map(|dc| dc.invocation_id.map(|id| (id, dc.name)))
+        .collect())
+}
+
+fn flatten(value: &serde_json::Value, prefix: String, properties: &mut Vec<(String, String)>) {
+    let key = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}.{name}")
+        }
+    };
+    match value {
+        serde_json::Value::Object(map) => {
+            for (name, value) in map {
+                flatten(value, key(name), properties);
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for (idx, value) in values.iter().enumerate() {
+                flatten(value, key(&idx.to_string()), properties);
+            }
+        }
+        serde_json::Value::Null => properties.push((prefix, String::new())),
+        serde_json::Value::String(s) => properties.push((prefix, s.clone())),
+        value => properties.push((prefix, value.to_string())),
+    }
+}
+
+pub(crate) fn find_by_path(
+    root: &Rc<ObjectTreeEntry>,
+    path: &[&str],
+) -> Option<Rc<ObjectTreeEntry>> {
+    let mut entry = Rc::clone(root);
+    for name in path {
+        entry = entry.find_child_by_name(name)?;
+    }
+    Some(entry)
+}
+
+/// converts a (negative) time interval, as used in the password and lockout
+/// policy, into minutes. Returns `None` if the interval is infinite
+pub(crate) fn interval_to_minutes(interval: i64) -> Option<i64> {
+    if interval == i64::MIN {
+        None
+    } else {
+        Some(interval.abs() / INTERVALS_PER_MINUTE)
+    }
+}
+
+/// converts a time interval which is used as a limit, such as the maximum
+/// password age or the lockout duration, into minutes. In this case, both
+/// 0 and an infinite interval mean that there is no limit
+pub(crate) fn limit_to_minutes(interval: i64) -> TimeLimit {
+    match interval {
+        0 => TimeLimit::Never,
+        interval => interval_to_minutes(interval).map_or(TimeLimit::Never, TimeLimit::Value),
+    }
+}
+
+/// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-adts/d7422d35-448a-451a-8846-6a7def0044df>
+fn functional_level_name(level: i32) -> String {
+    let name = match level {
+        0 => "Windows 2000",
+        1 => "Windows Server 2003 interim",
+        2 => "Windows Server 2003",
+        3 => "Windows Server 2008",
+        4 => "Windows Server 2008 R2",
+        5 => "Windows Server 2012",
+        6 => "Windows Server 2012 R2",
+        7 => "Windows Server 2016",
+        10 => "Windows Server 2025",
+        _ => "unknown",
+    };
+    format!("{name} ({level})")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{functional_level_name, interval_to_minutes, limit_to_minutes, TimeLimit};
+
+    #[test]
+    fn test_interval_to_minutes() {
+        // 42 days, which is the default maximum password age
+        assert_eq!(interval_to_minutes(-36_288_000_000_000), Some(42 * 24 * 60));
+        assert_eq!(interval_to_minutes(-18_000_000_000), Some(30));
+        assert_eq!(interval_to_minutes(i64::MIN), None);
+        assert_eq!(interval_to_minutes(0), Some(0));
+    }
+
+    #[test]
+    fn test_limit_to_minutes() {
+        assert_eq!(limit_to_minutes(-18_000_000_000), TimeLimit::Value(30));
+        assert_eq!(limit_to_minutes(0), TimeLimit::Never);
+        assert_eq!(limit_to_minutes(i64::MIN), TimeLimit::Never);
+        assert_eq!(
+            limit_to_minutes(-36_288_000_000_000).minutes_to_days(),
+            TimeLimit::Value(42)
+        );
+        assert_eq!(TimeLimit::Never.minutes_to_days(), TimeLimit::Never);
+    }
+
+    #[test]
+    fn test_serialize_time_limit() {
+        assert_eq!(serde_json::to_string(&TimeLimit::Value(42)).unwrap(), "42");
+        assert_eq!(
+            serde_json::to_string(&TimeLimit::Never).unwrap(),
+            r#""never""#
+        );
+    }
+
+    #[test]
+    fn test_functional_level_name() {
+        assert_eq!(functional_level_name(7), "Windows Server 2016 (7)");
+        assert_eq!(functional_level_name(8), "unknown (8)");
+    }
+}
@@ -0,0 +1,382 @@
+use std::collections::HashMap;
+
+use getset::Getters;
+use serde::Serialize;
+
+use crate::cache::{DataEntryCore, RecordPointer};
+use crate::win32_types::{GpLinkEntry, TruncatedWindowsFileTime};
+
+use super::DataTable;
+
+/// common name of the schema class of group policy containers
+const GPO_CLASS: &str = "Group-Policy-Container";
+
+/// classes of objects which can have GPOs linked to them
+const LINKABLE_CLASSES: [&str; 3] = ["Domain-DNS", "Organizational-Unit", "Site"];
+
+/// `gPOptions` flag which blocks inheritance of GPOs from the parent
+const GPO_BLOCK_INHERITANCE: i32 = 0x1;
+
+/// `flags` of a GPO
+const GPO_USER_DISABLED: i32 = 0x1;
+const GPO_MACHINE_DISABLED: i32 = 0x2;
+
+/// a group policy object (`groupPolicyContainer`)
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct GroupPolicy {
+    display_name: Option<String>,
+    guid: String,
+    file_sys_path: Option<String>,
+    user_version: Option<i32>,
+    machine_version: Option<i32>,
+    user_enabled: bool,
+    machine_enabled: bool,
+    wmi_filter: Option<String>,
+    when_created: Option<TruncatedWindowsFileTime>,
+    when_changed: Option<TruncatedWindowsFileTime>,
+    is_deleted: bool,
+}
+
+/// a link of a GPO to a domain, site or organizational unit
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct GpoLink {
+    distinguished_name: Option<String>,
+    object_class: Option<String>,
+    link_order: usize,
+    gpo: Option<String>,
+    gpo_guid: Option<String>,
+    enforced: bool,
+    disabled: bool,
+    block_inheritance: bool,
+
+    #[serde(skip)]
+    ptr: RecordPointer,
+}
+
+impl GroupPolicy {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let class = match data_table.schema().type_entry(GPO_CLASS) {
+            Some(class) => class,
+            None => {
+                log::error!("the schema has no class named '{GPO_CLASS}'");
+                return Ok(Vec::new());
+            }
+        };
+
+        let metadata = data_table.data_table().metadata();
+        let wmi_filters = Self::wmi_filters(data_table)?;
+        let mut policies = Vec::new();
+
+        for entry in metadata.entries_of_type(class.ds_record_id()) {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+
+            // the upper 16 bits contain the version of the user settings,
+            // the lower 16 bits contain the version of the computer settings
+            let version = record.att_version_number_opt()?;
+            let flags = record.att_flags_opt()?.unwrap_or(0);
+
+            let wmi_filter = record
+                .att_gpc_wql_filter_opt()?
+                .map(|filter| wmi_filter_name(filter, &wmi_filters));
+
+            policies.push(Self {
+                display_name: record.att_display_name_opt()?,
+                guid: entry.rdn().name().to_string(),
+                file_sys_path: record.att_gpc_file_sys_path_opt()?,
+                user_version: version.map(|v| (v >> 16) & 0xffff),
+                machine_version: version.map(|v| v & 0xffff),
+                user_enabled: flags & GPO_USER_DISABLED == 0,
+                machine_enabled: flags & GPO_MACHINE_DISABLED == 0,
+                wmi_filter,
+                when_created: record.att_when_created_opt()?,
+                when_changed: record.att_when_changed_opt()?,
+                is_deleted: entry.rdn().deleted_from_container().is_some(),
+            });
+        }
+
+        policies.sort_by(|lhs, rhs| lhs.display_name.cmp(&rhs.display_name));
+        Ok(policies)
+    }
+
+    /// returns the names of all WMI filters (`msWMI-Som`), indexed by their
+    /// id in uppercase
+    fn wmi_filters(data_table: &DataTable) -> crate::ntds::Result<HashMap<String, String>> {
+        let mut filters = HashMap::new();
+        let name_column = match data_table.data_table().column_of_attribute("msWMI-Name") {
+            Some(column) => column,
+            None => return Ok(filters),
+        };
+        let class = match data_table.schema().type_entry("ms-WMI-Som") {
+            Some(class) => class,
+            None => return Ok(filters),
+        };
+
+        for entry in data_table
+            .data_table()
+            .metadata()
+            .entries_of_type(class.ds_record_id())
+        {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+            if let Some(name) = record.get_value_of_column_opt::<String>(name_column)? {
+                filters.insert(entry.rdn().name().to_uppercase(), name);
+            }
+        }
+        Ok(filters)
+    }
+}
+
+impl GpoLink {
+    /// collects the links of all domains, sites and organizational units
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let metadata = data_table.data_table().metadata();
+
+        let gpos: HashMap<String, &DataEntryCore> = match data_table.schema().type_entry(GPO_CLASS)
+        {
+            Some(class) => metadata
+                .entries_of_type(class.ds_record_id())
+                .map(|e| (e.rdn().name().to_uppercase(), e))
+                .collect(),
+            None => HashMap::new(),
+        };
+
+        let mut links = Vec::new();
+        for class_name in LINKABLE_CLASSES {
+            let class = match data_table.schema().type_entry(class_name) {
+                Some(class) => class,
+                None => {
+                    log::warn!("the schema has no class named '{class_name}'");
+                    continue;
+                }
+            };
+
+            for entry in metadata.entries_of_type(class.ds_record_id()) {
+                let record = data_table
+                    .data_table()
+                    .data_table_record_from(*entry.record_ptr())?;
+                let block_inheritance = record
+                    .att_gp_options_opt()?
+                    .is_some_and(|o| o & GPO_BLOCK_INHERITANCE != 0);
+                let gp_link = record.att_gp_link_opt()?.unwrap_or_default();
+
+                links.extend(Self::from_gp_link(
+                    &gp_link,
+                    block_inheritance,
+                    data_table.object_tree().dn_of(entry.record_ptr()),
+                    class_name,
+                    *entry.record_ptr(),
+                    |gpo_cn| match gpos.get(&gpo_cn.to_uppercase()) {
+                        Some(gpo) => data_table
+                            .data_table()
+                            .data_table_record_from(*gpo.record_ptr())?
+                            .att_display_name_opt(),
+                        None => Ok(None),
+                    },
+                )?);
+            }
+        }
+
+        links.sort_by(|lhs, rhs| {
+            (&lhs.distinguished_name, lhs.link_order)
+                .cmp(&(&rhs.distinguished_name, rhs.link_order))
+        });
+        Ok(links)
+    }
+
+    /// creates one entry per link in `gp_link`. Objects which block
+    /// inheritance get an entry even if they have no links.
+    /// `display_name_of` returns the name of a GPO, given its common name
+    fn from_gp_link(
+        gp_link: &str,
+        block_inheritance: bool,
+        distinguished_name: Option<String>,
+        class_name: &str,
+        ptr: RecordPointer,
+        display_name_of: impl Fn(&str) -> crate::ntds::Result<Option<String>>,
+    ) -> crate::ntds::Result<Vec<Self>> {
+        let mut links = Vec::new();
+        for link in GpLinkEntry::parse(gp_link) {
+            let gpo = match link.gpo_cn() {
+                Some(gpo_cn) => display_name_of(gpo_cn)?,
+                None => None,
+            };
+
+            links.push(Self {
+                distinguished_name: distinguished_name.clone(),
+                object_class: Some(class_name.to_string()),
+                link_order: *link.link_order(),
+                gpo,
+                gpo_guid: link.gpo_cn().map(String::from),
+                enforced: link.is_enforced(),
+                disabled: link.is_disabled(),
+                block_inheritance,
+                ptr,
+            });
+        }
+
+        // blocking inheritance is relevant even without any links
+        if block_inheritance && gp_link.trim().is_empty() {
+            links.push(Self {
+                distinguished_name,
+                object_class: Some(class_name.to_string()),
+                link_order: 0,
+                gpo: None,
+                gpo_guid: None,
+                enforced: false,
+                disabled: false,
+                block_inheritance,
+                ptr,
+            });
+        }
+        Ok(links)
+    }
+
+    /// short description of this link, which is used to annotate the object
+    /// tree
+    pub fn summary(&self) -> String {
+        if self.gpo_guid.is_none() {
+            return "GPO inheritance blocked".to_string();
+        }
+        let mut flags = Vec::new();
+        if self.enforced {
+            flags.push("enforced");
+        }
+        if self.disabled {
+            flags.push("disabled");
+        }
+        if self.block_inheritance {
+            flags.push("inheritance blocked");
+        }
+
+        let name = self
+            .gpo
+            .as_deref()
+            .or(self.gpo_guid.as_deref())
+            .unwrap_or_default();
+        if flags.is_empty() {
+            format!("GPO #{}: {name}", self.link_order)
+        } else {
+            format!("GPO #{}: {name} ({})", self.link_order, flags.join(", "))
+        }
+    }
+}
+
+/// returns the name of the WMI filter which is referenced by `gPCWQLFilter`.
+/// This value looks like `[example.com;{GUID};0]`, and the id of the filter
+/// is not always written in the same case as the name of its `msWMI-Som`
+fn wmi_filter_name(filter: String, wmi_filters: &HashMap<String, String>) -> String {
+    filter
+        .split(';')
+        .nth(1)
+        .and_then(|id| wmi_filters.get(&id.to_uppercase()))
+        .cloned()
+        .unwrap_or(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::cache::RecordPointer;
+
+    use super::{wmi_filter_name, GpoLink};
+
+    const DEFAULT_DOMAIN_POLICY: &str = "{31B2F340-016D-11D2-945F-00C04FB984F9}";
+    const WORKSTATIONS: &str = "{6AC1786C-016F-11D2-945F-00C04FB984F9}";
+
+    fn links(gp_link: &str, block_inheritance: bool) -> Vec<GpoLink> {
+        let names = HashMap::from([
+            (DEFAULT_DOMAIN_POLICY, "Default Domain Policy"),
+            (WORKSTATIONS, "Workstations"),
+        ]);
+        GpoLink::from_gp_link(
+            gp_link,
+            block_inheritance,
+            Some("OU=Clients,DC=example,DC=com".to_string()),
+            "Organizational-Unit",
+            RecordPointer::default(),
+            |cn| Ok(names.get(cn).map(|name| name.to_string())),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_link_order() {
+        let links = links(
+            &format!(
+                "[LDAP://cn={DEFAULT_DOMAIN_POLICY},cn=policies,cn=system,DC=example,DC=com;0]\
+                 [LDAP://cn={WORKSTATIONS},cn=policies,cn=system,DC=example,DC=com;0]"
+            ),
+            false,
+        );
+        let summaries: Vec<_> = links.iter().map(GpoLink::summary).collect();
+        assert_eq!(
+            summaries,
+            ["GPO #2: Default Domain Policy", "GPO #1: Workstations"]
+        );
+    }
+
+    #[test]
+    fn test_link_options() {
+        let links = links(
+            &format!(
+                "[LDAP://cn={DEFAULT_DOMAIN_POLICY},cn=policies,cn=system,DC=example,DC=com;1]\
+                 [LDAP://cn={WORKSTATIONS},cn=policies,cn=system,DC=example,DC=com;2]"
+            ),
+            true,
+        );
+        assert!(links[0].disabled && !links[0].enforced);
+        assert!(!links[1].disabled && links[1].enforced);
+        assert_eq!(
+            links[0].summary(),
+            "GPO #2: Default Domain Policy (disabled, inheritance blocked)"
+        );
+        assert_eq!(
+            links[1].summary(),
+            "GPO #1: Workstations (enforced, inheritance blocked)"
+        );
+    }
+
+    #[test]
+    fn test_unknown_gpo() {
+        let unknown = "{00000000-0000-0000-0000-000000000000}";
+        let links = links(
+            &format!("[LDAP://cn={unknown},cn=policies,cn=system,DC=example,DC=com;0]"),
+            false,
+        );
+        assert_eq!(links[0].summary(), format!("GPO #1: {unknown}"));
+    }
+
+    #[test]
+    fn test_block_inheritance() {
+        let blocked = links("", true);
+        assert_eq!(blocked.len(), 1);
+        assert_eq!(blocked[0].summary(), "GPO inheritance blocked");
+
+        assert!(links(" ", false).is_empty());
+    }
+
+    #[test]
+    fn test_wmi_filter_name() {
+        let filters = HashMap::from([(
+            "{0A1B2C3D-0000-4000-8000-00000000000A}".to_string(),
+            "Windows 11".to_string(),
+        )]);
+        assert_eq!(
+            wmi_filter_name(
+                "[example.com;{0a1b2c3d-0000-4000-8000-00000000000a};0]".to_string(),
+                &filters
+            ),
+            "Windows 11"
+        );
+        assert_eq!(
+            wmi_filter_name("[example.com;{unknown};0]".to_string(), &filters),
+            "[example.com;{unknown};0]"
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use getset::Getters;
+use serde::Serialize;
+use strum::Display;
+
+use crate::win32_types::{AccessControlType, SecurityDescriptor, Sid, UserAccountControl};
+
+use super::DataTable;
+
+/// `lDAPDisplayName` of the attribute which configures resource-based
+/// constrained delegation. This attribute is not contained in `attids.h`,
+/// so we need to find its column using the schema
+const RBCD_ATTRIBUTE: &str = "msDS-AllowedToActOnBehalfOfOtherIdentity";
+
+#[derive(Display, Serialize, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+pub enum DelegationKind {
+    #[strum(serialize = "unconstrained")]
+    #[serde(rename = "unconstrained")]
+    Unconstrained,
+
+    #[strum(serialize = "constrained")]
+    #[serde(rename = "constrained")]
+    Constrained,
+
+    #[strum(serialize = "constrained with protocol transition")]
+    #[serde(rename = "constrained with protocol transition")]
+    ConstrainedWithProtocolTransition,
+
+    #[strum(serialize = "resource-based")]
+    #[serde(rename = "resource-based")]
+    ResourceBased,
+}
+
+/// an account which is configured for some kind of Kerberos delegation
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct KerberosDelegation {
+    account: String,
+    distinguished_name: Option<String>,
+    sid: Sid,
+    kind: DelegationKind,
+
+    /// the SPNs the account may delegate to, or the principals which may
+    /// delegate to the account (in case of resource-based delegation)
+    targets: String,
+    is_domain_controller: bool,
+}
+
+impl KerberosDelegation {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let metadata = data_table.data_table().metadata();
+        let rbcd_column = data_table.data_table().column_of_attribute(RBCD_ATTRIBUTE);
+        let mut delegations = Vec::new();
+
+        // only security principals can be configured for delegation
+        for entry in metadata
+            .iter()
+            .filter(|e| e.sid().is_some() && e.object_category().is_some())
+        {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+            let uac = record
+                .att_user_account_control_opt()?
+                .unwrap_or(UserAccountControl::empty());
+            let is_domain_controller = Self::is_domain_controller_account(&uac);
+            let account = entry
+                .sam_account_name()
+                .clone()
+                .unwrap_or_else(|| entry.rdn().name().to_string());
+            let sid = entry.sid().clone().unwrap();
+            let distinguished_name = data_table.object_tree().dn_of(entry.record_ptr());
+
+            let mut add = |kind, targets| {
+                delegations.push(Self {
+                    account: account.clone(),
+                    distinguished_name: distinguished_name.clone(),
+                    sid: sid.clone(),
+                    kind,
+                    targets,
+                    is_domain_controller,
+                })
+            };
+
+            let allowed_to_delegate_to =
+                record.att_allowed_to_delegate_to_opt()?.unwrap_or_default();
+            for (kind, targets) in Self::kinds_of(&uac, &allowed_to_delegate_to) {
+                add(kind, targets);
+            }
+
+            if let Some(rbcd_column) = rbcd_column {
+                match record.get_value_of_column_opt::<SecurityDescriptor>(rbcd_column) {
+                    Ok(Some(sd)) => {
+                        let principals: Vec<_> = sd
+                            .dacl_entries()
+                            .into_iter()
+                            .filter(|ace| *ace.access_type() == AccessControlType::Allow)
+                            .map(|ace| match data_table.principal_name(ace.trustee()) {
+                                Some(name) => format!("{name} ({})", ace.trustee()),
+                                None => ace.trustee().to_string(),
+                            })
+                            .collect();
+                        add(DelegationKind::ResourceBased, principals.join(", "));
+                    }
+                    Ok(None) => (),
+                    Err(why) => {
+                        log::warn!("unable to read {RBCD_ATTRIBUTE} of {entry}: {why}")
+                    }
+                }
+            }
+        }
+
+        // domain controllers are expected to have unconstrained delegation,
+        // so we show them last
+        delegations.sort_by(|lhs, rhs| {
+            (lhs.is_domain_controller, lhs.kind, &lhs.account).cmp(&(
+                rhs.is_domain_controller,
+                rhs.kind,
+                &rhs.account,
+            ))
+        });
+        Ok(delegations)
+    }
+
+    /// read-only domain controllers are domain controllers as well, but
+    /// they don't have `ADS_UF_SERVER_TRUST_ACCOUNT` set
+    fn is_domain_controller_account(uac: &UserAccountControl) -> bool {
+        uac.intersects(
+            UserAccountControl::ADS_UF_SERVER_TRUST_ACCOUNT
+                | UserAccountControl::ADS_UF_PARTIAL_SECRETS_ACCOUNT,
+        )
+    }
+
+    /// returns the kinds of delegation which are configured using
+    /// `userAccountControl` and `msDS-AllowedToDelegateTo`, together with
+    /// the delegation targets
+    fn kinds_of(
+        uac: &UserAccountControl,
+        allowed_to_delegate_to: &[String],
+    ) -> Vec<(DelegationKind, String)> {
+        let mut kinds = Vec::new();
+        if uac.contains(UserAccountControl::ADS_UF_TRUSTED_FOR_DELEGATION) {
+            kinds.push((DelegationKind::Unconstrained, String::new()));
+        }
+
+        // protocol transition allows the account to obtain service tickets
+        // for any user to itself, even if it cannot delegate them further
+        if uac.contains(UserAccountControl::ADS_UF_TRUSTED_TO_AUTHENTICATE_FOR_DELEGATION) {
+            kinds.push((
+                DelegationKind::ConstrainedWithProtocolTransition,
+                allowed_to_delegate_to.join(", "),
+            ));
+        } else if !allowed_to_delegate_to.is_empty() {
+            kinds.push((
+                DelegationKind::Constrained,
+                allowed_to_delegate_to.join(", "),
+            ));
+        }
+        kinds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::win32_types::UserAccountControl;
+
+    use super::{DelegationKind, KerberosDelegation};
+
+    #[test]
+    fn test_delegation_kinds() {
+        let targets = vec!["cifs/fs01".to_string(), "cifs/fs01.example.com".to_string()];
+        let kinds = |uac, targets: &[String]| -> Vec<DelegationKind> {
+            KerberosDelegation::kinds_of(&uac, targets)
+                .into_iter()
+                .map(|(kind, _)| kind)
+                .collect()
+        };
+
+        assert!(kinds(UserAccountControl::ADS_UF_NORMAL_ACCOUNT, &[]).is_empty());
+        assert!(
+            kinds(UserAccountControl::ADS_UF_NORMAL_ACCOUNT, &targets)
+                == vec![DelegationKind::Constrained]
+        );
+        assert!(
+            kinds(
+                UserAccountControl::ADS_UF_TRUSTED_TO_AUTHENTICATE_FOR_DELEGATION,
+                &targets
+            ) == vec![DelegationKind::ConstrainedWithProtocolTransition]
+        );
+        assert!(
+            kinds(
+                UserAccountControl::ADS_UF_TRUSTED_TO_AUTHENTICATE_FOR_DELEGATION,
+                &[]
+            ) == vec![DelegationKind::ConstrainedWithProtocolTransition]
+        );
+        assert!(
+            kinds(UserAccountControl::ADS_UF_TRUSTED_FOR_DELEGATION, &[])
+                == vec![DelegationKind::Unconstrained]
+        );
+    }
+
+    #[test]
+    fn test_domain_controllers() {
+        assert!(KerberosDelegation::is_domain_controller_account(
+            &UserAccountControl::ADS_UF_SERVER_TRUST_ACCOUNT
+        ));
+        assert!(KerberosDelegation::is_domain_controller_account(
+            &(UserAccountControl::ADS_UF_WORKSTATION_TRUST_ACCOUNT
+                | UserAccountControl::ADS_UF_PARTIAL_SECRETS_ACCOUNT)
+        ));
+        assert!(!KerberosDelegation::is_domain_controller_account(
+            &UserAccountControl::ADS_UF_WORKSTATION_TRUST_ACCOUNT
+        ));
+    }
+}
@@ -0,0 +1,49 @@
+use getset::Getters;
+
+use crate::cache::RecordId;
+use crate::win32_types::{LinkValueMetaData, TruncatedWindowsFileTime};
+
+/// a single row of the link table, which represents one value of a linked
+/// attribute
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub struct Link {
+    /// the object which holds the (forward) linked attribute
+    link_dnt: RecordId,
+
+    /// the object the attribute refers to
+    backlink_dnt: RecordId,
+
+    /// the binary part of DN-Binary values
+    data: Option<Vec<u8>>,
+
+    /// `true` if this link has been removed, but not yet been garbage
+    /// collected
+    is_deleted: bool,
+
+    /// time when this link has been removed
+    deletion_time: Option<TruncatedWindowsFileTime>,
+
+    /// when and where this link has been added or removed
+    metadata: Option<LinkValueMetaData>,
+}
+
+impl Link {
+    pub fn new(
+        link_dnt: RecordId,
+        backlink_dnt: RecordId,
+        data: Option<Vec<u8>>,
+        is_deleted: bool,
+        deletion_time: Option<TruncatedWindowsFileTime>,
+        metadata: Option<LinkValueMetaData>,
+    ) -> Self {
+        Self {
+            link_dnt,
+            backlink_dnt,
+            data,
+            is_deleted,
+            deletion_time,
+            metadata,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::cell::OnceCell;
 use std::collections::{HashMap, HashSet};
 
 use crate::cache::RecordPointer;
@@ -6,18 +7,25 @@ use crate::ntds::link_table_builder::LinkTableBuilder;
 use crate::win32_types::Rdn;
 use crate::{Membership, MembershipSet, SerializationType};
 
-use super::DataTable;
+use super::{DataTable, Link};
 
 /// wraps a ESEDB Table.
 /// This class assumes the a NTDS link_table is being wrapped
-pub struct LinkTable {
-    pub(crate) _forward_map: HashMap<RecordId, HashSet<RecordPointer>>,
+pub struct LinkTable<'info, 'db> {
+    pub(crate) forward_map: HashMap<RecordId, HashSet<RecordPointer>>,
     pub(crate) backward_map: HashMap<RecordId, HashSet<RecordPointer>>,
+
+    pub(crate) link_table: cache::LinkTable<'info, 'db>,
+
+    /// all values of all linked attributes, indexed by their link base
+    /// (which is the forward LinkID divided by 2). Most commands don't
+    /// need them, so they are read on first use
+    pub(crate) links: OnceCell<HashMap<u32, Vec<Link>>>,
 }
 
-impl LinkTable {
+impl<'info, 'db> LinkTable<'info, 'db> {
     /// create a new datatable wrapper
-    pub fn new<'info, 'db>(
+    pub fn new(
         link_table: cache::LinkTable<'info, 'db>,
         data_table: &cache::DataTable<'info, 'db>,
         schema_record_id: RecordPointer,
@@ -28,10 +36,46 @@ impl LinkTable {
         builder.build(data_table.metadata())
     }
 
+    /// returns all values of the linked attribute with the given
+    /// forward LinkID
+    pub fn links_of(&self, link_id: u32) -> impl Iterator<Item = &Link> {
+        self.links().get(&(link_id / 2)).into_iter().flatten()
+    }
+
+    /// returns all values of all linked attributes
+    pub fn all_links(&self) -> impl Iterator<Item = &Link> {
+        self.links().values().flatten()
+    }
+
+    fn links(&self) -> &HashMap<u32, Vec<Link>> {
+        self.links
+            .get_or_init(|| LinkTableBuilder::read_links(&self.link_table))
+    }
+
+    /// returns the direct members of a group, which are stored in the link
+    /// table
+    pub(crate) fn members(&self, dnt: &RecordId) -> Option<&HashSet<RecordPointer>> {
+        self.forward_map.get(dnt)
+    }
+
     pub(crate) fn member_of(&self, dnt: &RecordId) -> Option<&HashSet<RecordPointer>> {
         self.backward_map.get(dnt)
     }
 
+    /// returns all groups the object is a member of, including nested
+    /// memberships
+    pub fn transitive_member_of(&self, dnt: &RecordId) -> HashSet<RecordPointer> {
+        transitive_closure(&self.backward_map, dnt)
+    }
+
+    /// returns all members of the group, including members of nested
+    /// groups. Objects which have this group as their primary group are not
+    /// stored in the link table, and are therefore not included (see
+    /// [`DataTable::members_of`])
+    pub fn transitive_members_of(&self, dnt: &RecordId) -> HashSet<RecordPointer> {
+        transitive_closure(&self.forward_map, dnt)
+    }
+
     pub fn member_names_of(&self, object_id: RecordId, data_table: &DataTable<'_, '_>) -> Vec<Rdn> {
         let member_of = if let Some(children) = self.member_of(&object_id) {
             children
@@ -45,27 +89,54 @@ impl LinkTable {
         member_of
     }
 
+    /// returns all groups the object is a direct member of. The primary
+    /// group is not stored in the link table, so it must be passed
+    /// separately
     pub fn member_refs_of<T: SerializationType>(
         &self,
         object_id: RecordId,
+        primary_group: Option<&RecordPointer>,
         data_table: &DataTable<'_, '_>,
     ) -> MembershipSet<T> {
-        let member_of = if let Some(children) = self.member_of(&object_id) {
-            children
-                .iter()
-                .map(|child_id| &data_table.data_table().metadata()[child_id])
-                .map(|record| {
-                    (
-                        *record.record_ptr(),
-                        record.rdn().clone(),
-                        record.sid().clone(),
-                        record.sam_account_name().clone(),
-                    )
-                })
-                .collect()
-        } else {
-            vec![]
-        };
+        let mut groups: Vec<_> = self.member_of(&object_id).into_iter().flatten().collect();
+        if let Some(primary_group) = primary_group {
+            if !groups.contains(&primary_group) {
+                groups.push(primary_group);
+            }
+        }
+
+        let member_of: Vec<_> = groups
+            .into_iter()
+            .map(|child_id| &data_table.data_table().metadata()[child_id])
+            .map(|record| {
+                (
+                    *record.record_ptr(),
+                    record.rdn().clone(),
+                    record.sid().clone(),
+                    record.sam_account_name().clone(),
+                )
+            })
+            .collect();
         MembershipSet::<T>::from(member_of.into_iter().map(Membership::from))
     }
 }
+
+/// follows the links in `map`, starting at `dnt`, and returns all objects
+/// which are reachable this way
+pub(crate) fn transitive_closure(
+    map: &HashMap<RecordId, HashSet<RecordPointer>>,
+    dnt: &RecordId,
+) -> HashSet<RecordPointer> {
+    let mut result = HashSet::new();
+    let mut pending = vec![*dnt];
+    while let Some(dnt) = pending.pop() {
+        if let Some(next) = map.get(&dnt) {
+            for ptr in next {
+                if result.insert(*ptr) {
+                    pending.push(*ptr.ds_record_id());
+                }
+            }
+        }
+    }
+    result
+}
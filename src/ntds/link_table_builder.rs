@@ -1,21 +1,23 @@
+use std::cell::OnceCell;
 use std::collections::{HashMap, HashSet};
 
-use crate::cache::{self, MetaDataCache, RecordId, RecordPointer, Value, WithValue};
+use crate::cache::{self, ColumnIndex, MetaDataCache, RecordId, RecordPointer, Value, WithValue};
 use crate::ntds::Error;
 use crate::value::FromValue;
+use crate::win32_types::{LinkValueMetaData, TruncatedWindowsFileTime};
 
-use super::{LinkTable, NtdsAttributeId};
+use super::{Link, LinkTable, NtdsAttributeId};
 
-pub(crate) struct LinkTableBuilder<'info, 'db> {
+pub(crate) struct LinkTableBuilder<'info, 'db, 'd> {
     link_table: cache::LinkTable<'info, 'db>,
-    data_table: &'db cache::DataTable<'info, 'db>,
+    data_table: &'d cache::DataTable<'info, 'db>,
     schema_record_id: RecordPointer,
 }
 
-impl<'info, 'db> LinkTableBuilder<'info, 'db> {
+impl<'info, 'db, 'd> LinkTableBuilder<'info, 'db, 'd> {
     pub fn from(
         link_table: cache::LinkTable<'info, 'db>,
-        data_table: &'db cache::DataTable<'info, 'db>,
+        data_table: &'d cache::DataTable<'info, 'db>,
         schema_record_id: RecordPointer,
     ) -> crate::ntds::Result<Self> {
         Ok(Self {
@@ -25,7 +27,7 @@ impl<'info, 'db> LinkTableBuilder<'info, 'db> {
         })
     }
 
-    pub fn build(self, metadata: &MetaDataCache) -> crate::ntds::Result<LinkTable> {
+    pub fn build(self, metadata: &MetaDataCache) -> crate::ntds::Result<LinkTable<'info, 'db>> {
         log::info!("building link table associations");
 
         let (member_link_id, _member_of_link_id) = self.find_member_link_id_pair()?;
@@ -34,17 +36,14 @@ impl<'info, 'db> LinkTableBuilder<'info, 'db> {
         let backlink_dnt_id = self.link_table.backlink_dnt_id();
         let link_base_id = self.link_table.link_base_id();
 
-        let mut forward_map = HashMap::new();
-        let mut backward_map = HashMap::new();
+        let link_deltime_id = self.link_table.link_deltime_id();
+
+        let mut rows = Vec::new();
+        for record in self.link_table.iter() {
+            if Self::link_base_of(&record, link_base_id) != Some(link_base) {
+                continue;
+            }
 
-        for record in self.link_table.iter().filter(|r| {
-            r.with_value(*link_base_id, |value| match value {
-                Some(Value::U32(v)) => Ok(*v == member_link_id),
-                Some(Value::I32(v)) => Ok(u32::try_from(*v).map_or(false, |v| v == link_base)),
-                _ => Ok(false),
-            })
-            .unwrap_or(false)
-        }) {
             if let Ok(Some(forward_link)) = record.with_value(*link_dnt_id, |v| {
                 RecordId::from_value(v.unwrap())
                     .map(|id| {
@@ -65,18 +64,17 @@ impl<'info, 'db> LinkTableBuilder<'info, 'db> {
                             })
                         })
                 }) {
-                    forward_map
-                        .entry(*forward_link.ds_record_id())
-                        .or_insert_with(HashSet::new)
-                        .insert(*backward_link);
-                    backward_map
-                        .entry(*backward_link.ds_record_id())
-                        .or_insert_with(HashSet::new)
-                        .insert(*forward_link);
+                    rows.push((
+                        *forward_link,
+                        *backward_link,
+                        Self::is_absent(&record, link_deltime_id),
+                    ));
                 }
             }
         }
 
+        let (forward_map, backward_map) = membership_maps(rows);
+
         for (key, value) in forward_map.iter() {
             log::info!("found link {key} --> {value:?}");
         }
@@ -92,11 +90,128 @@ impl<'info, 'db> LinkTableBuilder<'info, 'db> {
         );
 
         Ok(LinkTable {
-            _forward_map: forward_map,
+            forward_map,
             backward_map,
+            link_table: self.link_table,
+            links: OnceCell::new(),
         })
     }
 
+    /// reads all values of all linked attributes, indexed by their link base
+    pub(crate) fn read_links(link_table: &cache::LinkTable<'_, '_>) -> HashMap<u32, Vec<Link>> {
+        log::info!("reading the values of all linked attributes");
+        let mut links: HashMap<u32, Vec<Link>> = HashMap::new();
+        for record in link_table.iter() {
+            if let Some(link_base) = Self::link_base_of(&record, link_table.link_base_id()) {
+                if let Some(link) = Self::link_from_record(
+                    &record,
+                    link_table.link_dnt_id(),
+                    link_table.backlink_dnt_id(),
+                    link_table.link_data_id(),
+                    link_table.link_deltime_id(),
+                    link_table.link_metadata_id(),
+                ) {
+                    links.entry(link_base).or_default().push(link);
+                }
+            }
+        }
+        log::debug!("found {} different linked attributes", links.len());
+        links
+    }
+
+    fn link_base_of(record: &cache::Record<'_, '_>, link_base_id: &ColumnIndex) -> Option<u32> {
+        record
+            .with_value(*link_base_id, |value| match value {
+                Some(Value::U32(v)) => Ok(Some(*v)),
+                Some(Value::I32(v)) => Ok(u32::try_from(*v).ok()),
+                _ => Ok(None),
+            })
+            .unwrap_or(None)
+    }
+
+    /// returns `true` if the link has been removed, i.e. if `link_deltime`
+    /// contains a non-zero value
+    fn is_absent(record: &cache::Record<'_, '_>, link_deltime_id: &Option<ColumnIndex>) -> bool {
+        link_deltime_id
+            .map(|id| {
+                record
+                    .with_value(id, |v| match v {
+                        None | Some(Value::Null(())) => Ok(false),
+                        Some(Value::Currency(0))
+                        | Some(Value::DateTime(0))
+                        | Some(Value::I64(0)) => Ok(false),
+                        Some(_) => Ok(true),
+                    })
+                    .unwrap_or(false)
+            })
+            .unwrap_or(false)
+    }
+
+    fn link_from_record(
+        record: &cache::Record<'_, '_>,
+        link_dnt_id: &ColumnIndex,
+        backlink_dnt_id: &ColumnIndex,
+        link_data_id: &Option<ColumnIndex>,
+        link_deltime_id: &Option<ColumnIndex>,
+        link_metadata_id: &Option<ColumnIndex>,
+    ) -> Option<Link> {
+        let link_dnt = record
+            .with_value(*link_dnt_id, |v| {
+                RecordId::from_value_opt(v.unwrap_or(&Value::Null(())))
+            })
+            .ok()??;
+        let backlink_dnt = record
+            .with_value(*backlink_dnt_id, |v| {
+                RecordId::from_value_opt(v.unwrap_or(&Value::Null(())))
+            })
+            .ok()??;
+
+        let data = link_data_id.and_then(|id| {
+            record
+                .with_value(id, |v| match v {
+                    Some(Value::Binary(v)) | Some(Value::LargeBinary(v)) | Some(Value::Long(v)) => {
+                        Ok(Some(v.as_ref().clone()))
+                    }
+                    _ => Ok(None),
+                })
+                .unwrap_or(None)
+        });
+
+        let is_deleted = Self::is_absent(record, link_deltime_id);
+
+        let deletion_time = link_deltime_id.and_then(|id| {
+            record
+                .with_value(id, |v| Ok(deletion_time_of(v)))
+                .unwrap_or(None)
+        });
+
+        let metadata = link_metadata_id.and_then(|id| {
+            record
+                .with_value(id, |v| match v {
+                    Some(Value::Binary(v)) | Some(Value::LargeBinary(v)) | Some(Value::Long(v)) => {
+                        match LinkValueMetaData::try_from(&v[..]) {
+                            Ok(metadata) => Ok(Some(metadata)),
+                            Err(why) => {
+                                log::warn!("unable to parse link metadata: {why}");
+                                Ok(None)
+                            }
+                        }
+                    }
+                    _ => Ok(None),
+                })
+                .unwrap_or(None)
+        });
+
+        Some(Link::new(
+            link_dnt,
+            backlink_dnt,
+            data,
+            is_deleted,
+            deletion_time,
+            metadata,
+        ))
+    }
+
     fn find_member_link_id_pair(&self) -> crate::ntds::Result<(u32, u32)> {
         log::info!("searching for link attributes 'Member' and 'Is-Member-Of-DL'");
 
@@ -112,7 +227,8 @@ impl<'info, 'db> LinkTableBuilder<'info, 'db> {
 
         if member_link_id + 1 != member_of_link_id {
             return Err(Error::InvalidLinkIdValues {
-                member_link_id, member_of_link_id
+                member_link_id,
+                member_of_link_id,
             });
         }
 
@@ -137,3 +253,108 @@ impl<'info, 'db> LinkTableBuilder<'info, 'db> {
             .unwrap_or_else(|| panic!("missing link-id attribute in {attribute_name}")))
     }
 }
+
+/// creates the maps of group memberships from `(group, member, is_absent)`
+/// triples. Removed memberships (absent links) are kept in the link table
+/// until they are garbage collected, but they must not be treated as
+/// memberships
+fn membership_maps(
+    rows: impl IntoIterator<Item = (RecordPointer, RecordPointer, bool)>,
+) -> (
+    HashMap<RecordId, HashSet<RecordPointer>>,
+    HashMap<RecordId, HashSet<RecordPointer>>,
+) {
+    let mut forward_map = HashMap::new();
+    let mut backward_map = HashMap::new();
+    for (forward_link, backward_link, is_absent) in rows {
+        if is_absent {
+            log::debug!("ignoring removed membership {forward_link} --> {backward_link}");
+            continue;
+        }
+        forward_map
+            .entry(*forward_link.ds_record_id())
+            .or_insert_with(HashSet::new)
+            .insert(backward_link);
+        backward_map
+            .entry(*backward_link.ds_record_id())
+            .or_insert_with(HashSet::new)
+            .insert(forward_link);
+    }
+    (forward_map, backward_map)
+}
+
+/// reads `link_deltime`, which contains the number of seconds since
+/// 1601-01-01. Depending on the database, the column has one of several
+/// 64 bit types
+fn deletion_time_of(value: Option<&Value>) -> Option<TruncatedWindowsFileTime> {
+    let seconds = match value? {
+        Value::Currency(v) | Value::I64(v) => u64::try_from(*v).ok()?,
+        Value::DateTime(v) => *v,
+        _ => return None,
+    };
+    if seconds == 0 {
+        return None;
+    }
+    match TruncatedWindowsFileTime::try_from(seconds) {
+        Ok(ts) => Some(ts),
+        Err(why) => {
+            log::warn!("invalid value of link_deltime: {why}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use crate::cache::{RecordPointer, Value};
+    use crate::ntds::link_table::transitive_closure;
+
+    use super::{deletion_time_of, membership_maps};
+
+    fn rfc3339(value: &Value) -> Option<String> {
+        deletion_time_of(Some(value)).map(|ts| DateTime::<Utc>::from(ts).to_rfc3339())
+    }
+
+    #[test]
+    fn test_deletion_time() {
+        let expected = Some("2025-04-30T07:40:01+00:00".to_string());
+        assert_eq!(rfc3339(&Value::Currency(13390472401)), expected);
+        assert_eq!(rfc3339(&Value::I64(13390472401)), expected);
+        assert_eq!(rfc3339(&Value::DateTime(13390472401)), expected);
+    }
+
+    #[test]
+    fn test_no_deletion_time() {
+        assert!(deletion_time_of(None).is_none());
+        assert!(rfc3339(&Value::Null(())).is_none());
+        assert!(rfc3339(&Value::Currency(0)).is_none());
+        assert!(rfc3339(&Value::DateTime(0)).is_none());
+        assert!(rfc3339(&Value::I64(-1)).is_none());
+        assert!(rfc3339(&Value::DateTime(u64::MAX)).is_none());
+    }
+
+    #[test]
+    fn test_absent_link_is_no_membership() {
+        let domain_admins = RecordPointer::new(1.into(), 1.into());
+        let helpdesk = RecordPointer::new(2.into(), 2.into());
+        let user = RecordPointer::new(3.into(), 3.into());
+
+        // both the user and "Helpdesk" have been removed from "Domain Admins"
+        let (forward_map, backward_map) = membership_maps([
+            (domain_admins, user, true),
+            (domain_admins, helpdesk, true),
+            (helpdesk, user, false),
+        ]);
+
+        let groups = transitive_closure(&backward_map, user.ds_record_id());
+        assert!(groups.contains(&helpdesk));
+        assert!(!groups.contains(&domain_admins));
+
+        let members = transitive_closure(&forward_map, domain_admins.ds_record_id());
+        assert!(members.is_empty());
+        let members = transitive_closure(&forward_map, helpdesk.ds_record_id());
+        assert!(members.contains(&user));
+    }
+}
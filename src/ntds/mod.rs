@@ -1,29 +1,77 @@
+mod changed_object;
 mod data_table;
 mod link_table;
+mod link;
 mod sd_table;
+mod anomaly;
 mod attribute_id;
 mod link_table_builder;
 mod object_type;
 mod data_table_record;
+mod dns;
+mod domain_info;
+mod deleted_object;
 mod error;
 mod from_data_table;
+mod group_policy;
 mod object;
 mod schema;
 mod attribute_name;
 mod attribute_value;
 mod attribute_id_impl;
+mod certificate_services;
 mod is_member_of;
+mod schema_guids;
+mod ou_delegation;
+mod ownership;
+mod password_settings;
+mod primary_group;
+mod kerberos_delegation;
+mod replication_conflict;
+mod replication_topology;
+mod rodc_password_policy;
+mod roastable_account;
+mod shadow_credential;
+mod snapshot_diff;
+mod sid_history;
+mod timeline_event;
+mod timeline_filter;
+mod timeline_object;
 
+pub use changed_object::*;
 pub use data_table::*;
 pub use link_table::*;
+pub use link::*;
 pub use sd_table::*;
 pub use attribute_id::*;
 pub use object_type::*;
 pub use data_table_record::*;
+pub use dns::*;
+pub use deleted_object::*;
+pub use domain_info::*;
 pub use error::*;
 pub use from_data_table::*;
+pub use group_policy::*;
 pub use object::*;
 pub use schema::*;
+pub use anomaly::*;
 pub use attribute_name::*;
 pub use attribute_value::*;
+pub use certificate_services::*;
 pub use is_member_of::*;
+pub use schema_guids::*;
+pub use ou_delegation::*;
+pub use ownership::*;
+pub use password_settings::*;
+pub use primary_group::*;
+pub use kerberos_delegation::*;
+pub use replication_conflict::*;
+pub use replication_topology::*;
+pub use rodc_password_policy::*;
+pub use roastable_account::*;
+pub use shadow_credential::*;
+pub use snapshot_diff::*;
+pub use sid_history::*;
+pub use timeline_event::*;
+pub use timeline_filter::*;
+pub use timeline_object::*;
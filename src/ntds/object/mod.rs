@@ -6,6 +6,7 @@ mod has_serializable_fields;
 mod object_computer;
 mod object_group;
 mod object_person;
+mod object_trusted_domain;
 
 pub use object_base::*;
 pub use specific_object_attribute::*;
@@ -14,4 +15,5 @@ pub use has_serializable_fields::*;
 
 pub use object_computer::*;
 pub use object_group::*;
-pub use object_person::*;
\ No newline at end of file
+pub use object_person::*;
+pub use object_trusted_domain::*;
\ No newline at end of file
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::ntds::DataTableRecord;
+use crate::ntds::{DataTable, DataTableRecord};
 
 use super::{HasSerializableFields, SpecificObjectAttributes};
 
@@ -14,7 +14,7 @@ impl HasSerializableFields for NoSpecificAttributes {
 }
 
 impl SpecificObjectAttributes for NoSpecificAttributes {
-    fn from(_record: &DataTableRecord) -> anyhow::Result<Self> {
+    fn from(_record: &DataTableRecord, _data_table: &DataTable) -> anyhow::Result<Self> {
         Ok(Self)
     }
 
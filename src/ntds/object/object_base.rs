@@ -1,9 +1,8 @@
 use crate::cache::RecordPointer;
 use crate::cli::OutputOptions;
-use crate::win32_types::{Rdn, SecurityDescriptor, TimelineEntry, TruncatedWindowsFileTime, WindowsFileTime};
+use crate::win32_types::{Rdn, SecurityDescriptor, TruncatedWindowsFileTime, WindowsFileTime};
 use crate::win32_types::{SamAccountType, Sid, UserAccountControl};
-use crate::{FormattedValue, Membership, MembershipSet, SerializationType};
-use bodyfile::Bodyfile3Line;
+use crate::{FormattedValue, Membership, MembershipSet, SerializationType, StringList};
 use getset::Getters;
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize};
@@ -28,7 +27,7 @@ where
 
     sid: Option<Sid>,
     user_principal_name: Option<String>,
-    service_principal_name: Option<String>,
+    service_principal_name: StringList<T>,
     rdn: Option<Rdn>,
     sam_account_name: Option<String>,
     sam_account_type: Option<SamAccountType>,
@@ -177,40 +176,26 @@ where
         let object_id = dbrecord.ds_record_id()?;
 
         let primary_group_id = dbrecord.att_primary_group_id().ok();
-        let primary_group = primary_group_id.and_then(|group_id| {
-            match data_table
-                .data_table()
-                .metadata()
-                .entries_with_rid(group_id.try_into().unwrap())
-                .next() // there should be at most one entry with this rid
-                .map(|e| {
-                    data_table
-                        .data_table()
-                        .data_table_record_from(*e.record_ptr())
-                        .unwrap()
-                }) {
-                Some(group) => {
-                    let rdn = group.att_object_name2().unwrap();
-                    let sid = group.att_object_sid_opt().unwrap();
-                    let dn = data_table.object_tree().dn_of(group.ptr());
-                    let sam_account_name = group.att_sam_account_name_opt().unwrap();
-                    if let Some(dn) = dn {
-                        Some(Membership::<T>::from((dn, rdn, sid, sam_account_name)))
-                    } else {
-                        Some(Membership::<T>::from((
-                            *group.ptr(),
-                            rdn,
-                            sid,
-                            sam_account_name,
-                        )))
-                    }
-                }
-                None => None,
+        let primary_group_ptr = primary_group_id
+            .and_then(|group_id| u32::try_from(group_id).ok())
+            .and_then(|group_id| data_table.group_with_rid(group_id))
+            .map(|e| *e.record_ptr());
+        let primary_group = primary_group_ptr.map(|ptr| {
+            let group = data_table.data_table().data_table_record_from(ptr).unwrap();
+            let rdn = group.att_object_name2().unwrap();
+            let sid = group.att_object_sid_opt().unwrap();
+            let dn = data_table.object_tree().dn_of(group.ptr());
+            let sam_account_name = group.att_sam_account_name_opt().unwrap();
+            if let Some(dn) = dn {
+                Membership::<T>::from((dn, rdn, sid, sam_account_name))
+            } else {
+                Membership::<T>::from((*group.ptr(), rdn, sid, sam_account_name))
             }
         });
 
-        let member_refs = link_table.member_refs_of::<T>(object_id, data_table);
-        let specific_attributes = A::from(&dbrecord)?;
+        let member_refs =
+            link_table.member_refs_of::<T>(object_id, primary_group_ptr.as_ref(), data_table);
+        let specific_attributes = A::from(&dbrecord, data_table)?;
 
         Ok(Self {
             distinguished_name,
@@ -221,7 +206,9 @@ where
             sam_account_name: dbrecord.att_sam_account_name().ok(),
             rdn: dbrecord.att_object_name2().ok(),
             user_principal_name: dbrecord.att_user_principal_name().ok(),
-            service_principal_name: dbrecord.att_service_principal_name().ok(),
+            service_principal_name: StringList::from(
+                dbrecord.att_service_principal_name().unwrap_or_default(),
+            ),
             sam_account_type: dbrecord.att_sam_account_type().ok(),
             user_account_control: dbrecord.att_user_account_control().ok(),
             last_logon: dbrecord.att_last_logon().ok(),
@@ -246,64 +233,6 @@ where
     }
 }
 
-impl<T, O, A> From<Object<T, O, A>> for Vec<Bodyfile3Line>
-where
-    O: HasObjectType,
-    T: SerializationType,
-    A: SpecificObjectAttributes,
-{
-    fn from(obj: Object<T, O, A>) -> Self {
-        let object_type = O::object_type();
-        let upn = match obj.sam_account_name() {
-            Some(n) => Some(n.to_string()),
-            None => obj
-                .rdn()
-                .as_ref()
-                .map(|n| n.name().to_string())
-                .or(Some("UNNAMED_OBJECT".to_string())),
-        };
-        let inode = obj.ptr().ds_record_id().to_string();
-
-        if let Some(upn) = upn {
-            vec![
-                obj.record_time().as_ref().map(|ts| {
-                    ts.cr_entry(&upn, "record creation time", object_type)
-                        .with_inode(&inode)
-                }),
-                obj.when_created().as_ref().map(|ts| {
-                    ts.cr_entry(&upn, "object created", object_type)
-                        .with_inode(&inode)
-                }),
-                obj.when_changed().as_ref().map(|ts| {
-                    ts.c_entry(&upn, "object changed", object_type)
-                        .with_inode(&inode)
-                }),
-                obj.last_logon().as_ref().map(|ts| {
-                    ts.a_entry(&upn, "last logon on this DC", object_type)
-                        .with_inode(&inode)
-                }),
-                obj.last_logon_time_stamp().as_ref().map(|ts| {
-                    ts.c_entry(&upn, "last logon on any DC", object_type)
-                        .with_inode(&inode)
-                }),
-                obj.bad_pwd_time().as_ref().map(|ts| {
-                    ts.c_entry(&upn, "bad pwd time", object_type)
-                        .with_inode(&inode)
-                }),
-                obj.password_last_set().as_ref().map(|ts| {
-                    ts.m_entry(&upn, "password last set", object_type)
-                        .with_inode(&inode)
-                }),
-            ]
-            .into_iter()
-            .flatten()
-            .collect()
-        } else {
-            Vec::new()
-        }
-    }
-}
-
 impl<T, O, A> IsMemberOf for Object<T, O, A>
 where
     O: HasObjectType,
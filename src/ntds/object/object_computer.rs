@@ -22,7 +22,10 @@ impl HasSerializableFields for SpecificComputerAttributes {
 }
 
 impl SpecificObjectAttributes for SpecificComputerAttributes {
-    fn from(record: &crate::ntds::DataTableRecord) -> anyhow::Result<Self> {
+    fn from(
+        record: &crate::ntds::DataTableRecord,
+        _data_table: &crate::ntds::DataTable,
+    ) -> anyhow::Result<Self> {
         let creator_sid = record.att_creator_sid_opt()?;
         Ok(Self { creator_sid })
     }
@@ -1,4 +1,66 @@
-use crate::ntds::{types, NoSpecificAttributes, Object};
+use lazy_static::lazy_static;
+use serde::{ser::SerializeStruct, Deserialize, Serialize};
 
+use crate::win32_types::WindowsFileTime;
 
-pub type Person<T> = Object<T, types::Person, NoSpecificAttributes>;
\ No newline at end of file
+use crate::ntds::{types, HasSerializableFields, Object, PasswordSettings};
+
+use super::SpecificObjectAttributes;
+
+#[derive(Deserialize, Serialize)]
+pub struct SpecificPersonAttributes {
+    password_policy: Option<String>,
+    password_expires: Option<WindowsFileTime>,
+}
+
+impl HasSerializableFields for SpecificPersonAttributes {
+    fn fields() -> &'static Vec<&'static str> {
+        lazy_static! {
+            static ref PERSON_HEADER: Vec<&'static str> =
+                vec!["password_policy", "password_expires"];
+        }
+        &PERSON_HEADER
+    }
+}
+
+impl SpecificObjectAttributes for SpecificPersonAttributes {
+    fn from(
+        record: &crate::ntds::DataTableRecord,
+        data_table: &crate::ntds::DataTable,
+    ) -> anyhow::Result<Self> {
+        let settings = data_table.password_settings()?;
+        let policy = PasswordSettings::resultant(
+            settings,
+            data_table,
+            record.ptr(),
+            record.att_primary_group_id_opt()?,
+        );
+
+        let password_expires = policy.and_then(|policy| {
+            policy.password_expires(
+                record.att_password_last_set_opt().ok().flatten().as_ref(),
+                record
+                    .att_user_account_control_opt()
+                    .ok()
+                    .flatten()
+                    .as_ref(),
+            )
+        });
+
+        Ok(Self {
+            password_policy: policy.map(|p| p.name().clone()),
+            password_expires,
+        })
+    }
+
+    fn serialize_to<S>(&self, s: &mut S::SerializeStruct) -> Result<(), S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.serialize_field("password_policy", &self.password_policy)?;
+        s.serialize_field("password_expires", &self.password_expires)?;
+        Ok(())
+    }
+}
+
+pub type Person<T> = Object<T, types::Person, SpecificPersonAttributes>;
@@ -0,0 +1,113 @@
+use lazy_static::lazy_static;
+use serde::{ser::SerializeStruct, Deserialize, Serialize};
+
+use crate::win32_types::{Sid, TrustAttributes, TrustDirection, TrustType, UserAccountControl};
+
+use crate::ntds::{types, DataTable, HasSerializableFields, Object};
+
+use super::SpecificObjectAttributes;
+
+#[derive(Deserialize, Serialize)]
+pub struct SpecificTrustedDomainAttributes {
+    trust_partner: Option<String>,
+    flat_name: Option<String>,
+    partner_sid: Option<Sid>,
+    trust_direction: Option<TrustDirection>,
+    trust_type: Option<TrustType>,
+    trust_attributes: Option<TrustAttributes>,
+    sid_filtering: bool,
+    selective_authentication: bool,
+    tgt_delegation: bool,
+    trust_account: Option<String>,
+}
+
+impl HasSerializableFields for SpecificTrustedDomainAttributes {
+    fn fields() -> &'static Vec<&'static str> {
+        lazy_static! {
+            static ref TRUSTED_DOMAIN_HEADER: Vec<&'static str> = vec![
+                "trust_partner",
+                "flat_name",
+                "partner_sid",
+                "trust_direction",
+                "trust_type",
+                "trust_attributes",
+                "sid_filtering",
+                "selective_authentication",
+                "tgt_delegation",
+                "trust_account",
+            ];
+        }
+        &TRUSTED_DOMAIN_HEADER
+    }
+}
+
+impl SpecificObjectAttributes for SpecificTrustedDomainAttributes {
+    fn from(
+        record: &crate::ntds::DataTableRecord,
+        data_table: &crate::ntds::DataTable,
+    ) -> anyhow::Result<Self> {
+        let flat_name = record.att_flat_name_opt()?;
+        let trust_attributes = record.att_trust_attributes_opt()?;
+        let attributes = trust_attributes.unwrap_or(TrustAttributes::empty());
+
+        let trust_account = match &flat_name {
+            Some(flat_name) => Self::trust_account_of(data_table, flat_name)?,
+            None => None,
+        };
+
+        Ok(Self {
+            trust_partner: record.att_trust_partner_opt()?,
+            partner_sid: record.att_security_identifier_opt()?,
+            trust_direction: record.att_trust_direction_opt()?,
+            trust_type: record.att_trust_type_opt()?,
+            sid_filtering: attributes.sid_filtering(),
+            selective_authentication: attributes.selective_authentication(),
+            tgt_delegation: attributes.tgt_delegation(),
+            trust_attributes,
+            flat_name,
+            trust_account,
+        })
+    }
+
+    fn serialize_to<S>(&self, s: &mut S::SerializeStruct) -> Result<(), S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.serialize_field("trust_partner", &self.trust_partner)?;
+        s.serialize_field("flat_name", &self.flat_name)?;
+        s.serialize_field("partner_sid", &self.partner_sid)?;
+        s.serialize_field("trust_direction", &self.trust_direction)?;
+        s.serialize_field("trust_type", &self.trust_type)?;
+        s.serialize_field("trust_attributes", &self.trust_attributes)?;
+        s.serialize_field("sid_filtering", &self.sid_filtering)?;
+        s.serialize_field("selective_authentication", &self.selective_authentication)?;
+        s.serialize_field("tgt_delegation", &self.tgt_delegation)?;
+        s.serialize_field("trust_account", &self.trust_account)?;
+        Ok(())
+    }
+}
+
+impl SpecificTrustedDomainAttributes {
+    /// the interdomain trust account of an inbound trust is named after the
+    /// NetBIOS name of the trusting domain
+    fn trust_account_of(data_table: &DataTable, flat_name: &str) -> anyhow::Result<Option<String>> {
+        let account_name = format!("{flat_name}$");
+        for entry in data_table.data_table().metadata().iter().filter(|e| {
+            e.sam_account_name()
+                .as_ref()
+                .is_some_and(|n| n.eq_ignore_ascii_case(&account_name))
+        }) {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+            if record.att_user_account_control_opt()?.is_some_and(|uac| {
+                uac.contains(UserAccountControl::ADS_UF_INTERDOMAIN_TRUST_ACCOUNT)
+            }) {
+                return Ok(entry.sam_account_name().clone());
+            }
+        }
+        Ok(None)
+    }
+}
+
+pub type TrustedDomain<T> = Object<T, types::TrustedDomain, SpecificTrustedDomainAttributes>;
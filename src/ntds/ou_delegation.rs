@@ -0,0 +1,247 @@
+use getset::Getters;
+use sddl::AccessMask;
+use serde::Serialize;
+
+use crate::cache::RecordPointer;
+use crate::win32_types::{AccessControlEntry, AccessControlType, Sid};
+
+use super::{DataTable, SchemaGuids};
+
+/// classes of objects whose ACLs are being analyzed
+const CONTAINER_CLASSES: [&str; 3] = ["Organizational-Unit", "Container", "Domain-DNS"];
+
+/// builtin RIDs (`S-1-5-32-*`) which have permissions on containers by default
+const DEFAULT_BUILTIN_RIDS: [u32; 7] = [544, 548, 550, 554, 557, 560, 561];
+
+/// domain RIDs which have permissions on containers by default
+const DEFAULT_DOMAIN_RIDS: [u32; 10] = [498, 512, 516, 517, 518, 519, 521, 526, 527, 553];
+
+/// an explicit ACE on an organizational unit or container, which grants a
+/// non-default principal rights over child objects
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct OuDelegation {
+    distinguished_name: String,
+    object_class: String,
+    trustee_sid: Sid,
+    trustee: Option<String>,
+    rights: String,
+    object_type: Option<String>,
+    applies_to: Option<String>,
+    scope: &'static str,
+
+    #[serde(skip)]
+    ptr: RecordPointer,
+}
+
+impl OuDelegation {
+    /// collects the delegations of all organizational units and containers
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let sd_table = match data_table.sd_table() {
+            Some(sd_table) => sd_table,
+            None => {
+                log::error!("security descriptors are not available");
+                return Ok(Vec::new());
+            }
+        };
+
+        let guids = SchemaGuids::new(data_table)?;
+        let metadata = data_table.data_table().metadata();
+        let mut delegations = Vec::new();
+
+        for class_name in CONTAINER_CLASSES {
+            let class_ptr = match data_table.schema().type_entry(class_name) {
+                Some(ptr) => ptr,
+                None => {
+                    log::warn!("the schema has no class named '{class_name}'");
+                    continue;
+                }
+            };
+
+            for entry in metadata.entries_of_type(class_ptr.ds_record_id()) {
+                let sd = match entry
+                    .sd_id()
+                    .as_ref()
+                    .and_then(|id| sd_table.descriptor(id))
+                {
+                    Some(Ok(sd)) => sd,
+                    Some(Err(why)) => {
+                        log::warn!("unable to read the security descriptor of {entry}: {why}");
+                        continue;
+                    }
+                    None => continue,
+                };
+
+                let distinguished_name = match data_table.object_tree().dn_of(entry.record_ptr()) {
+                    Some(dn) => dn,
+                    None => continue,
+                };
+
+                for ace in sd.dacl_entries().into_iter().filter(Self::is_delegation) {
+                    delegations.push(Self {
+                        distinguished_name: distinguished_name.clone(),
+                        object_class: class_name.to_string(),
+                        trustee: data_table
+                            .principal_name(ace.trustee())
+                            .or(ace.well_known_name().map(String::from)),
+                        rights: ace.rights().join("|"),
+                        object_type: ace.object_type().as_ref().map(|g| guids.display_name(g)),
+                        applies_to: ace
+                            .inherited_object_type()
+                            .as_ref()
+                            .map(|g| guids.display_name(g)),
+                        scope: Self::scope_of(&ace),
+                        trustee_sid: ace.trustee().clone(),
+                        ptr: *entry.record_ptr(),
+                    });
+                }
+            }
+        }
+
+        delegations.sort_by(|lhs, rhs| lhs.distinguished_name.cmp(&rhs.distinguished_name));
+        Ok(delegations)
+    }
+
+    fn is_delegation(ace: &AccessControlEntry) -> bool {
+        *ace.access_type() == AccessControlType::Allow
+            && !ace.is_inherited()
+            && !Self::is_default_principal(ace.trustee())
+            && (ace.is_inheritable()
+                || ace
+                    .mask()
+                    .intersects(AccessMask::CREATE_CHILD | AccessMask::DELETE_CHILD))
+    }
+
+    fn is_default_principal(sid: &Sid) -> bool {
+        match (sid.authority(), sid.sub_authorities()) {
+            // Everyone
+            (1, [0]) => true,
+
+            // Creator Owner
+            (3, [0]) => true,
+
+            // Enterprise Domain Controllers, Self, Authenticated Users, Local System
+            (5, [9 | 10 | 11 | 18]) => true,
+
+            (5, [32, rid]) => DEFAULT_BUILTIN_RIDS.contains(rid),
+            (5, [21, _, _, _, rid]) => DEFAULT_DOMAIN_RIDS.contains(rid),
+            _ => false,
+        }
+    }
+
+    fn scope_of(ace: &AccessControlEntry) -> &'static str {
+        let no_propagate = ace
+            .flags()
+            .contains(sddl::AceHeaderFlags::NO_PROPAGATE_INHERIT_ACE);
+        match (ace.is_inheritable(), ace.is_inherit_only(), no_propagate) {
+            (false, _, _) => "this object",
+            (true, true, true) => "children",
+            (true, true, false) => "descendants",
+            (true, false, true) => "this object and children",
+            (true, false, false) => "this object and descendants",
+        }
+    }
+
+    /// short description which is used in the tree view
+    pub fn summary(&self) -> String {
+        let trustee = self
+            .trustee
+            .clone()
+            .unwrap_or_else(|| self.trustee_sid.to_string());
+        let object_type = self
+            .object_type
+            .as_ref()
+            .map(|o| format!(" ({o})"))
+            .unwrap_or_default();
+        let applies_to = self.applies_to.as_deref().unwrap_or("all objects");
+        format!(
+            "{trustee}: {}{object_type} on {applies_to}, {}",
+            self.rights, self.scope
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sddl::{AccessMask, Ace, AceHeaderFlags};
+
+    use crate::win32_types::AccessControlEntry;
+
+    use super::OuDelegation;
+
+    fn ace(flags: AceHeaderFlags, mask: AccessMask, sid: &str) -> AccessControlEntry {
+        AccessControlEntry::from_ace(&Ace::access_allowed(
+            flags,
+            mask,
+            sddl::Sid::try_from(sid).unwrap(),
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_delegations() {
+        let inheritable = AceHeaderFlags::CONTAINER_INHERIT_ACE;
+        let helpdesk = "S-1-5-21-1-2-3-1104";
+
+        // password resets on all descendants
+        assert!(OuDelegation::is_delegation(&ace(
+            inheritable | AceHeaderFlags::INHERIT_ONLY_ACE,
+            AccessMask::CONTROL_ACCESS,
+            helpdesk
+        )));
+
+        // creation of child objects, even if the ACE is not inheritable
+        assert!(OuDelegation::is_delegation(&ace(
+            AceHeaderFlags::empty(),
+            AccessMask::CREATE_CHILD,
+            helpdesk
+        )));
+
+        // rights on the OU itself only
+        assert!(!OuDelegation::is_delegation(&ace(
+            AceHeaderFlags::empty(),
+            AccessMask::READ_PROPERTY,
+            helpdesk
+        )));
+
+        // inherited ACEs are explicit on some parent object
+        assert!(!OuDelegation::is_delegation(&ace(
+            inheritable | AceHeaderFlags::INHERITED_ACE,
+            AccessMask::GENERIC_ALL,
+            helpdesk
+        )));
+
+        // default principals: Domain Admins, Account Operators, Authenticated Users
+        for sid in ["S-1-5-21-1-2-3-512", "S-1-5-32-548", "S-1-5-11"] {
+            assert!(!OuDelegation::is_delegation(&ace(
+                inheritable,
+                AccessMask::GENERIC_ALL,
+                sid
+            )));
+        }
+    }
+
+    #[test]
+    fn test_scope() {
+        let scope = |flags| {
+            OuDelegation::scope_of(&ace(flags, AccessMask::GENERIC_ALL, "S-1-5-21-1-2-3-1104"))
+        };
+        assert_eq!(scope(AceHeaderFlags::empty()), "this object");
+        assert_eq!(
+            scope(AceHeaderFlags::CONTAINER_INHERIT_ACE),
+            "this object and descendants"
+        );
+        assert_eq!(
+            scope(AceHeaderFlags::CONTAINER_INHERIT_ACE | AceHeaderFlags::INHERIT_ONLY_ACE),
+            "descendants"
+        );
+        assert_eq!(
+            scope(
+                AceHeaderFlags::CONTAINER_INHERIT_ACE
+                    | AceHeaderFlags::INHERIT_ONLY_ACE
+                    | AceHeaderFlags::NO_PROPAGATE_INHERIT_ACE
+            ),
+            "children"
+        );
+    }
+}
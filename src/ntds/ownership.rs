@@ -0,0 +1,305 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use getset::Getters;
+use serde::Serialize;
+use strum::Display;
+
+use crate::win32_types::Sid;
+
+use super::DataTable;
+
+/// classes of objects which deserve special attention
+const GPO_CLASS: &str = "Group-Policy-Container";
+const COMPUTER_CLASS: &str = "Computer";
+const GROUP_CLASS: &str = "Group";
+
+/// kinds of objects whose owner deserves special attention, ordered by their
+/// priority
+#[derive(Display, Serialize, Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum OwnedObjectCategory {
+    /// one of the highly privileged groups, e.g. Domain Admins
+    #[strum(serialize = "privileged group")]
+    #[serde(rename = "privileged group")]
+    PrivilegedGroup,
+
+    #[strum(serialize = "gpo")]
+    #[serde(rename = "gpo")]
+    Gpo,
+
+    #[strum(serialize = "computer")]
+    #[serde(rename = "computer")]
+    Computer,
+
+    #[strum(serialize = "other")]
+    #[serde(rename = "other")]
+    Other,
+}
+
+impl OwnedObjectCategory {
+    fn of(object_class: Option<&str>, sid: Option<&Sid>) -> Self {
+        match object_class {
+            Some(COMPUTER_CLASS) => Self::Computer,
+            Some(GPO_CLASS) => Self::Gpo,
+            Some(GROUP_CLASS) if sid.is_some_and(Sid::is_privileged) => Self::PrivilegedGroup,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// an object whose owner is not one of Domain Admins, Enterprise Admins,
+/// Local System or Administrators
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct NonDefaultOwner {
+    distinguished_name: Option<String>,
+    object_class: Option<String>,
+    category: OwnedObjectCategory,
+    owner_sid: Sid,
+    owner: Option<String>,
+}
+
+impl NonDefaultOwner {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let sd_table = match data_table.sd_table() {
+            Some(sd_table) => sd_table,
+            None => {
+                log::error!("security descriptors are not available");
+                return Ok(Vec::new());
+            }
+        };
+
+        let metadata = data_table.data_table().metadata();
+
+        // a lot of objects share the same security descriptor, so we cache the owners
+        let mut owners: HashMap<i64, Option<Sid>> = HashMap::new();
+        let mut result = Vec::new();
+
+        for entry in metadata.iter() {
+            // deleted objects have no object category
+            if entry.object_category().is_none() {
+                continue;
+            }
+
+            let sd_id = match entry.sd_id() {
+                Some(sd_id) => sd_id,
+                None => continue,
+            };
+
+            let owner = owners
+                .entry(*sd_id)
+                .or_insert_with(|| match sd_table.descriptor(sd_id) {
+                    Some(Ok(sd)) => sd.owner(),
+                    Some(Err(why)) => {
+                        log::warn!("unable to read the security descriptor of {entry}: {why}");
+                        None
+                    }
+                    None => None,
+                });
+
+            let owner_sid = match owner {
+                Some(owner_sid) if !Self::is_default_owner(owner_sid) => owner_sid.clone(),
+                _ => continue,
+            };
+
+            let object_class = metadata.object_class_name(entry);
+            let category = OwnedObjectCategory::of(object_class, entry.sid().as_ref());
+
+            result.push(Self {
+                distinguished_name: data_table.object_tree().dn_of(entry.record_ptr()),
+                object_class: object_class.map(String::from),
+                category,
+                owner: data_table.principal_name(&owner_sid),
+                owner_sid,
+            });
+        }
+
+        result.sort_by_key(|owner| owner.category);
+        Ok(result)
+    }
+
+    fn is_default_owner(sid: &Sid) -> bool {
+        sid.is_local_system()
+            || (sid.is_domain_sid() && [512, 519].contains(sid.get_rid()))
+            || (sid.is_builtin_sid() && *sid.get_rid() == 544)
+    }
+}
+
+/// a principal which created computer accounts using its
+/// `ms-DS-MachineAccountQuota`
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct MachineCreator {
+    creator_sid: Sid,
+    creator: Option<String>,
+    count: usize,
+    computers: String,
+}
+
+impl MachineCreator {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let computer_class = match data_table.schema().type_entry(COMPUTER_CLASS) {
+            Some(ptr) => ptr,
+            None => {
+                log::error!("the schema has no class named '{COMPUTER_CLASS}'");
+                return Ok(Vec::new());
+            }
+        };
+
+        let metadata = data_table.data_table().metadata();
+        let mut computers_of: HashMap<Sid, Vec<String>> = HashMap::new();
+
+        for entry in metadata.entries_of_type(computer_class.ds_record_id()) {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+            if let Some(creator_sid) = record.att_creator_sid_opt()? {
+                let name = entry
+                    .sam_account_name()
+                    .clone()
+                    .unwrap_or_else(|| entry.rdn().name().to_string());
+                computers_of.entry(creator_sid).or_default().push(name);
+            }
+        }
+
+        Ok(Self::from_computers(computers_of, |sid| {
+            data_table.principal_name(sid)
+        }))
+    }
+
+    /// creates one entry per creator, with the most active creators first
+    fn from_computers(
+        computers_of: HashMap<Sid, Vec<String>>,
+        name_of: impl Fn(&Sid) -> Option<String>,
+    ) -> Vec<Self> {
+        let mut creators: Vec<_> = computers_of
+            .into_iter()
+            .map(|(creator_sid, mut computers)| {
+                computers.sort();
+                Self {
+                    creator: name_of(&creator_sid),
+                    creator_sid,
+                    count: computers.len(),
+                    computers: computers.join(", "),
+                }
+            })
+            .collect();
+        creators.sort_by(|lhs, rhs| {
+            (
+                Reverse(lhs.count),
+                &lhs.creator,
+                lhs.creator_sid.to_string(),
+            )
+                .cmp(&(
+                    Reverse(rhs.count),
+                    &rhs.creator,
+                    rhs.creator_sid.to_string(),
+                ))
+        });
+        creators
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::win32_types::Sid;
+
+    use super::{MachineCreator, NonDefaultOwner, OwnedObjectCategory};
+
+    fn sid(sid: &str) -> Sid {
+        serde_json::from_str(&format!(r#""{sid}""#)).unwrap()
+    }
+
+    #[test]
+    fn test_is_default_owner() {
+        assert!(NonDefaultOwner::is_default_owner(&sid("S-1-5-18")));
+        assert!(NonDefaultOwner::is_default_owner(&sid("S-1-5-32-544")));
+        assert!(NonDefaultOwner::is_default_owner(&sid(
+            "S-1-5-21-1-2-3-512"
+        )));
+        assert!(NonDefaultOwner::is_default_owner(&sid(
+            "S-1-5-21-1-2-3-519"
+        )));
+        assert!(!NonDefaultOwner::is_default_owner(&sid(
+            "S-1-5-21-1-2-3-513"
+        )));
+        assert!(!NonDefaultOwner::is_default_owner(&sid(
+            "S-1-5-21-1-2-3-1104"
+        )));
+        assert!(!NonDefaultOwner::is_default_owner(&sid("S-1-5-32-548")));
+    }
+
+    #[test]
+    fn test_category() {
+        let domain_admins = sid("S-1-5-21-1-2-3-512");
+        let helpdesk = sid("S-1-5-21-1-2-3-1105");
+        assert_eq!(
+            OwnedObjectCategory::of(Some("Group"), Some(&domain_admins)),
+            OwnedObjectCategory::PrivilegedGroup
+        );
+        assert_eq!(
+            OwnedObjectCategory::of(Some("Group"), Some(&helpdesk)),
+            OwnedObjectCategory::Other
+        );
+        assert_eq!(
+            OwnedObjectCategory::of(Some("Group-Policy-Container"), None),
+            OwnedObjectCategory::Gpo
+        );
+        assert_eq!(
+            OwnedObjectCategory::of(Some("Computer"), Some(&helpdesk)),
+            OwnedObjectCategory::Computer
+        );
+        assert_eq!(
+            OwnedObjectCategory::of(None, None),
+            OwnedObjectCategory::Other
+        );
+
+        assert!(OwnedObjectCategory::PrivilegedGroup < OwnedObjectCategory::Gpo);
+        assert!(OwnedObjectCategory::Gpo < OwnedObjectCategory::Computer);
+        assert!(OwnedObjectCategory::Computer < OwnedObjectCategory::Other);
+        assert_eq!(
+            OwnedObjectCategory::PrivilegedGroup.to_string(),
+            "privileged group"
+        );
+    }
+
+    #[test]
+    fn test_machine_creators() {
+        let alice = sid("S-1-5-21-1-2-3-1104");
+        let bob = sid("S-1-5-21-1-2-3-1105");
+        let carol = sid("S-1-5-21-1-2-3-1106");
+        let computers_of = HashMap::from([
+            (carol.clone(), vec!["WS03$".to_string()]),
+            (bob.clone(), vec!["WS02$".to_string()]),
+            (
+                alice.clone(),
+                vec![
+                    "WS05$".to_string(),
+                    "WS01$".to_string(),
+                    "WS04$".to_string(),
+                ],
+            ),
+        ]);
+        let names = HashMap::from([
+            (alice, "alice".to_string()),
+            (bob, "bob".to_string()),
+            (carol, "carol".to_string()),
+        ]);
+
+        let creators = MachineCreator::from_computers(computers_of, |sid| names.get(sid).cloned());
+        let creators: Vec<_> = creators
+            .iter()
+            .map(|c| (c.creator.as_deref().unwrap(), c.count, c.computers.as_str()))
+            .collect();
+        assert_eq!(
+            creators,
+            [
+                ("alice", 3, "WS01$, WS04$, WS05$"),
+                ("bob", 1, "WS02$"),
+                ("carol", 1, "WS03$")
+            ]
+        );
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+use getset::Getters;
+use serde::Serialize;
+
+use crate::cache::{ColumnIndex, RecordId, RecordPointer};
+use crate::value::FromValue;
+use crate::win32_types::{Guid, UnixTimestamp, UserAccountControl, WindowsFileTime};
+
+use super::domain_info::{
+    interval_to_minutes, limit_to_minutes, LockoutPolicy, PasswordPolicy, TimeLimit,
+};
+use super::{DataTable, DataTableRecord};
+
+/// common name of the schema class of password settings objects
+const PASSWORD_SETTINGS_CLASS: &str = "ms-DS-Password-Settings";
+
+/// common name of the schema entry of `msDS-PSOAppliesTo`
+const PSO_APPLIES_TO_ATTRIBUTE: &str = "ms-DS-PSO-Applies-To";
+
+/// name which is used for the default password policy of the domain
+const DOMAIN_POLICY_NAME: &str = "Default Domain Policy";
+
+/// a fine-grained password policy (`msDS-PasswordSettings`), or the default
+/// password policy of the domain
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct PasswordSettings {
+    name: String,
+    distinguished_name: Option<String>,
+    is_domain_policy: bool,
+    precedence: Option<i32>,
+    min_length: Option<i32>,
+    complexity_enabled: Option<bool>,
+    reversible_encryption: Option<bool>,
+    history_length: Option<i32>,
+    min_age_days: Option<i64>,
+    max_age_days: Option<TimeLimit>,
+    lockout_threshold: Option<i32>,
+    lockout_duration_minutes: Option<TimeLimit>,
+    lockout_observation_window_minutes: Option<i64>,
+    applies_to: String,
+
+    /// maximum password age as (negative) interval of 100ns
+    #[serde(skip)]
+    max_age: Option<i64>,
+
+    #[serde(skip)]
+    applies_to_ids: HashSet<RecordId>,
+
+    /// used to order password settings objects with the same precedence
+    #[serde(skip)]
+    object_guid: Option<Guid>,
+}
+
+/// columns of the attributes of password settings objects, which are not
+/// contained in `attids.h`
+struct PsoColumns {
+    precedence: Option<ColumnIndex>,
+    min_length: Option<ColumnIndex>,
+    complexity_enabled: Option<ColumnIndex>,
+    reversible_encryption: Option<ColumnIndex>,
+    history_length: Option<ColumnIndex>,
+    min_age: Option<ColumnIndex>,
+    max_age: Option<ColumnIndex>,
+    lockout_threshold: Option<ColumnIndex>,
+    lockout_duration: Option<ColumnIndex>,
+    lockout_observation_window: Option<ColumnIndex>,
+}
+
+impl PsoColumns {
+    fn new(data_table: &DataTable) -> Self {
+        let column = |name| data_table.data_table().column_of_attribute(name);
+        Self {
+            precedence: column("msDS-PasswordSettingsPrecedence"),
+            min_length: column("msDS-MinimumPasswordLength"),
+            complexity_enabled: column("msDS-PasswordComplexityEnabled"),
+            reversible_encryption: column("msDS-PasswordReversibleEncryptionEnabled"),
+            history_length: column("msDS-PasswordHistoryLength"),
+            min_age: column("msDS-MinimumPasswordAge"),
+            max_age: column("msDS-MaximumPasswordAge"),
+            lockout_threshold: column("msDS-LockoutThreshold"),
+            lockout_duration: column("msDS-LockoutDuration"),
+            lockout_observation_window: column("msDS-LockoutObservationWindow"),
+        }
+    }
+}
+
+fn value_of<T: FromValue>(
+    record: &DataTableRecord,
+    column: Option<ColumnIndex>,
+) -> crate::ntds::Result<Option<T>> {
+    match column {
+        Some(column) => record.get_value_of_column_opt(column),
+        None => Ok(None),
+    }
+}
+
+fn days(minutes: Option<i64>) -> Option<i64> {
+    minutes.map(|m| m / (60 * 24))
+}
+
+impl PasswordSettings {
+    /// returns all password settings objects, ordered by their precedence,
+    /// followed by the default policy of the domain. If two objects have
+    /// the same precedence, the one with the lower `objectGUID` wins.
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let mut settings = Self::collect_psos(data_table)?;
+        Self::sort(&mut settings);
+        settings.push(Self::domain_policy(data_table)?);
+        Ok(settings)
+    }
+
+    fn collect_psos(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let class = match data_table.schema().type_entry(PASSWORD_SETTINGS_CLASS) {
+            Some(class) => class,
+            None => {
+                log::info!("the schema has no class named '{PASSWORD_SETTINGS_CLASS}'");
+                return Ok(Vec::new());
+            }
+        };
+
+        let metadata = data_table.data_table().metadata();
+        let columns = PsoColumns::new(data_table);
+        let applies_to_link_id = data_table.link_id_of(PSO_APPLIES_TO_ATTRIBUTE)?;
+        let mut settings = Vec::new();
+
+        for entry in metadata.entries_of_type(class.ds_record_id()) {
+            if entry.rdn().deleted_from_container().is_some() {
+                continue;
+            }
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+
+            let applies_to_ids: HashSet<_> = applies_to_link_id
+                .into_iter()
+                .flat_map(|link_id| data_table.link_table().links_of(link_id))
+                .filter(|link| !link.is_deleted())
+                .filter(|link| link.link_dnt() == entry.record_ptr().ds_record_id())
+                .map(|link| *link.backlink_dnt())
+                .collect();
+            let mut applies_to: Vec<_> = applies_to_ids
+                .iter()
+                .filter_map(|id| metadata.record(id))
+                .map(|e| {
+                    e.sam_account_name()
+                        .clone()
+                        .unwrap_or_else(|| e.rdn().name().to_string())
+                })
+                .collect();
+            applies_to.sort();
+
+            let max_age = value_of(&record, columns.max_age)?;
+            settings.push(Self {
+                name: entry.rdn().name().to_string(),
+                distinguished_name: data_table.object_tree().dn_of(entry.record_ptr()),
+                is_domain_policy: false,
+                precedence: value_of(&record, columns.precedence)?,
+                min_length: value_of(&record, columns.min_length)?,
+                complexity_enabled: value_of(&record, columns.complexity_enabled)?,
+                reversible_encryption: value_of(&record, columns.reversible_encryption)?,
+                history_length: value_of(&record, columns.history_length)?,
+                min_age_days: days(
+                    value_of(&record, columns.min_age)?.and_then(interval_to_minutes),
+                ),
+                max_age_days: max_age
+                    .map(limit_to_minutes)
+                    .map(TimeLimit::minutes_to_days),
+                lockout_threshold: value_of(&record, columns.lockout_threshold)?,
+                lockout_duration_minutes: value_of(&record, columns.lockout_duration)?
+                    .map(limit_to_minutes),
+                lockout_observation_window_minutes: value_of(
+                    &record,
+                    columns.lockout_observation_window,
+                )?
+                .and_then(interval_to_minutes),
+                applies_to: applies_to.join(", "),
+                max_age,
+                applies_to_ids,
+                object_guid: record.att_object_guid_opt()?,
+            });
+        }
+        Ok(settings)
+    }
+
+    fn sort(settings: &mut [Self]) {
+        settings.sort_by(|lhs, rhs| {
+            (lhs.precedence.unwrap_or(i32::MAX), &lhs.object_guid)
+                .cmp(&(rhs.precedence.unwrap_or(i32::MAX), &rhs.object_guid))
+        });
+    }
+
+    fn domain_policy(data_table: &DataTable) -> crate::ntds::Result<Self> {
+        let domain_root = data_table.special_records().domain_root();
+        let domain = data_table
+            .data_table()
+            .data_table_record_from(*domain_root.record_ptr())?;
+        let password_policy = PasswordPolicy::from_record(&domain)?;
+        let lockout_policy = LockoutPolicy::from_record(&domain)?;
+
+        Ok(Self {
+            name: DOMAIN_POLICY_NAME.to_string(),
+            distinguished_name: Some(domain_root.distinguished_name().clone()),
+            is_domain_policy: true,
+            precedence: None,
+            min_length: *password_policy.min_length(),
+            complexity_enabled: *password_policy.complexity_enabled(),
+            reversible_encryption: *password_policy.reversible_encryption(),
+            history_length: *password_policy.history_length(),
+            min_age_days: *password_policy.min_age_days(),
+            max_age_days: *password_policy.max_age_days(),
+            lockout_threshold: *lockout_policy.threshold(),
+            lockout_duration_minutes: *lockout_policy.duration_minutes(),
+            lockout_observation_window_minutes: *lockout_policy.observation_window_minutes(),
+            applies_to: String::new(),
+            max_age: domain.att_max_pwd_age_opt()?,
+            applies_to_ids: HashSet::new(),
+            object_guid: None,
+        })
+    }
+
+    /// returns the policy which applies to an account. Policies which are
+    /// applied directly to the account take precedence over policies which
+    /// are applied to one of its groups. If no password settings object
+    /// applies, the default policy of the domain is used.
+    pub fn resultant<'s>(
+        settings: &'s [Self],
+        data_table: &DataTable,
+        account: &RecordPointer,
+        primary_group_id: Option<i32>,
+    ) -> Option<&'s Self> {
+        let groups: HashSet<_> = data_table
+            .groups_of(account, primary_group_id)
+            .iter()
+            .map(|g| *g.ds_record_id())
+            .collect();
+        Self::resultant_of(settings, account.ds_record_id(), &groups)
+    }
+
+    fn resultant_of<'s>(
+        settings: &'s [Self],
+        account: &RecordId,
+        groups: &HashSet<RecordId>,
+    ) -> Option<&'s Self> {
+        // `settings` is ordered by precedence, so the first match wins
+        let mut psos = settings.iter().filter(|s| !s.is_domain_policy);
+        psos.clone()
+            .find(|s| s.applies_to_ids.contains(account))
+            .or_else(|| psos.find(|s| !s.applies_to_ids.is_disjoint(groups)))
+            .or_else(|| settings.iter().find(|s| s.is_domain_policy))
+    }
+
+    /// calculates the time when the password of an account expires
+    pub fn password_expires(
+        &self,
+        password_last_set: Option<&WindowsFileTime>,
+        uac: Option<&UserAccountControl>,
+    ) -> Option<WindowsFileTime> {
+        if uac.is_some_and(|uac| uac.contains(UserAccountControl::ADS_UF_DONT_EXPIRE_PASSWD)) {
+            return None;
+        }
+
+        // 0 means that the password never expires
+        let max_age = self.max_age.filter(|a| *a != 0 && *a != i64::MIN)?;
+
+        // a value of 0 means that the user must change the password at next logon
+        let password_last_set = password_last_set.filter(|ts| ts.timestamp() > 0)?;
+        let last_set: DateTime<Utc> = password_last_set.clone().into();
+        Some(WindowsFileTime::from(
+            last_set + Duration::microseconds(max_age.abs() / 10),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    use crate::cache::RecordId;
+    use crate::win32_types::Guid;
+
+    use super::PasswordSettings;
+
+    fn policy(
+        name: &str,
+        precedence: Option<i32>,
+        applies_to: &[i32],
+        guid: &str,
+    ) -> PasswordSettings {
+        PasswordSettings {
+            name: name.to_string(),
+            distinguished_name: None,
+            is_domain_policy: precedence.is_none(),
+            precedence,
+            min_length: None,
+            complexity_enabled: None,
+            reversible_encryption: None,
+            history_length: None,
+            min_age_days: None,
+            max_age_days: None,
+            lockout_threshold: None,
+            lockout_duration_minutes: None,
+            lockout_observation_window_minutes: None,
+            applies_to: String::new(),
+            max_age: None,
+            applies_to_ids: applies_to.iter().map(|id| RecordId::from(*id)).collect(),
+            object_guid: Guid::from_str(guid).ok(),
+        }
+    }
+
+    fn settings() -> Vec<PasswordSettings> {
+        let mut settings = vec![
+            policy(
+                "groups",
+                Some(10),
+                &[100],
+                "00000000-0000-0000-0000-000000000003",
+            ),
+            policy(
+                "tie-b",
+                Some(5),
+                &[200],
+                "00000000-0000-0000-0000-000000000002",
+            ),
+            policy(
+                "tie-a",
+                Some(5),
+                &[200],
+                "00000000-0000-0000-0000-000000000001",
+            ),
+            policy(
+                "direct",
+                Some(20),
+                &[1],
+                "00000000-0000-0000-0000-000000000004",
+            ),
+        ];
+        PasswordSettings::sort(&mut settings);
+        settings.push(policy("domain", None, &[], ""));
+        settings
+    }
+
+    fn resultant(account: i32, groups: &[i32]) -> String {
+        let settings = settings();
+        let groups: HashSet<_> = groups.iter().map(|id| RecordId::from(*id)).collect();
+        PasswordSettings::resultant_of(&settings, &RecordId::from(account), &groups)
+            .unwrap()
+            .name
+            .clone()
+    }
+
+    #[test]
+    fn test_sort() {
+        let names: Vec<_> = settings().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["tie-a", "tie-b", "groups", "direct", "domain"]);
+    }
+
+    #[test]
+    fn test_resultant() {
+        // a directly applied policy wins, even with a lower precedence
+        assert_eq!(resultant(1, &[100, 200]), "direct");
+
+        // otherwise, the group policy with the lowest precedence value wins
+        assert_eq!(resultant(2, &[100]), "groups");
+        assert_eq!(resultant(2, &[100, 200]), "tie-a");
+
+        // the domain policy is used if no policy applies
+        assert_eq!(resultant(2, &[300]), "domain");
+        assert_eq!(resultant(2, &[]), "domain");
+    }
+}
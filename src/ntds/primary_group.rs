@@ -0,0 +1,214 @@
+use std::collections::HashSet;
+
+use getset::Getters;
+use serde::Serialize;
+
+use crate::cache::RecordPointer;
+use crate::win32_types::{Sid, UserAccountControl};
+
+use super::DataTable;
+
+const DOMAIN_USERS_RID: i32 = 513;
+const DOMAIN_GUESTS_RID: i32 = 514;
+const DOMAIN_COMPUTERS_RID: i32 = 515;
+const DOMAIN_CONTROLLERS_RID: i32 = 516;
+const READONLY_DOMAIN_CONTROLLERS_RID: i32 = 521;
+const GUEST_RID: u32 = 501;
+
+/// an account whose `primaryGroupID` differs from the default value.
+/// Membership in the primary group is not stored in the `member` attribute
+/// of the group, so it is hidden from most tools
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct PrimaryGroupFinding {
+    account: Option<String>,
+    distinguished_name: Option<String>,
+    sid: Option<Sid>,
+    is_enabled: bool,
+    primary_group_id: i32,
+    primary_group: Option<String>,
+    default_primary_group_id: i32,
+
+    /// the primary group is one of the highly privileged groups, such as
+    /// Domain Admins or Enterprise Admins
+    is_privileged: bool,
+}
+
+impl PrimaryGroupFinding {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let metadata = data_table.data_table().metadata();
+        let mut findings = Vec::new();
+
+        for entry in metadata.iter().filter(|e| e.sid().is_some()) {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+            let primary_group_id = match record.att_primary_group_id_opt()? {
+                Some(id) => id,
+                None => continue,
+            };
+
+            let uac = record
+                .att_user_account_control_opt()?
+                .unwrap_or(UserAccountControl::empty());
+            let default_primary_group_id = Self::expected_primary_group_id(entry.sid(), &uac);
+            if primary_group_id == default_primary_group_id {
+                continue;
+            }
+
+            let primary_group = u32::try_from(primary_group_id)
+                .ok()
+                .and_then(|rid| data_table.group_with_rid(rid));
+
+            findings.push(Self {
+                account: entry
+                    .sam_account_name()
+                    .clone()
+                    .or_else(|| Some(entry.rdn().name().to_string())),
+                distinguished_name: data_table.object_tree().dn_of(entry.record_ptr()),
+                sid: entry.sid().clone(),
+                is_enabled: !uac.contains(UserAccountControl::ADS_UF_ACCOUNTDISABLE),
+                primary_group_id,
+                primary_group: primary_group.map(|g| {
+                    g.sam_account_name()
+                        .clone()
+                        .unwrap_or_else(|| g.rdn().name().to_string())
+                }),
+                default_primary_group_id,
+                is_privileged: primary_group
+                    .and_then(|g| g.sid().as_ref())
+                    .is_some_and(Sid::is_privileged),
+            });
+        }
+
+        findings.sort_by_key(|f| (!f.is_privileged, !f.is_enabled));
+        Ok(findings)
+    }
+
+    /// returns the `primaryGroupID` which is assigned to new accounts
+    fn expected_primary_group_id(sid: &Option<Sid>, uac: &UserAccountControl) -> i32 {
+        if uac.contains(UserAccountControl::ADS_UF_PARTIAL_SECRETS_ACCOUNT) {
+            READONLY_DOMAIN_CONTROLLERS_RID
+        } else if uac.contains(UserAccountControl::ADS_UF_SERVER_TRUST_ACCOUNT) {
+            DOMAIN_CONTROLLERS_RID
+        } else if uac.contains(UserAccountControl::ADS_UF_WORKSTATION_TRUST_ACCOUNT) {
+            DOMAIN_COMPUTERS_RID
+        } else if sid.as_ref().is_some_and(|sid| *sid.get_rid() == GUEST_RID) {
+            DOMAIN_GUESTS_RID
+        } else {
+            DOMAIN_USERS_RID
+        }
+    }
+}
+
+/// returns all members of `group`, including members of nested groups.
+/// `direct_members_of` must return the members of a group which are stored in
+/// the link table and the objects which have it as their primary group
+pub(crate) fn transitive_members<F>(
+    group: &RecordPointer,
+    direct_members_of: F,
+) -> HashSet<RecordPointer>
+where
+    F: Fn(&RecordPointer) -> Vec<RecordPointer>,
+{
+    let mut members = HashSet::new();
+    let mut pending = vec![*group];
+    while let Some(group) = pending.pop() {
+        for member in direct_members_of(&group) {
+            if members.insert(member) {
+                pending.push(member);
+            }
+        }
+    }
+    members
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::cache::RecordPointer;
+    use crate::win32_types::{Sid, UserAccountControl};
+
+    use super::{
+        transitive_members, PrimaryGroupFinding, DOMAIN_COMPUTERS_RID, DOMAIN_CONTROLLERS_RID,
+        DOMAIN_GUESTS_RID, DOMAIN_USERS_RID, READONLY_DOMAIN_CONTROLLERS_RID,
+    };
+
+    #[test]
+    fn test_expected_primary_group_id() {
+        let domain: Sid = serde_json::from_str(r#""S-1-5-21-1-2-3""#).unwrap();
+        let expected = |rid, uac| {
+            PrimaryGroupFinding::expected_primary_group_id(&Some(domain.with_rid(rid)), &uac)
+        };
+
+        assert_eq!(
+            expected(1104, UserAccountControl::ADS_UF_NORMAL_ACCOUNT),
+            DOMAIN_USERS_RID
+        );
+        assert_eq!(
+            expected(501, UserAccountControl::ADS_UF_NORMAL_ACCOUNT),
+            DOMAIN_GUESTS_RID
+        );
+        assert_eq!(
+            expected(1105, UserAccountControl::ADS_UF_WORKSTATION_TRUST_ACCOUNT),
+            DOMAIN_COMPUTERS_RID
+        );
+        assert_eq!(
+            expected(1000, UserAccountControl::ADS_UF_SERVER_TRUST_ACCOUNT),
+            DOMAIN_CONTROLLERS_RID
+        );
+        assert_eq!(
+            expected(
+                1106,
+                UserAccountControl::ADS_UF_WORKSTATION_TRUST_ACCOUNT
+                    | UserAccountControl::ADS_UF_PARTIAL_SECRETS_ACCOUNT
+            ),
+            READONLY_DOMAIN_CONTROLLERS_RID
+        );
+        assert_eq!(
+            PrimaryGroupFinding::expected_primary_group_id(
+                &None,
+                &UserAccountControl::ADS_UF_NORMAL_ACCOUNT
+            ),
+            DOMAIN_USERS_RID
+        );
+    }
+
+    #[test]
+    fn test_transitive_members() {
+        let ptr = |id: i32| RecordPointer::new(id.into(), id.into());
+        let (allowed, helpdesk, alice, bob, carol, dave) =
+            (ptr(1), ptr(2), ptr(3), ptr(4), ptr(5), ptr(6));
+
+        // alice and helpdesk are stored in `member` of the allowed group, which
+        // is the primary group of bob. helpdesk is the primary group of carol
+        let links = HashMap::from([
+            (*allowed.ds_record_id(), vec![alice, helpdesk]),
+            (*helpdesk.ds_record_id(), vec![dave]),
+        ]);
+        let primary = HashMap::from([
+            (*allowed.ds_record_id(), vec![bob]),
+            (*helpdesk.ds_record_id(), vec![carol]),
+        ]);
+        let direct_members_of = |group: &RecordPointer| {
+            links
+                .get(group.ds_record_id())
+                .into_iter()
+                .chain(primary.get(group.ds_record_id()))
+                .flatten()
+                .copied()
+                .collect()
+        };
+
+        let members = transitive_members(&allowed, direct_members_of);
+        assert_eq!(members.len(), 5);
+        for member in [alice, helpdesk, bob, carol, dave] {
+            assert!(members.contains(&member));
+        }
+        assert!(!members.contains(&allowed));
+
+        let members = transitive_members(&helpdesk, direct_members_of);
+        assert_eq!(members.len(), 2);
+    }
+}
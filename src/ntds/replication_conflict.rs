@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use getset::Getters;
+use serde::Serialize;
+
+use crate::cache::{RecordId, RecordPointer};
+use crate::win32_types::{Guid, Rdn, TruncatedWindowsFileTime};
+
+use super::{domain_controller_names, DataTable, NtdsAttributeId};
+
+/// an object which has been renamed because another object with the same
+/// name has been created on another domain controller at the same time.
+/// The name of such an object contains a `CNF:` marker, followed by its own
+/// `objectGUID`. So the marker doesn't tell which object kept the original
+/// name; instead, this object is searched by its name
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct ReplicationConflict {
+    name: String,
+    object_class: Option<String>,
+    distinguished_name: Option<String>,
+    is_deleted: bool,
+
+    /// the GUID from the `CNF:` marker, which is the `objectGUID` of the
+    /// renamed object itself
+    conflict_guid: Guid,
+    created: Option<TruncatedWindowsFileTime>,
+    created_on: Option<String>,
+
+    /// the object which kept the original name, which is the object with
+    /// the unmangled name in the same container (or in the last known
+    /// container, if the renamed object has been deleted)
+    surviving_object: Option<String>,
+    surviving_created: Option<TruncatedWindowsFileTime>,
+    surviving_created_on: Option<String>,
+}
+
+impl ReplicationConflict {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let metadata = data_table.data_table().metadata();
+        let domain_controllers = domain_controller_names(data_table)?;
+        let mut conflicts = Vec::new();
+
+        for entry in metadata
+            .iter()
+            .filter(|entry| !entry.rdn().conflicting_objects().is_empty())
+        {
+            let name = entry.rdn().name();
+            let is_deleted = entry.rdn().deleted_from_container().is_some();
+            let last_known_parent = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?
+                .att_last_known_parent_opt()?;
+            let surviving_object = metadata
+                .ptr_from_id(&container_of(entry.parent(), is_deleted, last_known_parent))
+                .into_iter()
+                .flat_map(|parent| metadata.children_of(parent))
+                .find(|sibling| is_surviving_sibling(name, sibling.rdn()));
+            let (created, created_on) =
+                Self::creation_of(data_table, &domain_controllers, entry.record_ptr())?;
+            let (surviving_created, surviving_created_on) = match surviving_object {
+                Some(object) => {
+                    Self::creation_of(data_table, &domain_controllers, object.record_ptr())?
+                }
+                None => (None, None),
+            };
+
+            for conflict_guid in entry.rdn().conflicting_objects() {
+                if metadata
+                    .ptr_from_guid(conflict_guid)
+                    .is_some_and(|ptr| ptr.ds_record_id() != entry.record_ptr().ds_record_id())
+                {
+                    log::warn!(
+                        "the CNF marker of '{entry}' contains the objectGUID of another object"
+                    );
+                }
+                conflicts.push(Self {
+                    name: name.clone(),
+                    object_class: metadata.object_class_name(entry).map(str::to_string),
+                    distinguished_name: data_table.object_tree().dn_of(entry.record_ptr()),
+                    is_deleted,
+                    conflict_guid: conflict_guid.clone(),
+                    created: created.clone(),
+                    created_on: created_on.clone(),
+                    surviving_object: surviving_object
+                        .and_then(|o| data_table.object_tree().dn_of(o.record_ptr())),
+                    surviving_created: surviving_created.clone(),
+                    surviving_created_on: surviving_created_on.clone(),
+                });
+            }
+        }
+
+        conflicts.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+        Ok(conflicts)
+    }
+
+    /// returns the creation time of an object, and the domain controller on
+    /// which it has been created. If the domain controller is unknown, its
+    /// invocation ID is returned
+    fn creation_of(
+        data_table: &DataTable,
+        domain_controllers: &HashMap<Guid, String>,
+        ptr: &RecordPointer,
+    ) -> crate::ntds::Result<(Option<TruncatedWindowsFileTime>, Option<String>)> {
+        let record = data_table.data_table().data_table_record_from(*ptr)?;
+        let created_on = match record.att_repl_property_meta_data_opt() {
+            Ok(repl_metadata) => repl_metadata
+                .as_ref()
+                .and_then(|m| m.of(NtdsAttributeId::AttWhenCreated as u32))
+                .map(|m| {
+                    domain_controllers
+                        .get(m.originating_dsa())
+                        .cloned()
+                        .unwrap_or_else(|| m.originating_dsa().to_string())
+                }),
+            Err(why) => {
+                log::warn!("unable to parse the replication metadata of {ptr}: {why}");
+                None
+            }
+        };
+        Ok((record.att_when_created_opt()?, created_on))
+    }
+}
+
+/// returns the container in which the object with the original name is
+/// searched. Deleted objects have been moved to the Deleted Objects container,
+/// so their last known parent is used instead
+fn container_of(
+    parent: &RecordId,
+    is_deleted: bool,
+    last_known_parent: Option<RecordId>,
+) -> RecordId {
+    match last_known_parent {
+        Some(last_known_parent) if is_deleted => last_known_parent,
+        _ => *parent,
+    }
+}
+
+/// `true` if `sibling` is an object which kept the name `name` in a
+/// replication conflict
+fn is_surviving_sibling(name: &str, sibling: &Rdn) -> bool {
+    sibling.name() == name
+        && sibling.conflicting_objects().is_empty()
+        && sibling.deleted_from_container().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::{RecordId, Value};
+    use crate::value::FromValue;
+    use crate::win32_types::Rdn;
+
+    use super::{container_of, is_surviving_sibling};
+
+    const GUID: &str = "4d5b8a1e-2b2a-4a49-9d2f-1f0d2b3c4e5f";
+
+    fn rdn(value: &str) -> Rdn {
+        Rdn::from_value(&Value::Text(Box::new(value.to_string()))).unwrap()
+    }
+
+    #[test]
+    fn test_container_of() {
+        let parent = RecordId::from(10);
+        let last_known_parent = RecordId::from(20);
+        assert_eq!(container_of(&parent, false, None), parent);
+        assert_eq!(
+            container_of(&parent, false, Some(last_known_parent)),
+            parent
+        );
+        assert_eq!(
+            container_of(&parent, true, Some(last_known_parent)),
+            last_known_parent
+        );
+        assert_eq!(container_of(&parent, true, None), parent);
+    }
+
+    #[test]
+    fn test_is_surviving_sibling() {
+        assert!(is_surviving_sibling("Alice", &rdn("Alice")));
+        assert!(!is_surviving_sibling("Alice", &rdn("Bob")));
+        assert!(!is_surviving_sibling(
+            "Alice",
+            &rdn(&format!("Alice\nCNF:{GUID}"))
+        ));
+        assert!(!is_surviving_sibling(
+            "Alice",
+            &rdn(&format!("Alice\nDEL:{GUID}"))
+        ));
+    }
+}
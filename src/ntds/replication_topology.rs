@@ -0,0 +1,517 @@
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use getset::Getters;
+use serde::Serialize;
+
+use crate::cache::RecordId;
+use crate::object_tree_entry::ObjectTreeEntry;
+use crate::win32_types::TruncatedWindowsFileTime;
+
+use super::domain_info::find_by_path;
+use super::DataTable;
+
+/// `options` flag of `nTDSDSA` objects of global catalog servers
+const NTDSDSA_OPT_IS_GC: i32 = 0x1;
+
+/// `options` flag of `nTDSConnection` objects which have been created by
+/// the Knowledge Consistency Checker (KCC)
+const NTDSCONN_OPT_IS_GENERATED: i32 = 0x1;
+
+/// a site (`site`)
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct Site {
+    name: String,
+    distinguished_name: String,
+    description: Option<String>,
+    location: Option<String>,
+    subnets: String,
+    servers: String,
+    when_created: Option<TruncatedWindowsFileTime>,
+}
+
+/// a subnet (`subnet`) and the site it is mapped to
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct Subnet {
+    name: String,
+    site: Option<String>,
+    description: Option<String>,
+    location: Option<String>,
+    when_created: Option<TruncatedWindowsFileTime>,
+}
+
+/// a server (`server`) in a site, which is a domain controller if it has an
+/// `nTDSDSA` object
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct SiteServer {
+    name: String,
+    site: String,
+    dns_host_name: Option<String>,
+    computer: Option<String>,
+    is_domain_controller: bool,
+    is_read_only: bool,
+    is_global_catalog: bool,
+    when_created: Option<TruncatedWindowsFileTime>,
+}
+
+/// an inbound replication connection (`nTDSConnection`)
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct ReplicationConnection {
+    name: String,
+    from_server: Option<String>,
+    from_site: Option<String>,
+    to_server: String,
+    to_site: String,
+    transport: Option<String>,
+    is_enabled: bool,
+    is_generated: bool,
+    is_inter_site: bool,
+    schedule: Option<String>,
+    when_created: Option<TruncatedWindowsFileTime>,
+}
+
+/// a link between sites (`siteLink`)
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct SiteLink {
+    name: String,
+    transport: String,
+    sites: String,
+    cost: Option<i32>,
+    repl_interval_minutes: Option<i32>,
+    schedule: Option<String>,
+    when_created: Option<TruncatedWindowsFileTime>,
+}
+
+/// sites, subnets, servers and replication connections, as stored below
+/// `CN=Sites` in the configuration partition
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub struct ReplicationTopology {
+    sites: Vec<Site>,
+    subnets: Vec<Subnet>,
+    servers: Vec<SiteServer>,
+    connections: Vec<ReplicationConnection>,
+    site_links: Vec<SiteLink>,
+}
+
+/// returns the name of the schema class of an object
+fn class_of<'d>(data_table: &'d DataTable, entry: &ObjectTreeEntry) -> Option<&'d str> {
+    let metadata = data_table.data_table().metadata();
+    metadata
+        .record(entry.record_ptr().ds_record_id())
+        .and_then(|e| metadata.object_class_name(e))
+}
+
+/// returns all children of an object which have the given class, ordered
+/// by their name
+fn children_of_class(
+    data_table: &DataTable,
+    entry: &ObjectTreeEntry,
+    class_names: &[&str],
+) -> Vec<Rc<ObjectTreeEntry>> {
+    let mut children: Vec<_> = entry
+        .children()
+        .borrow()
+        .iter()
+        .filter(|c| c.name().deleted_from_container().is_none())
+        .filter(|c| class_of(data_table, c).is_some_and(|n| class_names.contains(&n)))
+        .cloned()
+        .collect();
+    children.sort_by(|lhs, rhs| lhs.name().name().cmp(rhs.name().name()));
+    children
+}
+
+impl ReplicationTopology {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Self> {
+        let mut me = Self {
+            sites: Vec::new(),
+            subnets: Vec::new(),
+            servers: Vec::new(),
+            connections: Vec::new(),
+            site_links: Vec::new(),
+        };
+
+        let configuration = data_table.special_records().configuration();
+        let sites_container = match configuration.find_child_by_name("Sites") {
+            Some(sites) => sites,
+            None => {
+                log::error!("the configuration partition has no 'Sites' container");
+                return Ok(me);
+            }
+        };
+
+        let site_entries = children_of_class(data_table, &sites_container, &["Site"]);
+        let site_names: HashMap<RecordId, String> = site_entries
+            .iter()
+            .map(|s| (*s.record_ptr().ds_record_id(), s.name().name().to_string()))
+            .collect();
+        let name_of_site = |id: &RecordId| site_names.get(id).cloned();
+
+        me.collect_subnets(data_table, &sites_container, &name_of_site)?;
+
+        // maps the ids of nTDSDSA objects to the names of their server and site
+        let mut ntds_settings: HashMap<RecordId, (String, String)> = HashMap::new();
+        let mut subnets_of_site: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for subnet in &me.subnets {
+            if let Some(site) = &subnet.site {
+                subnets_of_site.entry(site).or_default().push(&subnet.name);
+            }
+        }
+
+        let mut sites = Vec::new();
+        let mut connections = Vec::new();
+        for site in &site_entries {
+            let site_name = site.name().name().to_string();
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*site.record_ptr())?;
+
+            let servers_container = site.find_child_by_name("Servers");
+            let server_entries = match &servers_container {
+                Some(servers) => children_of_class(data_table, servers, &["Server"]),
+                None => Vec::new(),
+            };
+
+            for server in &server_entries {
+                let server_name = server.name().name().to_string();
+                let server_record = data_table
+                    .data_table()
+                    .data_table_record_from(*server.record_ptr())?;
+                let dsa = children_of_class(data_table, server, &["NTDS-DSA", "NTDS-DSA-RO"])
+                    .into_iter()
+                    .next();
+                let dsa_options = match &dsa {
+                    Some(dsa) => data_table
+                        .data_table()
+                        .data_table_record_from(*dsa.record_ptr())?
+                        .att_options_opt()?
+                        .unwrap_or(0),
+                    None => 0,
+                };
+
+                if let Some(dsa) = &dsa {
+                    ntds_settings.insert(
+                        *dsa.record_ptr().ds_record_id(),
+                        (server_name.clone(), site_name.clone()),
+                    );
+                    for connection in children_of_class(data_table, dsa, &["NTDS-Connection"]) {
+                        connections.push((connection, server_name.clone(), site_name.clone()));
+                    }
+                }
+
+                me.servers.push(SiteServer {
+                    name: server_name,
+                    site: site_name.clone(),
+                    dns_host_name: server_record.att_dns_host_name_opt()?,
+                    computer: server_record
+                        .att_server_reference_opt()?
+                        .and_then(|id| data_table.data_table().metadata().ptr_from_id(&id))
+                        .and_then(|ptr| data_table.object_tree().dn_of(ptr)),
+                    is_domain_controller: dsa.is_some(),
+                    is_read_only: dsa
+                        .as_ref()
+                        .is_some_and(|dsa| class_of(data_table, dsa) == Some("NTDS-DSA-RO")),
+                    is_global_catalog: dsa_options & NTDSDSA_OPT_IS_GC != 0,
+                    when_created: server_record.att_when_created_opt()?,
+                });
+            }
+
+            sites.push(Site {
+                distinguished_name: site.distinguished_name().clone(),
+                description: record.att_description_opt()?,
+                location: record.att_location_opt()?,
+                subnets: subnets_of_site
+                    .get(site_name.as_str())
+                    .map(|s| s.join(", "))
+                    .unwrap_or_default(),
+                servers: server_entries
+                    .iter()
+                    .map(|s| s.name().name().as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                when_created: record.att_when_created_opt()?,
+                name: site_name,
+            });
+        }
+        me.sites = sites;
+
+        let metadata = data_table.data_table().metadata();
+        for (connection, to_server, to_site) in connections {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*connection.record_ptr())?;
+            let from = record
+                .att_from_server_opt()?
+                .and_then(|id| ntds_settings.get(&id));
+            let options = record.att_options_opt()?.unwrap_or(0);
+
+            me.connections.push(ReplicationConnection {
+                name: connection.name().name().to_string(),
+                from_server: from.map(|(server, _)| server.clone()),
+                from_site: from.map(|(_, site)| site.clone()),
+                is_inter_site: from.is_some_and(|(_, site)| *site != to_site),
+                to_server,
+                to_site,
+                transport: record
+                    .att_transport_type_opt()?
+                    .and_then(|id| metadata.record(&id))
+                    .map(|t| t.rdn().name().to_string()),
+                is_enabled: record.att_enabled_connection_opt()?.unwrap_or(true),
+                is_generated: options & NTDSCONN_OPT_IS_GENERATED != 0,
+                schedule: Self::schedule_of(&record),
+                when_created: record.att_when_created_opt()?,
+            });
+        }
+
+        me.collect_site_links(data_table, &sites_container, &name_of_site)?;
+        Ok(me)
+    }
+
+    fn collect_subnets(
+        &mut self,
+        data_table: &DataTable,
+        sites_container: &Rc<ObjectTreeEntry>,
+        name_of_site: &impl Fn(&RecordId) -> Option<String>,
+    ) -> crate::ntds::Result<()> {
+        let subnets = match sites_container.find_child_by_name("Subnets") {
+            Some(subnets) => subnets,
+            None => return Ok(()),
+        };
+
+        for subnet in children_of_class(data_table, &subnets, &["Subnet"]) {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*subnet.record_ptr())?;
+            self.subnets.push(Subnet {
+                name: subnet.name().name().to_string(),
+                site: record
+                    .att_site_object_opt()?
+                    .and_then(|id| name_of_site(&id)),
+                description: record.att_description_opt()?,
+                location: record.att_location_opt()?,
+                when_created: record.att_when_created_opt()?,
+            });
+        }
+        Ok(())
+    }
+
+    fn collect_site_links(
+        &mut self,
+        data_table: &DataTable,
+        sites_container: &Rc<ObjectTreeEntry>,
+        name_of_site: &impl Fn(&RecordId) -> Option<String>,
+    ) -> crate::ntds::Result<()> {
+        let transports = match find_by_path(sites_container, &["Inter-Site Transports"]) {
+            Some(transports) => transports,
+            None => return Ok(()),
+        };
+
+        for transport in children_of_class(data_table, &transports, &["Inter-Site-Transport"]) {
+            for site_link in children_of_class(data_table, &transport, &["Site-Link"]) {
+                let record = data_table
+                    .data_table()
+                    .data_table_record_from(*site_link.record_ptr())?;
+                let mut sites: Vec<_> = record
+                    .att_site_list_opt()?
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(name_of_site)
+                    .collect();
+                sites.sort();
+
+                self.site_links.push(SiteLink {
+                    name: site_link.name().name().to_string(),
+                    transport: transport.name().name().to_string(),
+                    sites: sites.join(", "),
+                    cost: record.att_cost_opt()?,
+                    repl_interval_minutes: record.att_repl_interval_opt()?,
+                    schedule: Self::schedule_of(&record),
+                    when_created: record.att_when_created_opt()?,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// a missing schedule means that replication is always allowed
+    fn schedule_of(record: &super::DataTableRecord) -> Option<String> {
+        match record.att_schedule_opt() {
+            Ok(schedule) => Some(
+                schedule
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "always".to_string()),
+            ),
+            Err(why) => {
+                log::warn!("unable to parse replication schedule: {why}");
+                None
+            }
+        }
+    }
+
+    /// renders the replication graph in Graphviz DOT format. Servers are
+    /// grouped by their site, and every connection is an edge from the
+    /// source server to the target server
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec![
+            "digraph replication {".to_string(),
+            "  rankdir=LR;".to_string(),
+            "  node [shape=box];".to_string(),
+        ];
+
+        for (idx, site) in self.sites.iter().enumerate() {
+            lines.push(format!("  subgraph cluster_{idx} {{"));
+            lines.push(format!("    label={};", dot_string(&site.name)));
+            for server in self.servers.iter().filter(|s| s.site == site.name) {
+                let style = if server.is_read_only {
+                    " [style=dashed]"
+                } else {
+                    ""
+                };
+                lines.push(format!("    {}{style};", dot_string(&server.name)));
+            }
+            lines.push("  }".to_string());
+        }
+
+        for connection in &self.connections {
+            let from = match &connection.from_server {
+                Some(from) => from,
+                None => continue,
+            };
+            let mut attributes = Vec::new();
+            if connection.is_inter_site {
+                attributes.push("color=blue".to_string());
+            }
+            if !connection.is_enabled {
+                attributes.push("style=dotted".to_string());
+            }
+            if !connection.is_generated {
+                attributes.push(format!("label={}", dot_string("manual")));
+            }
+            let attributes = if attributes.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", attributes.join(", "))
+            };
+            lines.push(format!(
+                "  {} -> {}{attributes};",
+                dot_string(from),
+                dot_string(&connection.to_server)
+            ));
+        }
+
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+}
+
+fn dot_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dot_string, ReplicationConnection, ReplicationTopology, Site, SiteServer};
+
+    fn site(name: &str) -> Site {
+        Site {
+            name: name.to_string(),
+            distinguished_name: format!("CN={name},CN=Sites,CN=Configuration,DC=example,DC=com"),
+            description: None,
+            location: None,
+            subnets: String::new(),
+            servers: String::new(),
+            when_created: None,
+        }
+    }
+
+    fn server(name: &str, site: &str, is_read_only: bool) -> SiteServer {
+        SiteServer {
+            name: name.to_string(),
+            site: site.to_string(),
+            dns_host_name: None,
+            computer: None,
+            is_domain_controller: true,
+            is_read_only,
+            is_global_catalog: false,
+            when_created: None,
+        }
+    }
+
+    fn connection(
+        from: Option<&str>,
+        to: &str,
+        is_inter_site: bool,
+        is_enabled: bool,
+        is_generated: bool,
+    ) -> ReplicationConnection {
+        ReplicationConnection {
+            name: "connection".to_string(),
+            from_server: from.map(str::to_string),
+            from_site: None,
+            to_server: to.to_string(),
+            to_site: String::new(),
+            transport: None,
+            is_enabled,
+            is_generated,
+            is_inter_site,
+            schedule: None,
+            when_created: None,
+        }
+    }
+
+    #[test]
+    fn test_dot_string() {
+        assert_eq!(dot_string("DC01"), r#""DC01""#);
+        assert_eq!(dot_string(r#"Site "A""#), r#""Site \"A\"""#);
+        assert_eq!(dot_string(r"Branch\1"), r#""Branch\\1""#);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let topology = ReplicationTopology {
+            sites: vec![site("Default-First-Site-Name"), site(r#"Branch "1"\"#)],
+            subnets: Vec::new(),
+            servers: vec![
+                server("DC01", "Default-First-Site-Name", false),
+                server("DC02", "Default-First-Site-Name", false),
+                server("RODC01", r#"Branch "1"\"#, true),
+            ],
+            connections: vec![
+                connection(Some("DC01"), "DC02", false, true, true),
+                connection(Some("DC02"), "DC01", false, false, false),
+                connection(Some("DC01"), "RODC01", true, true, true),
+                // the source server has been removed
+                connection(None, "DC01", false, true, true),
+            ],
+            site_links: Vec::new(),
+        };
+
+        assert_eq!(
+            topology.to_dot(),
+            [
+                "digraph replication {",
+                "  rankdir=LR;",
+                "  node [shape=box];",
+                "  subgraph cluster_0 {",
+                r#"    label="Default-First-Site-Name";"#,
+                r#"    "DC01";"#,
+                r#"    "DC02";"#,
+                "  }",
+                "  subgraph cluster_1 {",
+                r#"    label="Branch \"1\"\\";"#,
+                r#"    "RODC01" [style=dashed];"#,
+                "  }",
+                r#"  "DC01" -> "DC02";"#,
+                r#"  "DC02" -> "DC01" [style=dotted, label="manual"];"#,
+                r#"  "DC01" -> "RODC01" [color=blue];"#,
+                "}",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use chrono::Utc;
+use getset::Getters;
+use serde::Serialize;
+use strum::Display;
+
+use crate::win32_types::{
+    Sid, SupportedEncryptionTypes, UnixTimestamp, UserAccountControl, WindowsFileTime,
+};
+
+use super::DataTable;
+
+/// `lDAPDisplayName` of the attribute which contains the encryption types
+/// supported by an account. This attribute is not contained in `attids.h`
+const ENCRYPTION_TYPES_ATTRIBUTE: &str = "msDS-SupportedEncryptionTypes";
+
+/// passwords which are older than this are considered to be weak
+const OLD_PASSWORD_DAYS: i64 = 365;
+
+/// RID of the `krbtgt` account, which has an SPN but cannot be kerberoasted
+const KRBTGT_RID: u32 = 502;
+
+#[derive(Display, Serialize, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+pub enum Risk {
+    #[strum(serialize = "high")]
+    #[serde(rename = "high")]
+    High,
+
+    #[strum(serialize = "medium")]
+    #[serde(rename = "medium")]
+    Medium,
+
+    #[strum(serialize = "low")]
+    #[serde(rename = "low")]
+    Low,
+}
+
+/// a user account which is vulnerable to Kerberoasting (because it has an
+/// SPN) or to AS-REP roasting (because it doesn't require preauthentication)
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct RoastableAccount {
+    risk: Risk,
+    sam_account_name: Option<String>,
+    distinguished_name: Option<String>,
+    sid: Option<Sid>,
+    kerberoastable: bool,
+    asrep_roastable: bool,
+    service_principal_names: String,
+    is_enabled: bool,
+    admin_count: Option<i32>,
+    password_last_set: Option<WindowsFileTime>,
+    password_age_days: Option<i64>,
+    supported_encryption_types: Option<SupportedEncryptionTypes>,
+    privileged_groups: String,
+}
+
+impl RoastableAccount {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let person_class = match data_table.schema().type_entry("Person") {
+            Some(ptr) => ptr,
+            None => {
+                log::error!("the schema has no class named 'Person'");
+                return Ok(Vec::new());
+            }
+        };
+
+        let metadata = data_table.data_table().metadata();
+        let encryption_types_column = data_table
+            .data_table()
+            .column_of_attribute(ENCRYPTION_TYPES_ATTRIBUTE);
+        let now = Utc::now().timestamp();
+        let mut accounts = Vec::new();
+
+        for entry in metadata.entries_of_type(person_class.ds_record_id()) {
+            if entry
+                .sid()
+                .as_ref()
+                .is_some_and(|sid| *sid.get_rid() == KRBTGT_RID)
+            {
+                continue;
+            }
+
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+            let uac = record
+                .att_user_account_control_opt()?
+                .unwrap_or(UserAccountControl::empty());
+
+            let service_principal_names =
+                record.att_service_principal_name_opt()?.unwrap_or_default();
+            let kerberoastable = !service_principal_names.is_empty();
+            let asrep_roastable = uac.contains(UserAccountControl::ADS_UF_DONT_REQUIRE_PREAUTH);
+            if !(kerberoastable || asrep_roastable) {
+                continue;
+            }
+
+            let is_enabled = !uac.contains(UserAccountControl::ADS_UF_ACCOUNTDISABLE);
+            let admin_count = record.att_admin_count_opt()?;
+
+            // a value of 0 means that the user must change the password at next logon
+            let password_last_set = record
+                .att_password_last_set_opt()?
+                .filter(|ts| ts.timestamp() > 0);
+            let password_age_days = password_last_set
+                .as_ref()
+                .map(|ts| (now - ts.timestamp()) / 86400);
+
+            let supported_encryption_types = match encryption_types_column {
+                Some(column) => record.get_value_of_column_opt(column)?,
+                None => None,
+            };
+
+            let privileged_groups =
+                data_table.privileged_groups_of(entry, record.att_primary_group_id_opt()?);
+
+            let risk = if !is_enabled {
+                Risk::Low
+            } else if !privileged_groups.is_empty() {
+                Risk::High
+            } else if admin_count == Some(1)
+                || password_age_days.map_or(true, |age| age > OLD_PASSWORD_DAYS)
+                || supported_encryption_types
+                    .as_ref()
+                    .map_or(true, SupportedEncryptionTypes::lacks_aes)
+            {
+                Risk::Medium
+            } else {
+                Risk::Low
+            };
+
+            accounts.push(Self {
+                risk,
+                sam_account_name: entry.sam_account_name().clone(),
+                distinguished_name: data_table.object_tree().dn_of(entry.record_ptr()),
+                sid: entry.sid().clone(),
+                kerberoastable,
+                asrep_roastable,
+                service_principal_names: service_principal_names.join(", "),
+                is_enabled,
+                admin_count,
+                password_last_set,
+                password_age_days,
+                supported_encryption_types,
+                privileged_groups: privileged_groups.join(", "),
+            });
+        }
+
+        // order by risk, and then show the oldest passwords first
+        accounts.sort_by_key(|a| {
+            (
+                a.risk,
+                std::cmp::Reverse(a.password_age_days.unwrap_or(i64::MAX)),
+            )
+        });
+        Ok(accounts)
+    }
+}
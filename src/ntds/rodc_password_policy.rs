@@ -0,0 +1,427 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use getset::Getters;
+use serde::Serialize;
+use strum::Display;
+
+use crate::cache::{DataEntryCore, RecordId};
+use crate::win32_types::{Sid, UserAccountControl};
+
+use super::{DataTable, Link};
+
+/// common name of the schema attribute which lists all accounts whose
+/// secrets have been replicated to an RODC
+const REVEALED_LIST: &str = "ms-DS-Revealed-List";
+
+/// common name of the schema attribute which lists all principals whose
+/// secrets may be cached on an RODC
+const REVEAL_ON_DEMAND_GROUP: &str = "ms-DS-Reveal-OnDemand-Group";
+
+/// common name of the schema attribute which lists all principals whose
+/// secrets must never be cached on an RODC
+const NEVER_REVEAL_GROUP: &str = "ms-DS-Never-Reveal-Group";
+
+/// common name of the schema attribute which refers to the `krbtgt` account
+/// of an RODC
+const KRBTGT_LINK: &str = "ms-DS-KrbTgt-Link";
+
+#[derive(Display, Serialize, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+pub enum PasswordReplication {
+    /// the secrets of the account are cached on the RODC
+    #[strum(serialize = "revealed")]
+    #[serde(rename = "revealed")]
+    Revealed,
+
+    /// the principal is contained in `msDS-RevealOnDemandGroup`
+    #[strum(serialize = "allowed")]
+    #[serde(rename = "allowed")]
+    Allowed,
+
+    /// the principal is contained in `msDS-NeverRevealGroup`
+    #[strum(serialize = "denied")]
+    #[serde(rename = "denied")]
+    Denied,
+}
+
+/// a principal which is part of the password replication policy of a
+/// read-only domain controller, or whose secrets have been revealed to it
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct RodcPasswordPolicyEntry {
+    rodc: String,
+    krbtgt_account: Option<String>,
+    replication: PasswordReplication,
+    principal: String,
+    object_class: Option<String>,
+    distinguished_name: Option<String>,
+    sid: Option<Sid>,
+
+    /// the principal is (or contains) a privileged account, and is allowed
+    /// to be cached on the RODC or has already been revealed to it
+    is_exposed: bool,
+    privileged_groups: String,
+
+    /// privileged accounts which are members of an allowed group
+    privileged_members: String,
+}
+
+/// the linked attributes of the password replication policy
+struct PolicyLinks {
+    revealed: Option<u32>,
+    allowed: Option<u32>,
+    denied: Option<u32>,
+    krbtgt: Option<u32>,
+}
+
+impl PolicyLinks {
+    fn new(data_table: &DataTable) -> crate::ntds::Result<Self> {
+        Ok(Self {
+            revealed: data_table.link_id_of(REVEALED_LIST)?,
+            allowed: data_table.link_id_of(REVEAL_ON_DEMAND_GROUP)?,
+            denied: data_table.link_id_of(NEVER_REVEAL_GROUP)?,
+            krbtgt: data_table.link_id_of(KRBTGT_LINK)?,
+        })
+    }
+
+    /// returns the targets of the linked attribute of an object, without
+    /// duplicates (there is one `msDS-RevealedList` value per secret)
+    fn targets_of(
+        data_table: &DataTable,
+        link_id: Option<u32>,
+        object: &RecordId,
+    ) -> HashSet<RecordId> {
+        match link_id {
+            Some(link_id) => targets_in(data_table.link_table().links_of(link_id), object),
+            None => HashSet::new(),
+        }
+    }
+}
+
+/// returns the targets of all links of `object` which have not been removed
+fn targets_in<'l>(links: impl Iterator<Item = &'l Link>, object: &RecordId) -> HashSet<RecordId> {
+    links
+        .filter(|link| !link.is_deleted() && link.link_dnt() == object)
+        .map(|link| *link.backlink_dnt())
+        .collect()
+}
+
+impl RodcPasswordPolicyEntry {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let metadata = data_table.data_table().metadata();
+        let links = PolicyLinks::new(data_table)?;
+        if links.revealed.is_none() && links.allowed.is_none() {
+            log::error!("the schema does not support read-only domain controllers");
+            return Ok(Vec::new());
+        }
+
+        let mut privileged_groups = HashMap::new();
+        let mut entries = Vec::new();
+
+        for rodc in Self::find_rodcs(data_table)? {
+            let rodc = match metadata.record(&rodc) {
+                Some(rodc) => rodc,
+                None => continue,
+            };
+            let rodc_name = Self::name_of(rodc);
+            let rodc_id = rodc.record_ptr().ds_record_id();
+            let krbtgt_account = PolicyLinks::targets_of(data_table, links.krbtgt, rodc_id)
+                .into_iter()
+                .next()
+                .and_then(|id| metadata.record(&id))
+                .map(Self::name_of);
+
+            for (replication, link_id) in [
+                (PasswordReplication::Revealed, links.revealed),
+                (PasswordReplication::Allowed, links.allowed),
+                (PasswordReplication::Denied, links.denied),
+            ] {
+                for target in PolicyLinks::targets_of(data_table, link_id, rodc_id) {
+                    let principal = match metadata.record(&target) {
+                        Some(principal) => principal,
+                        None => continue,
+                    };
+
+                    let groups = Self::cached_privileged_groups(
+                        data_table,
+                        &mut privileged_groups,
+                        principal,
+                    )?
+                    .clone();
+
+                    // a group can contain privileged accounts without being
+                    // privileged itself
+                    let mut members = BTreeSet::new();
+                    if replication == PasswordReplication::Allowed {
+                        for member in data_table.members_of(principal.record_ptr())? {
+                            let member = &metadata[&member];
+                            if member.sam_account_name().is_some()
+                                && (member.sid().as_ref().is_some_and(Sid::is_privileged)
+                                    || !Self::cached_privileged_groups(
+                                        data_table,
+                                        &mut privileged_groups,
+                                        member,
+                                    )?
+                                    .is_empty())
+                            {
+                                members.insert(Self::name_of(member));
+                            }
+                        }
+                    }
+
+                    let is_exposed = Self::is_exposed_principal(
+                        replication,
+                        principal.sid().as_ref().is_some_and(Sid::is_privileged),
+                        &groups,
+                        &members,
+                    );
+
+                    entries.push(Self {
+                        rodc: rodc_name.clone(),
+                        krbtgt_account: krbtgt_account.clone(),
+                        replication,
+                        principal: Self::name_of(principal),
+                        object_class: metadata.object_class_name(principal).map(str::to_string),
+                        distinguished_name: data_table.object_tree().dn_of(principal.record_ptr()),
+                        sid: principal.sid().clone(),
+                        is_exposed,
+                        privileged_groups: groups.join(", "),
+                        privileged_members: members.into_iter().collect::<Vec<_>>().join(", "),
+                    });
+                }
+            }
+        }
+
+        Self::sort(&mut entries);
+        Ok(entries)
+    }
+
+    /// `true` if the principal is (or contains) a privileged account, and its
+    /// secrets may be cached on the RODC
+    fn is_exposed_principal(
+        replication: PasswordReplication,
+        is_privileged_principal: bool,
+        privileged_groups: &[String],
+        privileged_members: &BTreeSet<String>,
+    ) -> bool {
+        let is_privileged = is_privileged_principal
+            || !privileged_groups.is_empty()
+            || !privileged_members.is_empty();
+        is_privileged && replication != PasswordReplication::Denied
+    }
+
+    /// orders the entries by RODC, and shows exposed principals first
+    fn sort(entries: &mut [Self]) {
+        entries.sort_by(|lhs, rhs| {
+            (&lhs.rodc, !lhs.is_exposed, lhs.replication, &lhs.principal).cmp(&(
+                &rhs.rodc,
+                !rhs.is_exposed,
+                rhs.replication,
+                &rhs.principal,
+            ))
+        });
+    }
+
+    /// returns the computer accounts of all read-only domain controllers.
+    /// These have the `PARTIAL_SECRETS_ACCOUNT` flag set, and their server
+    /// object contains an `nTDSDSARO` object
+    fn find_rodcs(data_table: &DataTable) -> crate::ntds::Result<HashSet<RecordId>> {
+        let metadata = data_table.data_table().metadata();
+        let mut rodcs = HashSet::new();
+
+        if let Some(class) = data_table.schema().type_entry("Computer") {
+            for computer in metadata.entries_of_type(class.ds_record_id()) {
+                if computer.rdn().deleted_from_container().is_some() {
+                    continue;
+                }
+                let uac = data_table
+                    .data_table()
+                    .data_table_record_from(*computer.record_ptr())?
+                    .att_user_account_control_opt()?
+                    .unwrap_or(UserAccountControl::empty());
+                if uac.contains(UserAccountControl::ADS_UF_PARTIAL_SECRETS_ACCOUNT) {
+                    rodcs.insert(*computer.record_ptr().ds_record_id());
+                }
+            }
+        }
+
+        if let Some(class) = data_table.schema().type_entry("NTDS-DSA-RO") {
+            for ntds_settings in metadata.entries_of_type(class.ds_record_id()) {
+                if ntds_settings.rdn().deleted_from_container().is_some() {
+                    continue;
+                }
+                let server = match metadata.ptr_from_id(ntds_settings.parent()) {
+                    Some(server) => server,
+                    None => continue,
+                };
+                if let Some(computer) = data_table
+                    .data_table()
+                    .data_table_record_from(*server)?
+                    .att_server_reference_opt()?
+                {
+                    rodcs.insert(computer);
+                }
+            }
+        }
+
+        Ok(rodcs)
+    }
+
+    /// returns the privileged groups of a principal. Principals can be
+    /// listed for multiple RODCs, so the result is cached
+    fn cached_privileged_groups<'c>(
+        data_table: &DataTable,
+        cache: &'c mut HashMap<RecordId, Vec<String>>,
+        entry: &DataEntryCore,
+    ) -> crate::ntds::Result<&'c Vec<String>> {
+        match cache.entry(*entry.record_ptr().ds_record_id()) {
+            Entry::Occupied(groups) => Ok(groups.into_mut()),
+            Entry::Vacant(vacant) => {
+                let primary_group_id = data_table
+                    .data_table()
+                    .data_table_record_from(*entry.record_ptr())?
+                    .att_primary_group_id_opt()?;
+                Ok(vacant.insert(data_table.privileged_groups_of(entry, primary_group_id)))
+            }
+        }
+    }
+
+    fn name_of(entry: &DataEntryCore) -> String {
+        entry
+            .sam_account_name()
+            .clone()
+            .unwrap_or_else(|| entry.rdn().name().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashSet};
+
+    use crate::cache::RecordId;
+    use crate::ntds::Link;
+
+    use super::{targets_in, PasswordReplication, RodcPasswordPolicyEntry};
+
+    impl RodcPasswordPolicyEntry {
+        fn with(
+            rodc: &str,
+            replication: PasswordReplication,
+            principal: &str,
+            is_exposed: bool,
+        ) -> Self {
+            Self {
+                rodc: rodc.to_string(),
+                krbtgt_account: None,
+                replication,
+                principal: principal.to_string(),
+                object_class: None,
+                distinguished_name: None,
+                sid: None,
+                is_exposed,
+                privileged_groups: String::new(),
+                privileged_members: String::new(),
+            }
+        }
+    }
+
+    fn link(rodc: i32, target: i32, is_deleted: bool) -> Link {
+        Link::new(rodc.into(), target.into(), None, is_deleted, None, None)
+    }
+
+    #[test]
+    fn test_targets() {
+        let rodc = RecordId::from(1);
+        let links = [
+            // there is one value of msDS-RevealedList per secret
+            link(1, 10, false),
+            link(1, 10, false),
+            link(1, 11, false),
+            link(1, 12, true),
+            link(2, 13, false),
+        ];
+        assert_eq!(
+            targets_in(links.iter(), &rodc),
+            HashSet::from([RecordId::from(10), RecordId::from(11)])
+        );
+
+        // msDS-KrbTgtLink of the RODC
+        let krbtgt = [link(2, 20, false), link(1, 21, false)];
+        assert_eq!(
+            targets_in(krbtgt.iter(), &rodc),
+            HashSet::from([RecordId::from(21)])
+        );
+    }
+
+    #[test]
+    fn test_is_exposed() {
+        let no_groups: Vec<String> = Vec::new();
+        let no_members = BTreeSet::new();
+        let groups = vec!["Domain Admins".to_string()];
+        let members = BTreeSet::from(["Administrator".to_string()]);
+
+        for replication in [PasswordReplication::Revealed, PasswordReplication::Allowed] {
+            assert!(RodcPasswordPolicyEntry::is_exposed_principal(
+                replication,
+                true,
+                &no_groups,
+                &no_members
+            ));
+            assert!(RodcPasswordPolicyEntry::is_exposed_principal(
+                replication,
+                false,
+                &groups,
+                &no_members
+            ));
+            assert!(RodcPasswordPolicyEntry::is_exposed_principal(
+                replication,
+                false,
+                &no_groups,
+                &members
+            ));
+            assert!(!RodcPasswordPolicyEntry::is_exposed_principal(
+                replication,
+                false,
+                &no_groups,
+                &no_members
+            ));
+        }
+        assert!(!RodcPasswordPolicyEntry::is_exposed_principal(
+            PasswordReplication::Denied,
+            true,
+            &groups,
+            &members
+        ));
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut entries = vec![
+            RodcPasswordPolicyEntry::with("RODC2", PasswordReplication::Revealed, "alice", false),
+            RodcPasswordPolicyEntry::with(
+                "RODC1",
+                PasswordReplication::Denied,
+                "Domain Admins",
+                false,
+            ),
+            RodcPasswordPolicyEntry::with("RODC1", PasswordReplication::Revealed, "bob", false),
+            RodcPasswordPolicyEntry::with("RODC1", PasswordReplication::Allowed, "Helpdesk", true),
+            RodcPasswordPolicyEntry::with("RODC1", PasswordReplication::Allowed, "carol", false),
+        ];
+        RodcPasswordPolicyEntry::sort(&mut entries);
+        let order: Vec<_> = entries
+            .iter()
+            .map(|e| (e.rodc.as_str(), e.principal.as_str()))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("RODC1", "Helpdesk"),
+                ("RODC1", "bob"),
+                ("RODC1", "carol"),
+                ("RODC1", "Domain Admins"),
+                ("RODC2", "alice")
+            ]
+        );
+    }
+}
@@ -12,19 +12,33 @@ use super::ObjectType;
 pub struct Schema {
     supported_type_entries: HashMap<ObjectType, RecordPointer>,
     all_type_entries: HashSet<RecordPointer>,
+
+    #[getset(skip)]
+    type_entries_by_name: HashMap<String, RecordPointer>,
 }
 
 impl Schema {
     pub fn new(metadata: &MetaDataCache, special_records: &SpecialRecords) -> Self {
         let mut supported_type_entries = HashMap::new();
         let mut all_type_entries = HashSet::new();
+        let mut type_entries_by_name = HashMap::new();
         for record in metadata.children_of(special_records.schema().record_ptr()) {
             if let Ok(object_type) = ObjectType::try_from(&record.rdn().name()[..]) {
                 supported_type_entries.insert(object_type, *record.record_ptr());
             }
             all_type_entries.insert(*record.record_ptr());
+            type_entries_by_name.insert(record.rdn().name().to_string(), *record.record_ptr());
+        }
+        Self {
+            supported_type_entries,
+            all_type_entries,
+            type_entries_by_name,
         }
-        Self { supported_type_entries, all_type_entries }
+    }
+
+    /// returns the schema entry with the given common name, e.g. `Organizational-Unit`
+    pub fn type_entry(&self, name: &str) -> Option<&RecordPointer> {
+        self.type_entries_by_name.get(name)
     }
 }
 
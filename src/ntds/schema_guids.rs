@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::win32_types::Guid;
+
+use super::DataTable;
+
+/// maps the GUIDs which are used in object specific ACEs to human readable
+/// names. These are the `schemaIDGUID` values of classes and attributes
+/// and the `rightsGuid` values of extended rights.
+pub struct SchemaGuids {
+    names: HashMap<Guid, String>,
+}
+
+impl SchemaGuids {
+    pub fn new(data_table: &DataTable) -> crate::ntds::Result<Self> {
+        log::info!("reading schema GUIDs and extended rights");
+        let metadata = data_table.data_table().metadata();
+        let mut names = HashMap::new();
+
+        for entry in metadata.children_of(data_table.special_records().schema().record_ptr()) {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+            if let Some(guid) = record.att_schema_id_guid_opt()? {
+                let name = record
+                    .att_ldap_display_name_opt()?
+                    .unwrap_or_else(|| entry.rdn().name().to_string());
+                names.insert(guid, name);
+            }
+        }
+
+        match data_table
+            .special_records()
+            .configuration()
+            .find_child_by_name("Extended-Rights")
+        {
+            Some(extended_rights) => {
+                for entry in metadata.children_of(extended_rights.record_ptr()) {
+                    let record = data_table
+                        .data_table()
+                        .data_table_record_from(*entry.record_ptr())?;
+                    if let Some(rights_guid) = record.att_rights_guid_opt()? {
+                        match Guid::from_str(&rights_guid) {
+                            Ok(guid) => {
+                                names.insert(guid, entry.rdn().name().to_string());
+                            }
+                            Err(why) => {
+                                log::warn!("invalid rightsGuid '{rights_guid}' in {entry}: {why}")
+                            }
+                        }
+                    }
+                }
+            }
+            None => log::warn!("db has no `Extended-Rights` entry"),
+        }
+
+        log::debug!("found {} schema GUIDs", names.len());
+        Ok(Self { names })
+    }
+
+    pub fn name_of(&self, guid: &Guid) -> Option<&str> {
+        self.names.get(guid).map(|s| &s[..])
+    }
+
+    /// returns the name of the GUID, or the GUID itself if no name is known
+    pub fn display_name(&self, guid: &Guid) -> String {
+        self.name_of(guid)
+            .map(String::from)
+            .unwrap_or_else(|| guid.to_string())
+    }
+}
@@ -0,0 +1,104 @@
+use getset::Getters;
+use serde::Serialize;
+
+use crate::win32_types::{Guid, KeyCredential, WindowsFileTime};
+
+use super::DataTable;
+
+/// common name of the schema entry of `msDS-KeyCredentialLink`. This
+/// attribute is not contained in `attids.h`
+const KEY_CREDENTIAL_LINK_ATTRIBUTE: &str = "ms-DS-Key-Credential-Link";
+
+/// classes of accounts which can use key credentials to authenticate
+const ACCOUNT_CLASSES: [&str; 2] = ["Person", "Computer"];
+
+/// a single value of `msDS-KeyCredentialLink`
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct ShadowCredential {
+    account: Option<String>,
+    object_class: Option<String>,
+    distinguished_name: Option<String>,
+    key_id: Option<String>,
+    key_usage: Option<String>,
+    key_source: Option<String>,
+    device_id: Option<Guid>,
+    creation_time: Option<WindowsFileTime>,
+    last_logon_time: Option<WindowsFileTime>,
+    custom_key_information: Option<String>,
+    key_size: Option<usize>,
+    public_key: Option<String>,
+
+    /// `true` if the value has been removed, but not yet been garbage collected
+    is_deleted: bool,
+
+    /// `true` if the key credential has been added to a user or computer
+    /// account, but seems not to be created by Windows Hello for Business
+    is_suspicious: bool,
+}
+
+impl ShadowCredential {
+    pub fn collect(data_table: &DataTable, export_keys: bool) -> crate::ntds::Result<Vec<Self>> {
+        let link_id = match data_table.link_id_of(KEY_CREDENTIAL_LINK_ATTRIBUTE)? {
+            Some(link_id) => link_id,
+            None => {
+                log::error!("the schema does not contain '{KEY_CREDENTIAL_LINK_ATTRIBUTE}'; this might be a pre-2016 schema");
+                return Ok(Vec::new());
+            }
+        };
+
+        let metadata = data_table.data_table().metadata();
+        let mut credentials = Vec::new();
+
+        for link in data_table.link_table().links_of(link_id) {
+            let data = match link.data() {
+                Some(data) => data,
+                None => continue,
+            };
+
+            let key = match KeyCredential::try_from(&data[..]) {
+                Ok(key) => key,
+                Err(why) => {
+                    log::warn!(
+                        "unable to parse a key credential of the object with id {}: {why}",
+                        link.link_dnt()
+                    );
+                    continue;
+                }
+            };
+
+            let entry = metadata.record(link.link_dnt());
+            let object_class = entry.and_then(|e| metadata.object_class_name(e));
+            let is_account = object_class.is_some_and(|c| ACCOUNT_CLASSES.contains(&c));
+
+            credentials.push(Self {
+                account: entry.map(|e| {
+                    e.sam_account_name()
+                        .clone()
+                        .unwrap_or_else(|| e.rdn().name().to_string())
+                }),
+                object_class: object_class.map(String::from),
+                distinguished_name: entry
+                    .and_then(|e| data_table.object_tree().dn_of(e.record_ptr())),
+                key_id: key.key_id().as_ref().map(hex::encode),
+                key_usage: key.key_usage().map(|u| u.to_string()),
+                key_source: key.key_source().map(|s| s.to_string()),
+                device_id: key.device_id().clone(),
+                custom_key_information: key.custom_key_information().as_ref().map(hex::encode),
+                key_size: key.public_key().as_ref().and_then(|k| k.key_size()),
+                public_key: if export_keys {
+                    key.public_key().as_ref().and_then(|k| k.to_pem())
+                } else {
+                    None
+                },
+                is_deleted: *link.is_deleted(),
+                is_suspicious: is_account && !key.is_windows_hello_key(),
+                creation_time: key.creation_time().clone(),
+                last_logon_time: key.last_logon_time().clone(),
+            });
+        }
+
+        credentials.sort_by_key(|c| !c.is_suspicious);
+        Ok(credentials)
+    }
+}
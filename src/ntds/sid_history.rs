@@ -0,0 +1,80 @@
+use getset::Getters;
+use serde::Serialize;
+
+use crate::win32_types::Sid;
+
+use super::DataTable;
+
+/// a single value of `sIDHistory`
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct SidHistoryEntry {
+    account: Option<String>,
+    object_class: Option<String>,
+    distinguished_name: Option<String>,
+    sid: Option<Sid>,
+    historical_sid: Sid,
+
+    /// the historical SID belongs to the current domain, which never
+    /// happens during a legitimate migration
+    same_domain: bool,
+
+    /// the historical SID has a privileged RID, e.g. Domain Admins
+    privileged: bool,
+
+    /// the object which currently owns the historical SID
+    resolved_object: Option<String>,
+    resolved_is_deleted: bool,
+
+    is_suspicious: bool,
+}
+
+impl SidHistoryEntry {
+    pub fn collect(data_table: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let metadata = data_table.data_table().metadata();
+        let domain_sid = data_table.domain_sid();
+        if domain_sid.is_none() {
+            log::warn!("unable to find the SID of the current domain");
+        }
+
+        let mut entries = Vec::new();
+        for entry in metadata.iter().filter(|e| e.sid().is_some()) {
+            let record = data_table
+                .data_table()
+                .data_table_record_from(*entry.record_ptr())?;
+            let sid_history = match record.att_sid_history_opt()? {
+                Some(sid_history) => sid_history,
+                None => continue,
+            };
+
+            for historical_sid in sid_history {
+                let same_domain = domain_sid.is_some()
+                    && historical_sid.domain_sid().as_ref() == domain_sid.as_ref();
+                let privileged = historical_sid.is_privileged();
+                let resolved = metadata
+                    .ptr_from_sid(&historical_sid)
+                    .and_then(|ptr| metadata.record(ptr.ds_record_id()));
+
+                entries.push(Self {
+                    account: entry
+                        .sam_account_name()
+                        .clone()
+                        .or_else(|| Some(entry.rdn().name().to_string())),
+                    object_class: metadata.object_class_name(entry).map(String::from),
+                    distinguished_name: data_table.object_tree().dn_of(entry.record_ptr()),
+                    sid: entry.sid().clone(),
+                    resolved_object: resolved.and_then(|r| metadata.dn(r)),
+                    resolved_is_deleted: resolved
+                        .is_some_and(|r| r.rdn().deleted_from_container().is_some()),
+                    is_suspicious: same_domain || privileged,
+                    historical_sid,
+                    same_domain,
+                    privileged,
+                });
+            }
+        }
+
+        entries.sort_by_key(|e| !e.is_suspicious);
+        Ok(entries)
+    }
+}
@@ -0,0 +1,454 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use getset::Getters;
+use serde::Serialize;
+use strum::Display;
+
+use crate::cache::{DataEntryCore, RecordId, RecordPointer};
+use crate::win32_types::Guid;
+
+use super::{DataTable, NtdsAttributeId};
+
+/// attributes which are changed by every modification of an object, or
+/// which are compared separately
+const IGNORED_ATTRIBUTES: [NtdsAttributeId; 6] = [
+    NtdsAttributeId::AttWhenChanged,
+    NtdsAttributeId::AttUsnChanged,
+    NtdsAttributeId::AttReplPropertyMetaData,
+    NtdsAttributeId::AttNtSecurityDescriptor,
+    NtdsAttributeId::AttRdn,
+    NtdsAttributeId::AttCommonName,
+];
+
+/// prefix of the names of columns which contain DN values. These values
+/// contain record ids, which are specific to every database
+const DN_COLUMN_PREFIX: &str = "ATTb";
+
+#[derive(Display, Serialize, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+pub enum Change {
+    #[strum(serialize = "added")]
+    #[serde(rename = "added")]
+    Added,
+
+    #[strum(serialize = "deleted")]
+    #[serde(rename = "deleted")]
+    Deleted,
+
+    #[strum(serialize = "restored")]
+    #[serde(rename = "restored")]
+    Restored,
+
+    #[strum(serialize = "moved")]
+    #[serde(rename = "moved")]
+    Moved,
+
+    #[strum(serialize = "renamed")]
+    #[serde(rename = "renamed")]
+    Renamed,
+
+    #[strum(serialize = "modified")]
+    #[serde(rename = "modified")]
+    Modified,
+
+    #[strum(serialize = "member-added")]
+    #[serde(rename = "member-added")]
+    MemberAdded,
+
+    #[strum(serialize = "member-removed")]
+    #[serde(rename = "member-removed")]
+    MemberRemoved,
+
+    #[strum(serialize = "security-descriptor")]
+    #[serde(rename = "security-descriptor")]
+    SecurityDescriptor,
+}
+
+/// a difference between two snapshots of the same database. Objects are
+/// matched by their `objectGUID`
+#[derive(Getters, Serialize)]
+#[getset(get = "pub")]
+pub struct SnapshotDiff {
+    change: Change,
+    object_guid: Guid,
+    distinguished_name: Option<String>,
+    object_class: Option<String>,
+    attribute: Option<String>,
+    old_value: Option<String>,
+    new_value: Option<String>,
+}
+
+/// attribute values of an object, indexed by the attribute id. The values
+/// of multi-valued attributes are compared as sets
+type AttributeMap = BTreeMap<NtdsAttributeId, (String, BTreeSet<String>)>;
+
+/// one of the two databases which are compared
+struct Snapshot<'d, 'info, 'db> {
+    data_table: &'d DataTable<'info, 'db>,
+    guids: HashMap<RecordId, Guid>,
+}
+
+impl<'d, 'info, 'db> Snapshot<'d, 'info, 'db> {
+    fn new(data_table: &'d DataTable<'info, 'db>) -> Self {
+        let guids = data_table
+            .data_table()
+            .metadata()
+            .records_by_guid()
+            .map(|(guid, ptr)| (*ptr.ds_record_id(), guid.clone()))
+            .collect();
+        Self { data_table, guids }
+    }
+
+    fn dn_of(&self, ptr: &RecordPointer) -> Option<String> {
+        self.data_table.object_tree().dn_of(ptr)
+    }
+
+    fn dn_of_id(&self, id: &RecordId) -> Option<String> {
+        self.data_table
+            .data_table()
+            .metadata()
+            .ptr_from_id(id)
+            .and_then(|ptr| self.dn_of(ptr))
+    }
+
+    fn entry(&self, ptr: &RecordPointer) -> &'d DataEntryCore {
+        &self.data_table.data_table().metadata()[ptr]
+    }
+
+    /// returns all attribute values of an object. Values which refer to
+    /// other objects are replaced by their distinguished name
+    fn attributes_of(&self, ptr: &RecordPointer) -> crate::ntds::Result<AttributeMap> {
+        let record = self.data_table.data_table().data_table_record_from(*ptr)?;
+        Ok(record
+            .all_attributes()
+            .into_iter()
+            .filter(|(id, _)| !IGNORED_ATTRIBUTES.contains(id))
+            .map(|(id, attribute)| {
+                let is_dn = attribute.column().starts_with(DN_COLUMN_PREFIX);
+                let values = attribute
+                    .values()
+                    .iter()
+                    .map(|value| {
+                        if is_dn {
+                            value
+                                .parse::<i32>()
+                                .ok()
+                                .and_then(|id| self.dn_of_id(&RecordId::from(id)))
+                                .unwrap_or_else(|| value.clone())
+                        } else {
+                            value.clone()
+                        }
+                    })
+                    .collect();
+                (id, (attribute.attribute().to_string(), values))
+            })
+            .collect())
+    }
+
+    /// returns the security descriptor of an object in SDDL format
+    fn security_descriptor_of(&self, entry: &DataEntryCore) -> Option<String> {
+        let sd_table = self.data_table.sd_table().as_ref()?;
+        match sd_table.descriptor(&(*entry.sd_id())?)? {
+            Ok(sd) => Some(sd.to_string()),
+            Err(why) => {
+                log::warn!("unable to read the security descriptor of '{entry}': {why}");
+                None
+            }
+        }
+    }
+
+    /// returns the members of all groups, indexed by the `objectGUID` of the
+    /// group
+    fn memberships(&self) -> crate::ntds::Result<HashMap<Guid, HashSet<Guid>>> {
+        let mut memberships: HashMap<Guid, HashSet<Guid>> = HashMap::new();
+        if let Some(link_id) = self.data_table.link_id_of("Member")? {
+            for link in self
+                .data_table
+                .link_table()
+                .links_of(link_id)
+                .filter(|link| !link.is_deleted())
+            {
+                if let (Some(group), Some(member)) = (
+                    self.guids.get(link.link_dnt()),
+                    self.guids.get(link.backlink_dnt()),
+                ) {
+                    memberships
+                        .entry(group.clone())
+                        .or_default()
+                        .insert(member.clone());
+                }
+            }
+        }
+        Ok(memberships)
+    }
+
+    fn dn_of_guid(&self, guid: &Guid) -> Option<String> {
+        self.data_table
+            .data_table()
+            .metadata()
+            .ptr_from_guid(guid)
+            .and_then(|ptr| self.dn_of(ptr))
+    }
+}
+
+impl SnapshotDiff {
+    /// compares two snapshots. All changes are reported from the point of
+    /// view of `baseline`, e.g. objects which only exist in `current` are
+    /// reported as `added`
+    pub fn collect(baseline: &DataTable, current: &DataTable) -> crate::ntds::Result<Vec<Self>> {
+        let baseline = Snapshot::new(baseline);
+        let current = Snapshot::new(current);
+        let baseline_metadata = baseline.data_table.data_table().metadata();
+        let current_metadata = current.data_table.data_table().metadata();
+        let compare_sds =
+            baseline.data_table.sd_table().is_some() && current.data_table.sd_table().is_some();
+        let mut changes = Vec::new();
+
+        for (guid, ptr) in current_metadata.records_by_guid() {
+            let entry = current.entry(ptr);
+            let diff = |change, attribute: Option<&str>, old_value, new_value| Self {
+                change,
+                object_guid: guid.clone(),
+                distinguished_name: current.dn_of(ptr),
+                object_class: current_metadata
+                    .object_class_name(entry)
+                    .map(str::to_string),
+                attribute: attribute.map(str::to_string),
+                old_value,
+                new_value,
+            };
+
+            let old_ptr = match baseline_metadata.ptr_from_guid(guid) {
+                Some(old_ptr) => old_ptr,
+                None => {
+                    changes.push(diff(Change::Added, None, None, None));
+                    continue;
+                }
+            };
+            let old_entry = baseline.entry(old_ptr);
+
+            match (
+                old_entry.rdn().deleted_from_container().is_some(),
+                entry.rdn().deleted_from_container().is_some(),
+            ) {
+                (false, true) => {
+                    changes.push(diff(Change::Deleted, None, baseline.dn_of(old_ptr), None))
+                }
+                (true, false) => {
+                    changes.push(diff(Change::Restored, None, None, current.dn_of(ptr)))
+                }
+                (false, false) => {
+                    if old_entry.rdn().name() != entry.rdn().name() {
+                        changes.push(diff(
+                            Change::Renamed,
+                            None,
+                            Some(old_entry.rdn().name().to_string()),
+                            Some(entry.rdn().name().to_string()),
+                        ));
+                    }
+                    if baseline.guids.get(old_entry.parent()) != current.guids.get(entry.parent()) {
+                        changes.push(diff(
+                            Change::Moved,
+                            None,
+                            baseline.dn_of_id(old_entry.parent()),
+                            current.dn_of_id(entry.parent()),
+                        ));
+                    }
+                }
+                (true, true) => (),
+            }
+
+            let old_attributes = baseline.attributes_of(old_ptr)?;
+            let new_attributes = current.attributes_of(ptr)?;
+            for (name, old_value, new_value) in
+                modified_attributes(&old_attributes, &new_attributes)
+            {
+                changes.push(diff(Change::Modified, Some(name), old_value, new_value));
+            }
+
+            // security descriptors can only be compared if both databases
+            // contain a security descriptor table
+            if compare_sds {
+                let old_sd = baseline.security_descriptor_of(old_entry);
+                let new_sd = current.security_descriptor_of(entry);
+                if old_sd != new_sd {
+                    changes.push(diff(
+                        Change::SecurityDescriptor,
+                        Some("nTSecurityDescriptor"),
+                        old_sd,
+                        new_sd,
+                    ));
+                }
+            }
+        }
+
+        for (guid, ptr) in baseline_metadata.records_by_guid() {
+            if current_metadata.ptr_from_guid(guid).is_none() {
+                changes.push(Self {
+                    change: Change::Deleted,
+                    object_guid: guid.clone(),
+                    distinguished_name: baseline.dn_of(ptr),
+                    object_class: baseline_metadata
+                        .object_class_name(baseline.entry(ptr))
+                        .map(str::to_string),
+                    attribute: None,
+                    old_value: baseline.dn_of(ptr),
+                    new_value: None,
+                });
+            }
+        }
+
+        let old_memberships = baseline.memberships()?;
+        let new_memberships = current.memberships()?;
+        let empty = HashSet::new();
+        let groups: HashSet<_> = old_memberships
+            .keys()
+            .chain(new_memberships.keys())
+            .collect();
+        for group in groups {
+            let old_members = old_memberships.get(group).unwrap_or(&empty);
+            let new_members = new_memberships.get(group).unwrap_or(&empty);
+            let (snapshot, metadata) = match current_metadata.ptr_from_guid(group) {
+                Some(_) => (&current, current_metadata),
+                None => (&baseline, baseline_metadata),
+            };
+            let object_class = metadata
+                .ptr_from_guid(group)
+                .and_then(|ptr| metadata.object_class_name(snapshot.entry(ptr)))
+                .map(str::to_string);
+
+            for member in new_members.difference(old_members) {
+                changes.push(Self {
+                    change: Change::MemberAdded,
+                    object_guid: group.clone(),
+                    distinguished_name: snapshot.dn_of_guid(group),
+                    object_class: object_class.clone(),
+                    attribute: Some("member".to_string()),
+                    old_value: None,
+                    new_value: current.dn_of_guid(member),
+                });
+            }
+            for member in old_members.difference(new_members) {
+                changes.push(Self {
+                    change: Change::MemberRemoved,
+                    object_guid: group.clone(),
+                    distinguished_name: snapshot.dn_of_guid(group),
+                    object_class: object_class.clone(),
+                    attribute: Some("member".to_string()),
+                    old_value: baseline.dn_of_guid(member),
+                    new_value: None,
+                });
+            }
+        }
+
+        changes.sort_by(|lhs, rhs| {
+            (lhs.change, &lhs.distinguished_name, &lhs.attribute).cmp(&(
+                rhs.change,
+                &rhs.distinguished_name,
+                &rhs.attribute,
+            ))
+        });
+        Ok(changes)
+    }
+}
+
+/// returns the name, the old value and the new value of every attribute
+/// whose set of values differs between both objects
+fn modified_attributes<'a>(
+    old_attributes: &'a AttributeMap,
+    new_attributes: &'a AttributeMap,
+) -> Vec<(&'a str, Option<String>, Option<String>)> {
+    let join = |values: &BTreeSet<String>| Vec::from_iter(values.iter().cloned()).join(", ");
+    let ids: BTreeSet<_> = old_attributes.keys().chain(new_attributes.keys()).collect();
+    ids.into_iter()
+        .filter_map(|id| {
+            let old_value = old_attributes.get(id);
+            let new_value = new_attributes.get(id);
+            if old_value.map(|(_, v)| v) == new_value.map(|(_, v)| v) {
+                return None;
+            }
+            let (name, _) = old_value.or(new_value)?;
+            Some((
+                name.as_str(),
+                old_value.map(|(_, v)| join(v)),
+                new_value.map(|(_, v)| join(v)),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use crate::ntds::NtdsAttributeId;
+
+    use super::{modified_attributes, AttributeMap};
+
+    fn attributes(values: &[(NtdsAttributeId, &str, &[&str])]) -> AttributeMap {
+        values
+            .iter()
+            .map(|(id, name, values)| {
+                let values: BTreeSet<_> = values.iter().map(|v| v.to_string()).collect();
+                (*id, (name.to_string(), values))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unmodified_attributes() {
+        let old = attributes(&[
+            (NtdsAttributeId::AttDescription, "description", &["admin"]),
+            (
+                NtdsAttributeId::AttServicePrincipalName,
+                "servicePrincipalName",
+                &["HTTP/a", "HTTP/b"],
+            ),
+        ]);
+
+        // the order of multiple values is not significant
+        let new = attributes(&[
+            (NtdsAttributeId::AttDescription, "description", &["admin"]),
+            (
+                NtdsAttributeId::AttServicePrincipalName,
+                "servicePrincipalName",
+                &["HTTP/b", "HTTP/a"],
+            ),
+        ]);
+        assert!(modified_attributes(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn test_modified_attributes() {
+        let old = attributes(&[
+            (NtdsAttributeId::AttDescription, "description", &["admin"]),
+            (
+                NtdsAttributeId::AttServicePrincipalName,
+                "servicePrincipalName",
+                &["HTTP/a", "HTTP/b"],
+            ),
+        ]);
+        let new = attributes(&[
+            (
+                NtdsAttributeId::AttServicePrincipalName,
+                "servicePrincipalName",
+                &["HTTP/a", "HTTP/b", "HTTP/c"],
+            ),
+            (NtdsAttributeId::AttDisplayName, "displayName", &["Alice"]),
+        ]);
+
+        let mut changes = modified_attributes(&old, &new);
+        changes.sort();
+        assert_eq!(
+            changes,
+            [
+                ("description", Some("admin".to_string()), None),
+                ("displayName", None, Some("Alice".to_string())),
+                (
+                    "servicePrincipalName",
+                    Some("HTTP/a, HTTP/b".to_string()),
+                    Some("HTTP/a, HTTP/b, HTTP/c".to_string())
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,635 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use bodyfile::Bodyfile3Line;
+use chrono::{DateTime, Utc};
+use getset::Getters;
+
+use crate::cache::{ColumnIndex, MetaDataCache, RecordId};
+use crate::win32_types::{
+    CertificateValidity, Guid, ReplicationMetaData, TimelineEntry, TruncatedWindowsFileTime,
+    UnixTimestamp, WindowsFileTime,
+};
+
+use super::{domain_controller_names, DataTable, DataTableRecord, Link, TimelineFilter};
+
+/// `oMSyntax` of `UTC-Time` attributes
+const OM_SYNTAX_UTC_TIME: i32 = 23;
+
+/// `oMSyntax` of `Generalized-Time` attributes, such as `whenCreated`
+const OM_SYNTAX_GENERALIZED_TIME: i32 = 24;
+
+/// `oMSyntax` of `Large-Integer` attributes. Some of them (such as
+/// `pwdLastSet`) contain timestamps, others (such as `uSNChanged`) don't.
+/// Therefore, only the attributes in [`KNOWN_ATTRIBUTES`] and
+/// [`LARGE_INTEGER_TIMESTAMPS`] are read
+const OM_SYNTAX_LARGE_INTEGER: i32 = 65;
+
+/// `Large-Integer` attributes which contain timestamps, but have no
+/// description in [`KNOWN_ATTRIBUTES`]
+const LARGE_INTEGER_TIMESTAMPS: [&str; 4] = [
+    "creationTime",
+    "lastSetTime",
+    "priorSetTime",
+    "msDS-ApproximateLastLogonTimeStamp",
+];
+
+/// attributes which contain DER encoded X.509 certificates
+const CERTIFICATE_ATTRIBUTES: [&str; 3] =
+    ["userCertificate", "cACertificate", "userSMIMECertificate"];
+
+/// descriptions of the events of certificates
+const CERTIFICATE_VALID: &str = "certificate becomes valid";
+const CERTIFICATE_EXPIRES: &str = "certificate expires";
+
+/// values of timestamp attributes outside of this range (1980-01-01 -
+/// 2100-01-01) are ignored. This excludes special values like `0` and
+/// `0x7fffffffffffffff` in `accountExpires`
+const FILETIME_RANGE: std::ops::Range<i64> = 119_600_064_000_000_000..157_469_184_000_000_000;
+
+/// generalized times before 1980-01-01 (in seconds since 1601-01-01) are
+/// ignored, e.g. the placeholder values in `dSCorePropagationData`
+const MIN_TRUNCATED_FILETIME: i64 = 11_960_006_400;
+
+/// the bodyfile timestamp which is used for an event
+#[derive(Clone, Copy)]
+pub enum TimestampType {
+    Created,
+    Changed,
+    Accessed,
+    Modified,
+}
+
+/// descriptions of well-known timestamp attributes. Timestamps of all
+/// other attributes are described by the name of the attribute
+const KNOWN_ATTRIBUTES: [(&str, &str, TimestampType); 14] = [
+    ("whenCreated", "object created", TimestampType::Created),
+    ("whenChanged", "object changed", TimestampType::Changed),
+    (
+        "lastLogon",
+        "last logon on this DC",
+        TimestampType::Accessed,
+    ),
+    (
+        "lastLogonTimestamp",
+        "last logon on any DC",
+        TimestampType::Accessed,
+    ),
+    ("lastLogoff", "last logoff", TimestampType::Accessed),
+    ("badPasswordTime", "bad pwd time", TimestampType::Changed),
+    ("pwdLastSet", "password last set", TimestampType::Modified),
+    ("accountExpires", "account expires", TimestampType::Changed),
+    ("lockoutTime", "account locked out", TimestampType::Changed),
+    (
+        "dSCorePropagationData",
+        "security descriptor propagated",
+        TimestampType::Changed,
+    ),
+    (
+        "msDS-LastSuccessfulInteractiveLogonTime",
+        "last successful interactive logon",
+        TimestampType::Accessed,
+    ),
+    (
+        "msDS-LastFailedInteractiveLogonTime",
+        "last failed interactive logon",
+        TimestampType::Changed,
+    ),
+    (
+        "ms-Mcs-AdmPwdExpirationTime",
+        "LAPS password expires",
+        TimestampType::Changed,
+    ),
+    (
+        "msLAPS-PasswordExpirationTime",
+        "LAPS password expires",
+        TimestampType::Changed,
+    ),
+];
+
+/// information about the whole database, which is needed to create the
+/// events of single objects
+pub struct TimelineContext<'l> {
+    /// names of all domain controllers, indexed by their invocation ID
+    domain_controllers: HashMap<Guid, String>,
+
+    /// values of the `member` attribute, indexed by the group
+    member_links: HashMap<RecordId, Vec<&'l Link>>,
+
+    /// columns of `Generalized-Time` and `UTC-Time` attributes
+    truncated_time_columns: Vec<(ColumnIndex, String)>,
+
+    /// columns of `Large-Integer` attributes
+    large_integer_columns: Vec<(ColumnIndex, String)>,
+
+    /// columns of the attributes in [`CERTIFICATE_ATTRIBUTES`]
+    certificate_columns: Vec<(ColumnIndex, &'static str)>,
+}
+
+impl<'l> TimelineContext<'l> {
+    /// attributes whose events cannot match the `--event` filter are not
+    /// read at all
+    pub fn new(data_table: &'l DataTable, filter: &TimelineFilter) -> crate::ntds::Result<Self> {
+        let mut member_links: HashMap<RecordId, Vec<&Link>> = HashMap::new();
+        if let Some(link_id) = data_table.link_id_of("Member")? {
+            for link in data_table.link_table().links_of(link_id) {
+                member_links.entry(*link.link_dnt()).or_default().push(link);
+            }
+        }
+
+        // the attributes are looked up only once, because the schema
+        // contains more than a thousand attributes
+        let metadata = data_table.data_table().metadata();
+        let columns_with_syntax = |om_syntaxes: &[i32]| -> Vec<(ColumnIndex, String)> {
+            om_syntaxes
+                .iter()
+                .flat_map(|om_syntax| {
+                    metadata
+                        .attributes_with_syntax(*om_syntax)
+                        .filter(|(_, name)| Self::is_selected(*om_syntax, name, filter))
+                })
+                .filter_map(|(attribute_id, name)| {
+                    data_table
+                        .data_table()
+                        .column_of_attribute_id(attribute_id)
+                        .map(|column| (column, name.to_string()))
+                })
+                .collect()
+        };
+        let certificate_columns = CERTIFICATE_ATTRIBUTES
+            .into_iter()
+            .filter(|name| {
+                filter.matches_attribute(name, CERTIFICATE_VALID)
+                    || filter.matches_attribute(name, CERTIFICATE_EXPIRES)
+            })
+            .filter_map(|name| {
+                metadata
+                    .attribute_id_of(name)
+                    .and_then(|id| data_table.data_table().column_of_attribute_id(id))
+                    .map(|column| (column, name))
+            })
+            .collect();
+
+        Ok(Self {
+            domain_controllers: domain_controller_names(data_table)?,
+            member_links,
+            truncated_time_columns: columns_with_syntax(&[
+                OM_SYNTAX_UTC_TIME,
+                OM_SYNTAX_GENERALIZED_TIME,
+            ]),
+            large_integer_columns: columns_with_syntax(&[OM_SYNTAX_LARGE_INTEGER]),
+            certificate_columns,
+        })
+    }
+
+    /// `true` if the attribute contains timestamps which can match the
+    /// filter
+    fn is_selected(om_syntax: i32, name: &str, filter: &TimelineFilter) -> bool {
+        let is_timestamp = match om_syntax {
+            OM_SYNTAX_UTC_TIME | OM_SYNTAX_GENERALIZED_TIME => true,
+            OM_SYNTAX_LARGE_INTEGER => {
+                KNOWN_ATTRIBUTES
+                    .iter()
+                    .any(|(known, _, _)| known.eq_ignore_ascii_case(name))
+                    || LARGE_INTEGER_TIMESTAMPS
+                        .iter()
+                        .any(|known| known.eq_ignore_ascii_case(name))
+            }
+            _ => false,
+        };
+        let (description, _) = TimelineEvent::description_of(name);
+        is_timestamp && filter.matches_attribute(name, description)
+    }
+
+    /// returns the name of the domain controller with the given invocation
+    /// ID, or the invocation ID if the domain controller is unknown
+    fn dc_name(&self, invocation_id: &Guid) -> String {
+        self.domain_controllers
+            .get(invocation_id)
+            .cloned()
+            .unwrap_or_else(|| invocation_id.to_string())
+    }
+}
+
+/// a single timestamp of an object
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub struct TimelineEvent {
+    timestamp: DateTime<Utc>,
+
+    /// `lDAPDisplayName` of the attribute which contains the timestamp
+    attribute: String,
+    description: String,
+    timestamp_type: TimestampType,
+}
+
+impl UnixTimestamp for TimelineEvent {
+    fn timestamp(&self) -> i64 {
+        self.timestamp.timestamp()
+    }
+}
+
+impl TimelineEntry for TimelineEvent {}
+
+impl TimelineEvent {
+    pub(crate) fn new(
+        timestamp: DateTime<Utc>,
+        attribute: &str,
+        description: String,
+        timestamp_type: TimestampType,
+    ) -> Self {
+        Self {
+            timestamp,
+            attribute: attribute.to_string(),
+            description,
+            timestamp_type,
+        }
+    }
+
+    /// returns all timestamps of a record. Timestamp attributes are found
+    /// using their syntax, as defined in the schema (see [`TimelineContext`])
+    pub fn events_of(
+        record: &DataTableRecord,
+        metadata: &MetaDataCache,
+        context: &TimelineContext,
+    ) -> crate::ntds::Result<Vec<Self>> {
+        let mut events = Vec::new();
+        if let Some(ts) = record.ds_record_time_opt()? {
+            events.push(Self::new(
+                ts.into(),
+                "DSRecordTime",
+                "record creation time".to_string(),
+                TimestampType::Created,
+            ));
+        }
+
+        for (column, name) in &context.truncated_time_columns {
+            for value in Self::values_of::<i64>(record, *column)? {
+                if value < MIN_TRUNCATED_FILETIME {
+                    continue;
+                }
+                match TruncatedWindowsFileTime::try_from(value as u64) {
+                    Ok(ts) => events.push(Self::from_attribute(ts.into(), name)),
+                    Err(why) => log::warn!("invalid value of '{name}': {why}"),
+                }
+            }
+        }
+
+        for (column, name) in &context.large_integer_columns {
+            for value in Self::values_of::<i64>(record, *column)? {
+                if FILETIME_RANGE.contains(&value) {
+                    events.push(Self::from_attribute(
+                        WindowsFileTime::from(value as u64).into(),
+                        name,
+                    ));
+                }
+            }
+        }
+
+        for (column, name) in &context.certificate_columns {
+            let certificates = match Self::values_of::<CertificateValidity>(record, *column) {
+                Ok(certificates) => certificates,
+                Err(why) => {
+                    log::warn!("unable to parse the certificates in '{name}': {why}");
+                    Vec::new()
+                }
+            };
+            for certificate in certificates {
+                events.push(Self::new(
+                    *certificate.not_before(),
+                    name,
+                    CERTIFICATE_VALID.to_string(),
+                    TimestampType::Created,
+                ));
+                events.push(Self::new(
+                    *certificate.not_after(),
+                    name,
+                    CERTIFICATE_EXPIRES.to_string(),
+                    TimestampType::Changed,
+                ));
+            }
+        }
+
+        match record.att_repl_property_meta_data_opt() {
+            Ok(Some(repl_metadata)) => {
+                events.extend(Self::replication_events_of(
+                    &repl_metadata,
+                    |attribute_id| metadata.attribute_name_of(attribute_id).map(str::to_string),
+                    context,
+                ));
+            }
+            Ok(None) => (),
+            Err(why) => log::warn!("unable to parse the replication metadata: {why}"),
+        }
+
+        if let Some(links) = context.member_links.get(record.ptr().ds_record_id()) {
+            for link in links {
+                let member = metadata
+                    .record(link.backlink_dnt())
+                    .map(|entry| {
+                        metadata
+                            .dn(entry)
+                            .unwrap_or_else(|| entry.rdn().name().to_string())
+                    })
+                    .unwrap_or_else(|| link.backlink_dnt().to_string());
+                events.extend(Self::membership_events_of(link, &member, context));
+            }
+        }
+
+        events.sort_by_key(|event| event.timestamp);
+        Ok(events)
+    }
+
+    /// returns one event per attribute in the replication metadata, which
+    /// tells when and where the attribute has been changed the last time
+    fn replication_events_of(
+        repl_metadata: &ReplicationMetaData,
+        attribute_name_of: impl Fn(i32) -> Option<String>,
+        context: &TimelineContext,
+    ) -> Vec<Self> {
+        repl_metadata
+            .entries()
+            .iter()
+            .map(|entry| {
+                let attribute_id = i32::from_ne_bytes(entry.attribute_id().to_ne_bytes());
+                let attribute = attribute_name_of(attribute_id)
+                    .unwrap_or_else(|| format!("{:#x}", entry.attribute_id()));
+                let dc = context.dc_name(entry.originating_dsa());
+                Self::new(
+                    entry.time_changed().clone().into(),
+                    "replPropertyMetaData",
+                    format!("{attribute} changed on {dc} (version {})", entry.version()),
+                    TimestampType::Changed,
+                )
+            })
+            .collect()
+    }
+
+    /// returns when a member has been added to or removed from a group,
+    /// using the metadata of the link. Links which have been created
+    /// before linked value replication was enabled don't have metadata,
+    /// so only their removal can be reported
+    fn membership_events_of(link: &Link, member: &str, context: &TimelineContext) -> Vec<Self> {
+        let mut events = Vec::new();
+        let removal_time = link.deletion_time().clone();
+        match link.metadata() {
+            Some(link_metadata) => {
+                let dc = context.dc_name(link_metadata.originating_dsa());
+                let time_created: DateTime<Utc> = link_metadata.time_created().clone().into();
+                let time_changed: DateTime<Utc> = link_metadata.time_changed().clone().into();
+                // the originating DC is only known for the last change
+                let added = if time_changed == time_created {
+                    format!("member added on {dc}: {member}")
+                } else {
+                    format!("member added: {member}")
+                };
+                events.push(Self::new(
+                    time_created,
+                    "member",
+                    added,
+                    TimestampType::Changed,
+                ));
+                if *link.is_deleted() {
+                    events.push(Self::new(
+                        removal_time
+                            .map(DateTime::<Utc>::from)
+                            .unwrap_or(time_changed),
+                        "member",
+                        format!("member removed on {dc}: {member}"),
+                        TimestampType::Changed,
+                    ));
+                } else if time_changed != time_created {
+                    events.push(Self::new(
+                        time_changed,
+                        "member",
+                        format!("member added again on {dc}: {member}"),
+                        TimestampType::Changed,
+                    ));
+                }
+            }
+            None => {
+                if let Some(removal_time) = removal_time {
+                    events.push(Self::new(
+                        removal_time.into(),
+                        "member",
+                        format!("member removed: {member}"),
+                        TimestampType::Changed,
+                    ));
+                }
+            }
+        }
+        events
+    }
+
+    fn from_attribute(timestamp: DateTime<Utc>, attribute: &str) -> Self {
+        let (description, timestamp_type) = Self::description_of(attribute);
+        Self::new(
+            timestamp,
+            attribute,
+            description.to_string(),
+            timestamp_type,
+        )
+    }
+
+    /// returns the description of the events of an attribute, and the
+    /// bodyfile timestamp which is used for them
+    fn description_of(attribute: &str) -> (&str, TimestampType) {
+        match KNOWN_ATTRIBUTES
+            .iter()
+            .find(|(name, _, _)| name.eq_ignore_ascii_case(attribute))
+        {
+            Some((_, description, timestamp_type)) => (description, *timestamp_type),
+            None => (attribute, TimestampType::Changed),
+        }
+    }
+
+    /// reads all values of an attribute
+    fn values_of<T>(record: &DataTableRecord, column: ColumnIndex) -> crate::ntds::Result<Vec<T>>
+    where
+        T: crate::value::FromValue,
+    {
+        Ok(record
+            .get_value_of_column_opt::<Vec<T>>(column)?
+            .unwrap_or_default())
+    }
+
+    pub fn to_bodyfile(&self, name: &str, object_type: impl Display) -> Bodyfile3Line {
+        match self.timestamp_type {
+            TimestampType::Created => self.cr_entry(name, &self.description, object_type),
+            TimestampType::Changed => self.c_entry(name, &self.description, object_type),
+            TimestampType::Accessed => self.a_entry(name, &self.description, object_type),
+            TimestampType::Modified => self.m_entry(name, &self.description, object_type),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{DateTime, Utc};
+
+    use crate::ntds::{Link, TimelineFilter};
+    use crate::win32_types::{
+        Guid, LinkValueMetaData, ReplicationMetaData, TruncatedWindowsFileTime,
+    };
+
+    use super::{
+        TimelineContext, TimelineEvent, OM_SYNTAX_GENERALIZED_TIME, OM_SYNTAX_LARGE_INTEGER,
+    };
+
+    const CREATED: u64 = 13390472401;
+    const CHANGED: u64 = 13390476001;
+    const DC: [u8; 16] = [0x11; 16];
+
+    fn context() -> TimelineContext<'static> {
+        TimelineContext {
+            domain_controllers: HashMap::from([(
+                Guid::try_from(&DC[..]).unwrap(),
+                "DC01".to_string(),
+            )]),
+            member_links: HashMap::new(),
+            truncated_time_columns: Vec::new(),
+            large_integer_columns: Vec::new(),
+            certificate_columns: Vec::new(),
+        }
+    }
+
+    fn ts(seconds: u64) -> DateTime<Utc> {
+        TruncatedWindowsFileTime::try_from(seconds).unwrap().into()
+    }
+
+    fn link(time_changed: Option<u64>, deletion_time: Option<u64>) -> Link {
+        let metadata = time_changed.map(|time_changed| {
+            let mut value = Vec::new();
+            value.extend_from_slice(&CREATED.to_le_bytes());
+            value.extend_from_slice(&1u32.to_le_bytes());
+            value.extend_from_slice(&time_changed.to_le_bytes());
+            value.extend_from_slice(&DC);
+            value.extend_from_slice(&4711i64.to_le_bytes());
+            LinkValueMetaData::try_from(&value[..]).unwrap()
+        });
+        Link::new(
+            1.into(),
+            2.into(),
+            None,
+            deletion_time.is_some(),
+            deletion_time.map(|t| TruncatedWindowsFileTime::try_from(t).unwrap()),
+            metadata,
+        )
+    }
+
+    fn descriptions(events: &[TimelineEvent]) -> Vec<(DateTime<Utc>, &str)> {
+        events
+            .iter()
+            .map(|e| (*e.timestamp(), e.description().as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_column_selection() {
+        let filter = TimelineFilter::default();
+        let is_selected = |om_syntax, name| TimelineContext::is_selected(om_syntax, name, &filter);
+        assert!(is_selected(OM_SYNTAX_GENERALIZED_TIME, "whenCreated"));
+        assert!(is_selected(OM_SYNTAX_GENERALIZED_TIME, "msTSExpireDate"));
+        assert!(is_selected(OM_SYNTAX_LARGE_INTEGER, "pwdLastSet"));
+        assert!(is_selected(OM_SYNTAX_LARGE_INTEGER, "lastlogontimestamp"));
+        assert!(is_selected(OM_SYNTAX_LARGE_INTEGER, "creationTime"));
+        assert!(!is_selected(OM_SYNTAX_LARGE_INTEGER, "uSNChanged"));
+        assert!(!is_selected(OM_SYNTAX_LARGE_INTEGER, "maxPwdAge"));
+        assert!(!is_selected(2, "pwdLastSet"));
+
+        let filter = TimelineFilter::new(None, None, Vec::new(), Some("logon")).unwrap();
+        let is_selected = |om_syntax, name| TimelineContext::is_selected(om_syntax, name, &filter);
+        assert!(is_selected(OM_SYNTAX_LARGE_INTEGER, "lastLogon"));
+        assert!(!is_selected(OM_SYNTAX_LARGE_INTEGER, "pwdLastSet"));
+        assert!(!is_selected(OM_SYNTAX_GENERALIZED_TIME, "whenCreated"));
+    }
+
+    #[test]
+    fn test_member_added() {
+        let events =
+            TimelineEvent::membership_events_of(&link(Some(CREATED), None), "alice", &context());
+        assert_eq!(
+            descriptions(&events),
+            [(ts(CREATED), "member added on DC01: alice")]
+        );
+    }
+
+    #[test]
+    fn test_member_removed() {
+        let events = TimelineEvent::membership_events_of(
+            &link(Some(CHANGED), Some(CHANGED)),
+            "alice",
+            &context(),
+        );
+        assert_eq!(
+            descriptions(&events),
+            [
+                (ts(CREATED), "member added: alice"),
+                (ts(CHANGED), "member removed on DC01: alice")
+            ]
+        );
+
+        // without metadata, only the removal is known
+        let events =
+            TimelineEvent::membership_events_of(&link(None, Some(CHANGED)), "alice", &context());
+        assert_eq!(
+            descriptions(&events),
+            [(ts(CHANGED), "member removed: alice")]
+        );
+        let events = TimelineEvent::membership_events_of(&link(None, None), "alice", &context());
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_member_added_again() {
+        let events =
+            TimelineEvent::membership_events_of(&link(Some(CHANGED), None), "alice", &context());
+        assert_eq!(
+            descriptions(&events),
+            [
+                (ts(CREATED), "member added: alice"),
+                (ts(CHANGED), "member added again on DC01: alice")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replication_events() {
+        let mut value = Vec::new();
+        for v in [1u32, 0, 2, 0] {
+            value.extend_from_slice(&v.to_le_bytes());
+        }
+        for (attribute_id, dc) in [(0x20030u32, DC), (0x90001, [0x22; 16])] {
+            value.extend_from_slice(&attribute_id.to_le_bytes());
+            value.extend_from_slice(&3u32.to_le_bytes());
+            value.extend_from_slice(&CHANGED.to_le_bytes());
+            value.extend_from_slice(&dc);
+            value.extend_from_slice(&4711i64.to_le_bytes());
+            value.extend_from_slice(&4711i64.to_le_bytes());
+        }
+        let repl_metadata = ReplicationMetaData::try_from(&value[..]).unwrap();
+
+        let names = HashMap::from([(0x20030, "whenCreated".to_string())]);
+        let events = TimelineEvent::replication_events_of(
+            &repl_metadata,
+            |id| names.get(&id).cloned(),
+            &context(),
+        );
+        let unknown_dc = Guid::try_from(&[0x22; 16][..]).unwrap();
+        assert_eq!(
+            descriptions(&events),
+            [
+                (ts(CHANGED), "whenCreated changed on DC01 (version 3)"),
+                (
+                    ts(CHANGED),
+                    format!("0x90001 changed on {unknown_dc} (version 3)").as_str()
+                )
+            ]
+        );
+        assert!(events
+            .iter()
+            .all(|e| e.attribute() == "replPropertyMetaData"));
+    }
+}
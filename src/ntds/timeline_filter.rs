@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use getset::Getters;
+use regex::{Regex, RegexBuilder};
+
+use super::TimelineEvent;
+
+/// restricts a timeline to some object types, a time range and some kinds
+/// of events
+#[derive(Getters, Default)]
+pub struct TimelineFilter {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+
+    /// names of schema classes, e.g. `Person` or `Group-Policy-Container`
+    #[getset(get = "pub")]
+    object_types: Vec<String>,
+
+    event: Option<Regex>,
+}
+
+impl TimelineFilter {
+    /// `event` is a regular expression, which is matched (ignoring case)
+    /// against the description and the attribute name of every event
+    pub fn new(
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        object_types: Vec<String>,
+        event: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let event = match event {
+            Some(event) => Some(RegexBuilder::new(event).case_insensitive(true).build()?),
+            None => None,
+        };
+        Ok(Self {
+            from,
+            to,
+            object_types,
+            event,
+        })
+    }
+
+    pub fn matches(&self, event: &TimelineEvent) -> bool {
+        self.from.map_or(true, |from| *event.timestamp() >= from)
+            && self.to.map_or(true, |to| *event.timestamp() <= to)
+            && self.matches_attribute(event.attribute(), event.description())
+    }
+
+    /// `true` if events of this attribute with this description can match
+    /// the filter. This is used to skip attributes before they are read
+    pub fn matches_attribute(&self, attribute: &str, description: &str) -> bool {
+        self.event.as_ref().map_or(true, |regex| {
+            regex.is_match(description) || regex.is_match(attribute)
+        })
+    }
+
+    /// `true` if an object, which has been created at `created`, can have
+    /// events in the time range of the filter. This is used to skip objects
+    /// before their timestamps are read. Objects which have not been written
+    /// since `from` are not skipped, because some of their timestamps (such
+    /// as `accountExpires`) may lie in the future
+    pub fn matches_object(&self, created: Option<DateTime<Utc>>) -> bool {
+        match (self.to, created) {
+            (Some(to), Some(created)) => created <= to,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, TimeZone, Utc};
+
+    use crate::ntds::{TimelineEvent, TimestampType};
+
+    use super::TimelineFilter;
+
+    fn ts(year: i32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    fn event(year: i32, attribute: &str, description: &str) -> TimelineEvent {
+        TimelineEvent::new(
+            ts(year),
+            attribute,
+            description.to_string(),
+            TimestampType::Changed,
+        )
+    }
+
+    #[test]
+    fn test_empty_filter() {
+        let filter = TimelineFilter::default();
+        assert!(filter.matches(&event(2020, "whenChanged", "object changed")));
+        assert!(filter.matches_object(None));
+        assert!(filter.matches_object(Some(ts(2010))));
+    }
+
+    #[test]
+    fn test_time_range() {
+        let filter = TimelineFilter::new(Some(ts(2020)), Some(ts(2022)), Vec::new(), None).unwrap();
+        assert!(!filter.matches(&event(2019, "whenChanged", "object changed")));
+        assert!(filter.matches(&event(2020, "whenChanged", "object changed")));
+        assert!(filter.matches(&event(2022, "whenChanged", "object changed")));
+        assert!(!filter.matches(&event(2023, "whenChanged", "object changed")));
+    }
+
+    #[test]
+    fn test_matches_object() {
+        let filter = TimelineFilter::new(Some(ts(2020)), Some(ts(2022)), Vec::new(), None).unwrap();
+
+        // the object has been created after the end of the range
+        assert!(!filter.matches_object(Some(ts(2023))));
+
+        // objects which have been created before the beginning of the range
+        // may have timestamps in the future
+        assert!(filter.matches_object(Some(ts(2010))));
+        assert!(filter.matches_object(Some(ts(2021))));
+
+        // objects without timestamps cannot be skipped
+        assert!(filter.matches_object(None));
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let filter =
+            TimelineFilter::new(None, None, Vec::new(), Some("^pwdLastSet$|logon")).unwrap();
+        assert!(filter.matches(&event(2020, "pwdLastSet", "password last set")));
+        assert!(filter.matches(&event(2020, "lastLogon", "last LOGON on this DC")));
+        assert!(!filter.matches(&event(2020, "whenChanged", "object changed")));
+
+        assert!(filter.matches_attribute("PWDLASTSET", "password last set"));
+        assert!(filter.matches_attribute("lastLogonTimestamp", "last logon on any DC"));
+        assert!(!filter.matches_attribute("accountExpires", "account expires"));
+    }
+
+    #[test]
+    fn test_invalid_event_filter() {
+        assert!(TimelineFilter::new(None, None, Vec::new(), Some("(")).is_err());
+    }
+}
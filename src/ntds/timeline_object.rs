@@ -0,0 +1,357 @@
+use bodyfile::Bodyfile3Line;
+use chrono::{DateTime, Utc};
+use flow_record::derive::*;
+use flow_record::prelude::*;
+use getset::Getters;
+use serde::Serialize;
+
+use crate::cache::MetaDataCache;
+use crate::win32_types::Sid;
+
+use super::{DataTableRecord, TimelineContext, TimelineEvent, TimelineFilter, TimestampType};
+
+/// value of the `source` column in TLN and l2tcsv timelines
+const TIMELINE_SOURCE: &str = "NTDS";
+
+/// an object and all of its timestamps
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub struct TimelineObject {
+    name: String,
+    object_type: String,
+    distinguished_name: Option<String>,
+    sid: Option<Sid>,
+    record_id: i32,
+    is_deleted: bool,
+    deleted_from: Option<String>,
+    events: Vec<TimelineEvent>,
+}
+
+#[derive(FlowRecord)]
+#[flow_record(version = 2, source = "ntdsextract2", classification = "ntds")]
+pub struct NtdsEvent {
+    ts: DateTime<Utc>,
+    ts_description: String,
+    attribute: String,
+    name: String,
+    object_type: String,
+    record_id: i32,
+    is_deleted: bool,
+    deleted_from: Option<String>,
+}
+
+/// a line in Plaso's l2tcsv format
+///
+/// <https://plaso.readthedocs.io/en/latest/sources/user/Output-and-formatting.html>
+#[derive(Serialize)]
+pub struct L2tCsvLine {
+    date: String,
+    time: String,
+    timezone: &'static str,
+    #[serde(rename = "MACB")]
+    macb: &'static str,
+    source: &'static str,
+    sourcetype: &'static str,
+    #[serde(rename = "type")]
+    timestamp_type: String,
+    user: String,
+    host: &'static str,
+    short: String,
+    desc: String,
+    version: u8,
+    filename: String,
+    inode: i32,
+    notes: &'static str,
+    format: &'static str,
+    extra: String,
+}
+
+/// an event which can be imported into Timesketch
+///
+/// <https://timesketch.org/guides/user/import-from-json-csv/>
+#[derive(Serialize)]
+pub struct TimesketchEvent<'o> {
+    message: String,
+    datetime: String,
+    timestamp: i64,
+    timestamp_desc: &'o str,
+    attribute: &'o str,
+    name: &'o str,
+    object_type: &'o str,
+    distinguished_name: &'o Option<String>,
+    sid: Option<String>,
+    record_id: i32,
+    is_deleted: bool,
+    deleted_from: &'o Option<String>,
+}
+
+impl TimelineObject {
+    pub fn from_record(
+        record: &DataTableRecord,
+        metadata: &MetaDataCache,
+        context: &TimelineContext,
+    ) -> anyhow::Result<Option<Self>> {
+        let name = match record.att_sam_account_name_opt()? {
+            Some(name) => name,
+            None => match record
+                .att_object_name_opt()?
+                .or(record.att_object_name2_opt()?)
+            {
+                Some(rdn) => rdn.name().to_string(),
+                None => return Ok(None),
+            },
+        };
+
+        let deleted_from = record
+            .att_last_known_parent_opt()?
+            .and_then(|last_known_parent| {
+                metadata
+                    .record(&last_known_parent)
+                    .and_then(|entry| metadata.dn(entry))
+            });
+
+        Ok(Some(Self {
+            name,
+            object_type: record.object_type_name(metadata)?,
+            distinguished_name: metadata.dn(&metadata[record.ptr()]),
+            sid: record.att_object_sid_opt().unwrap_or(None),
+            record_id: record.ptr().ds_record_id().inner(),
+            is_deleted: record.att_is_deleted_opt()?.unwrap_or(false),
+            deleted_from,
+            events: TimelineEvent::events_of(record, metadata, context)?,
+        }))
+    }
+
+    /// removes all events which don't match the filter
+    pub fn retain_events(&mut self, filter: &TimelineFilter) {
+        self.events.retain(|event| filter.matches(event))
+    }
+
+    /// object type as shown in bodyfile entries, which also tells whether
+    /// the object has been deleted
+    fn object_type_caption(&self) -> String {
+        let object_type = &self.object_type;
+        match &self.deleted_from {
+            Some(deleted_from) => format!("{object_type}, deleted from {deleted_from}"),
+            None if self.is_deleted => format!("deleted {object_type}"),
+            None => object_type.clone(),
+        }
+    }
+
+    /// a human readable description of an event, which contains as much
+    /// context as possible
+    fn message_of(&self, event: &TimelineEvent) -> String {
+        match &self.distinguished_name {
+            Some(dn) => format!(
+                "{} ({}): {} [{dn}]",
+                self.name,
+                self.object_type_caption(),
+                event.description()
+            ),
+            None => format!(
+                "{} ({}): {}",
+                self.name,
+                self.object_type_caption(),
+                event.description()
+            ),
+        }
+    }
+
+    pub fn to_bodyfile(&self) -> Vec<Bodyfile3Line> {
+        let object_type_caption = self.object_type_caption();
+        let inode = self.record_id.to_string();
+        self.events
+            .iter()
+            .map(|event| {
+                event
+                    .to_bodyfile(&self.name, &object_type_caption)
+                    .with_inode(&inode)
+            })
+            .collect()
+    }
+
+    pub fn to_flow_records(&self) -> Vec<NtdsEvent> {
+        self.events
+            .iter()
+            .map(|event| NtdsEvent {
+                ts: *event.timestamp(),
+                ts_description: event.description().clone(),
+                attribute: event.attribute().clone(),
+                name: self.name.clone(),
+                object_type: self.object_type.clone(),
+                record_id: self.record_id,
+                is_deleted: self.is_deleted,
+                deleted_from: self.deleted_from.clone(),
+            })
+            .collect()
+    }
+
+    /// returns the events in TLN format (`Time|Source|Host|User|Description`)
+    pub fn to_tln(&self) -> Vec<String> {
+        self.events
+            .iter()
+            .map(|event| {
+                format!(
+                    "{}|{TIMELINE_SOURCE}||{}|{}",
+                    event.timestamp().timestamp(),
+                    tln_value(&self.name),
+                    tln_value(&self.message_of(event))
+                )
+            })
+            .collect()
+    }
+
+    pub fn to_l2tcsv(&self) -> Vec<L2tCsvLine> {
+        let sid = self
+            .sid
+            .as_ref()
+            .map(|sid| format!("sid: {sid}; "))
+            .unwrap_or_default();
+        self.events
+            .iter()
+            .map(|event| L2tCsvLine {
+                date: event.timestamp().format("%m/%d/%Y").to_string(),
+                time: event.timestamp().format("%H:%M:%S").to_string(),
+                timezone: "UTC",
+                macb: match event.timestamp_type() {
+                    TimestampType::Modified => "M...",
+                    TimestampType::Accessed => ".A..",
+                    TimestampType::Changed => "..C.",
+                    TimestampType::Created => "...B",
+                },
+                source: TIMELINE_SOURCE,
+                sourcetype: "ntds.dit",
+                timestamp_type: event.description().clone(),
+                user: self.name.clone(),
+                host: "-",
+                short: format!("{}: {}", self.name, event.description()),
+                desc: self.message_of(event),
+                version: 2,
+                filename: self
+                    .distinguished_name
+                    .clone()
+                    .unwrap_or_else(|| "-".to_string()),
+                inode: self.record_id,
+                notes: "-",
+                format: "ntdsextract2",
+                extra: format!(
+                    "{sid}object_type: {}; attribute: {}",
+                    self.object_type,
+                    event.attribute()
+                ),
+            })
+            .collect()
+    }
+
+    pub fn to_timesketch(&self) -> Vec<TimesketchEvent<'_>> {
+        self.events
+            .iter()
+            .map(|event| TimesketchEvent {
+                message: self.message_of(event),
+                datetime: event.timestamp().to_rfc3339(),
+                timestamp: event.timestamp().timestamp_micros(),
+                timestamp_desc: event.description(),
+                attribute: event.attribute(),
+                name: &self.name,
+                object_type: &self.object_type,
+                distinguished_name: &self.distinguished_name,
+                sid: self.sid.as_ref().map(Sid::to_string),
+                record_id: self.record_id,
+                is_deleted: self.is_deleted,
+                deleted_from: &self.deleted_from,
+            })
+            .collect()
+    }
+}
+
+/// replaces the field separator and control characters (such as line breaks)
+/// of a TLN field, so that every event is written in a single line
+fn tln_value(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c == '|' || c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::ntds::{TimelineEvent, TimestampType};
+
+    use super::TimelineObject;
+
+    fn object(name: &str) -> TimelineObject {
+        TimelineObject {
+            name: name.to_string(),
+            object_type: "Person".to_string(),
+            distinguished_name: Some("CN=Alice,CN=Users,DC=example,DC=com".to_string()),
+            sid: Some(serde_json::from_str(r#""S-1-5-21-1-2-3-1104""#).unwrap()),
+            record_id: 4711,
+            is_deleted: false,
+            deleted_from: None,
+            events: vec![TimelineEvent::new(
+                Utc.with_ymd_and_hms(2024, 5, 1, 12, 34, 56).unwrap(),
+                "pwdLastSet",
+                "password last set".to_string(),
+                TimestampType::Modified,
+            )],
+        }
+    }
+
+    #[test]
+    fn test_tln() {
+        assert_eq!(
+            object("alice").to_tln(),
+            ["1714566896|NTDS||alice|alice (Person): password last set [CN=Alice,CN=Users,DC=example,DC=com]"]
+        );
+        assert_eq!(
+            object("a|b\nc\r").to_tln(),
+            ["1714566896|NTDS||a b c |a b c  (Person): password last set [CN=Alice,CN=Users,DC=example,DC=com]"]
+        );
+    }
+
+    #[test]
+    fn test_l2tcsv() {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for line in object("alice").to_l2tcsv() {
+            writer.serialize(line).unwrap();
+        }
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "date,time,timezone,MACB,source,sourcetype,type,user,host,short,desc,version,filename,inode,notes,format,extra",
+                "05/01/2024,12:34:56,UTC,M...,NTDS,ntds.dit,password last set,alice,-,alice: password last set,\
+                \"alice (Person): password last set [CN=Alice,CN=Users,DC=example,DC=com]\",2,\
+                \"CN=Alice,CN=Users,DC=example,DC=com\",4711,-,ntdsextract2,\
+                sid: S-1-5-21-1-2-3-1104; object_type: Person; attribute: pwdLastSet"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_timesketch() {
+        let object = object("alice");
+        let events = object.to_timesketch();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            serde_json::to_value(&events[0]).unwrap(),
+            serde_json::json!({
+                "message": "alice (Person): password last set [CN=Alice,CN=Users,DC=example,DC=com]",
+                "datetime": "2024-05-01T12:34:56+00:00",
+                "timestamp": 1714566896000000i64,
+                "timestamp_desc": "password last set",
+                "attribute": "pwdLastSet",
+                "name": "alice",
+                "object_type": "Person",
+                "distinguished_name": "CN=Alice,CN=Users,DC=example,DC=com",
+                "sid": "S-1-5-21-1-2-3-1104",
+                "record_id": 4711,
+                "is_deleted": false,
+                "deleted_from": null
+            })
+        );
+    }
+}
@@ -46,7 +46,12 @@ impl ObjectTree {
             .find_child_by_name("Deleted Objects")
             .ok_or(anyhow!("db has no `Deleted Objects` entry"))?;
 
-        Ok(SpecialRecords::new(schema_subpath, deleted_objects))
+        Ok(SpecialRecords::new(
+            Rc::clone(&domain_root[0]),
+            configuration,
+            schema_subpath,
+            deleted_objects,
+        ))
     }
 
     pub(crate) fn to_termtree(&self, max_depth: u8) -> Tree<Rc<ObjectTreeEntry>> {
@@ -68,6 +73,37 @@ impl ObjectTree {
         }
     }
 
+    /// creates a tree of entry names, where every entry which has some
+    /// annotation gets additional leaves (which are displayed first)
+    pub(crate) fn to_annotated_termtree(
+        &self,
+        max_depth: u8,
+        annotations: &HashMap<RecordPointer, Vec<String>>,
+    ) -> Tree<String> {
+        Self::__to_annotated_termtree(&self.root, max_depth, annotations)
+    }
+
+    fn __to_annotated_termtree(
+        me: &Rc<ObjectTreeEntry>,
+        max_depth: u8,
+        annotations: &HashMap<RecordPointer, Vec<String>>,
+    ) -> Tree<String> {
+        let tree = Tree::new(me.to_string());
+        let mut leaves: Vec<Tree<String>> = annotations
+            .get(me.record_ptr())
+            .map(|a| a.iter().map(|a| Tree::new(format!("[{a}]"))).collect())
+            .unwrap_or_default();
+        if max_depth > 0 {
+            leaves.extend(
+                me.children()
+                    .borrow()
+                    .iter()
+                    .map(|c| Self::__to_annotated_termtree(c, max_depth - 1, annotations)),
+            );
+        }
+        tree.with_leaves(leaves)
+    }
+
     pub fn dn_of(&self, ptr: &RecordPointer) -> Option<String> {
         match self.record_index.get(ptr) {
             Some(record) => Some(
@@ -198,7 +198,12 @@ impl ObjectTreeEntry {
             .find_child_by_name("Deleted Objects")
             .ok_or(anyhow!("db has no `Deleted Objects` entry"))?;
 
-        Ok(SpecialRecords::new(schema_subpath, deleted_objects))
+        Ok(SpecialRecords::new(
+            Rc::clone(&domain_root[0]),
+            configuration,
+            schema_subpath,
+            deleted_objects,
+        ))
     }
 
     /// returns the path to the domain root object, where the first entry in the list is the domain root object,
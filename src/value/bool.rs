@@ -2,7 +2,7 @@ use crate::cache::Value;
 
 use crate::ntds::Error;
 
-use super::FromValue;
+use super::{first_value_of, FromValue};
 
 impl FromValue for bool {
     fn from_value_opt(value: &Value) -> Result<Option<Self>, Error>
@@ -11,6 +11,7 @@ impl FromValue for bool {
     {
         match value {
             Value::Null(_) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
             Value::U8(val) => Ok(Some(*val == 1)),
             Value::U16(val) => Ok(Some(*val == 1)),
             Value::U32(val) => Ok(Some(*val == 1)),
@@ -0,0 +1,40 @@
+use crate::cache::Value;
+
+use crate::{
+    ntds::Error,
+    win32_types::{CertificateNameFlags, EnrollmentFlags},
+};
+
+use super::{first_value_of, FromValue};
+
+impl FromValue for CertificateNameFlags {
+    fn from_value_opt(value: &Value) -> Result<Option<Self>, Error> {
+        match value {
+            Value::I32(val) => Ok(Some(CertificateNameFlags::from_bits_retain(
+                u32::from_ne_bytes(val.to_ne_bytes()),
+            ))),
+            Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
+            _ => Err(Error::InvalidValueDetected(
+                value.to_string(),
+                "CertificateNameFlags (i32)",
+            )),
+        }
+    }
+}
+
+impl FromValue for EnrollmentFlags {
+    fn from_value_opt(value: &Value) -> Result<Option<Self>, Error> {
+        match value {
+            Value::I32(val) => Ok(Some(EnrollmentFlags::from_bits_retain(u32::from_ne_bytes(
+                val.to_ne_bytes(),
+            )))),
+            Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
+            _ => Err(Error::InvalidValueDetected(
+                value.to_string(),
+                "EnrollmentFlags (i32)",
+            )),
+        }
+    }
+}
@@ -63,3 +63,79 @@ pub trait FromValue {
         }
     }
 }
+
+/// converts the first value of a multi-valued attribute. This is used by
+/// all types which represent a single value; use `Vec<T>` to read all values
+/// of a multi-valued attribute. All other values are dropped
+pub fn first_value_of<T: FromValue>(values: &[Value]) -> crate::ntds::Result<Option<T>> {
+    if values.len() > 1 {
+        log::warn!(
+            "expected a single value of type {}, but found {} values; ignoring all but the first one",
+            std::any::type_name::<T>(),
+            values.len()
+        );
+    }
+    match values.first() {
+        Some(value) => T::from_value_opt(value),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::Value;
+    use crate::value::ToString;
+    use crate::win32_types::Sid;
+
+    use super::FromValue;
+
+    fn multi_value() -> Value {
+        Value::Multi(vec![Value::I32(4711), Value::I32(42)])
+    }
+
+    #[test]
+    fn test_multi_value_to_string() {
+        assert_eq!(ToString::to_string(&multi_value()), "4711, 42");
+        let texts = Value::Multi(vec![
+            Value::Text(Box::new("HOST/dc01".to_string())),
+            Value::Text(Box::new("ldap/dc01".to_string())),
+        ]);
+        assert_eq!(ToString::to_string(&texts), "HOST/dc01, ldap/dc01");
+    }
+
+    #[test]
+    fn test_scalar_from_multi_value() {
+        assert_eq!(i32::from_value_opt(&multi_value()).unwrap(), Some(4711));
+        assert_eq!(u32::from_value_opt(&multi_value()).unwrap(), Some(4711));
+        assert_eq!(i64::from_value_opt(&multi_value()).unwrap(), Some(4711));
+        assert_eq!(
+            i32::from_value_opt(&Value::Multi(Vec::new())).unwrap(),
+            None
+        );
+
+        let sid = Value::Multi(vec![Value::Binary(Box::new(vec![
+            1, 2, 0, 0, 0, 0, 0, 5, 32, 0, 0, 0, 0, 0, 2, 32,
+        ]))]);
+        assert_eq!(
+            Sid::from_value_opt(&sid).unwrap().unwrap().to_string(),
+            "S-1-5-32-544"
+        );
+    }
+
+    #[test]
+    fn test_vec_from_multi_value() {
+        assert_eq!(
+            Vec::<i32>::from_value_opt(&multi_value()).unwrap(),
+            Some(vec![4711, 42])
+        );
+        assert_eq!(
+            Vec::<i32>::from_value_opt(&Value::I32(1)).unwrap(),
+            Some(vec![1])
+        );
+    }
+
+    #[test]
+    fn test_invalid_i64() {
+        assert!(i64::from_value_opt(&Value::Text(Box::new("x".to_string()))).is_err());
+    }
+}
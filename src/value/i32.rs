@@ -2,7 +2,7 @@ use crate::cache::Value;
 
 use crate::ntds::Error;
 
-use super::FromValue;
+use super::{first_value_of, FromValue};
 
 impl FromValue for i32 {
     fn from_value_opt(value: &Value) -> Result<Option<Self>, Error>
@@ -12,6 +12,7 @@ impl FromValue for i32 {
         match value {
             Value::I32(val) => Ok(Some(*val)),
             Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
             _ => Err(Error::InvalidValueDetected(value.to_string(), "i32")),
         }
     }
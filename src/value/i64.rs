@@ -2,7 +2,7 @@ use crate::cache::Value;
 
 use crate::ntds::Error;
 
-use super::FromValue;
+use super::{first_value_of, FromValue};
 
 impl FromValue for i64 {
     fn from_value_opt(value: &Value) -> Result<Option<Self>, Error>
@@ -20,7 +20,8 @@ impl FromValue for i64 {
                 ])))
             }
             Value::Null(()) => Ok(None),
-            _ => panic!("test"), //Err(Error::InvalidValueDetected(value.to_string(), "i64")),
+            Value::Multi(values) => first_value_of(values),
+            _ => Err(Error::InvalidValueDetected(value.to_string(), "i64")),
         }
     }
 }
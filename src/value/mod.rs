@@ -1,12 +1,20 @@
 mod bool;
+mod certificate_template;
+mod certificate_validity;
+mod dns_record;
 mod from_value;
 mod i32;
 mod i64;
+mod replication_metadata;
 mod sam_account_type;
+mod schedule;
 mod sid;
 mod string;
+mod supported_encryption_types;
+mod trust;
 mod u32;
 mod user_acount_control;
+mod vec;
 mod to_string;
 
 pub use from_value::*;
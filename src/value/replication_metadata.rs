@@ -0,0 +1,30 @@
+use crate::cache::Value;
+
+use crate::{ntds::Error, win32_types::ReplicationMetaData};
+
+use super::{first_value_of, FromValue};
+
+impl FromValue for ReplicationMetaData {
+    fn from_value_opt(value: &Value) -> Result<Option<Self>, Error>
+    where
+        Self: Sized,
+    {
+        match value {
+            Value::Binary(val) | Value::LargeBinary(val) => {
+                Ok(Some(ReplicationMetaData::try_from(&val[..]).map_err(
+                    |why| Error::MiscConversionError {
+                        value: value.to_string(),
+                        intended_type: "ReplicationMetaData",
+                        why,
+                    },
+                )?))
+            }
+            Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
+            _ => Err(Error::InvalidValueDetected(
+                value.to_string(),
+                "ReplicationMetaData (binary)",
+            )),
+        }
+    }
+}
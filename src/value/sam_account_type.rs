@@ -3,7 +3,7 @@ use num_traits::FromPrimitive;
 
 use crate::{ntds::Error, win32_types::SamAccountType};
 
-use super::FromValue;
+use super::{first_value_of, FromValue};
 
 impl FromValue for SamAccountType {
     fn from_value_opt(value: &Value) -> Result<Option<Self>, Error> {
@@ -12,6 +12,7 @@ impl FromValue for SamAccountType {
                 val.to_ne_bytes(),
             ))),
             Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
             _ => Err(Error::InvalidValueDetected(value.to_string(), "SamAccountType (i32)")),
         }
     }
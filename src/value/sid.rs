@@ -2,7 +2,7 @@ use crate::cache::Value;
 
 use crate::{ntds::Error, win32_types::Sid};
 
-use super::FromValue;
+use super::{first_value_of, FromValue};
 
 impl FromValue for Sid {
     fn from_value_opt(value: &Value) -> Result<Option<Self>, Error>
@@ -18,6 +18,7 @@ impl FromValue for Sid {
                     })?))
             }
             Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
             _ => Err(Error::InvalidValueDetected(value.to_string(), "Sid (binary)")),
         }
     }
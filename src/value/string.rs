@@ -2,7 +2,7 @@ use crate::cache::Value;
 
 use crate::ntds::Error;
 
-use super::FromValue;
+use super::{first_value_of, FromValue};
 
 impl FromValue for String {
     fn from_value_opt(value: &Value) -> Result<Option<Self>, Error>
@@ -14,6 +14,9 @@ impl FromValue for String {
             Value::LargeText(val) => Ok(Some(val.as_ref().to_owned())),
             Value::Binary(val) | Value::LargeBinary(val) => Ok(Some(hex::encode(val.as_ref()))),
             Value::Null(()) => Ok(None),
+
+            // use `Vec<String>` to read all values of a multi-valued attribute
+            Value::Multi(values) => first_value_of(values),
             _ => Err(Error::InvalidValueDetected(value.to_string(), "String (one of (text, largetext or binary)")),
         }
     }
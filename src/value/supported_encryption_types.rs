@@ -0,0 +1,24 @@
+use crate::cache::Value;
+
+use crate::{ntds::Error, win32_types::SupportedEncryptionTypes};
+
+use super::{first_value_of, FromValue};
+
+impl FromValue for SupportedEncryptionTypes {
+    fn from_value_opt(value: &Value) -> Result<Option<Self>, Error>
+    where
+        Self: Sized,
+    {
+        match value {
+            Value::I32(val) => Ok(Some(<SupportedEncryptionTypes>::from_bits_truncate(
+                u32::from_ne_bytes(val.to_ne_bytes()),
+            ))),
+            Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
+            _ => Err(Error::InvalidValueDetected(
+                value.to_string(),
+                "SupportedEncryptionTypes (i32)",
+            )),
+        }
+    }
+}
@@ -26,7 +26,12 @@ impl ToString for Value {
             Value::SuperLarge(v) => hex::encode(v.as_ref()).to_string(),
             Value::Guid(v) => hex::encode(v.as_ref()).to_string(),
             Value::Long(_) => "Long".to_string(),
-            Value::Multi(_) => "Multi".to_string(),
+            Value::Multi(values) => values
+                .iter()
+                .filter(|value| !matches!(value, Value::Null(())))
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
         }
     }
 }
\ No newline at end of file
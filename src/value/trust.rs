@@ -0,0 +1,104 @@
+use crate::cache::Value;
+use num_traits::FromPrimitive;
+
+use crate::{
+    ntds::Error,
+    win32_types::{TrustAttributes, TrustDirection, TrustType},
+};
+
+use super::{first_value_of, FromValue};
+
+impl FromValue for TrustDirection {
+    fn from_value_opt(value: &Value) -> Result<Option<Self>, Error> {
+        match value {
+            Value::I32(val) => Ok(FromPrimitive::from_i32(*val)),
+            Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
+            _ => Err(Error::InvalidValueDetected(
+                value.to_string(),
+                "TrustDirection (i32)",
+            )),
+        }
+    }
+}
+
+impl FromValue for TrustType {
+    fn from_value_opt(value: &Value) -> Result<Option<Self>, Error> {
+        match value {
+            Value::I32(val) => Ok(FromPrimitive::from_i32(*val)),
+            Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
+            _ => Err(Error::InvalidValueDetected(
+                value.to_string(),
+                "TrustType (i32)",
+            )),
+        }
+    }
+}
+
+impl FromValue for TrustAttributes {
+    fn from_value_opt(value: &Value) -> Result<Option<Self>, Error> {
+        match value {
+            Value::I32(val) => Ok(Some(TrustAttributes::from_bits_truncate(
+                u32::from_ne_bytes(val.to_ne_bytes()),
+            ))),
+            Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
+            _ => Err(Error::InvalidValueDetected(
+                value.to_string(),
+                "TrustAttributes (i32)",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::Value;
+    use crate::value::FromValue;
+    use crate::win32_types::{TrustAttributes, TrustDirection, TrustType};
+
+    #[test]
+    fn test_trust_direction() {
+        assert!(
+            TrustDirection::from_value_opt(&Value::I32(3)).unwrap()
+                == Some(TrustDirection::TRUST_DIRECTION_BIDIRECTIONAL)
+        );
+        assert!(TrustDirection::from_value_opt(&Value::I32(42))
+            .unwrap()
+            .is_none());
+        assert!(TrustDirection::from_value_opt(&Value::Null(()))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_trust_type() {
+        assert!(
+            TrustType::from_value_opt(&Value::I32(2)).unwrap()
+                == Some(TrustType::TRUST_TYPE_UPLEVEL)
+        );
+        assert!(
+            TrustType::from_value_opt(&Value::Multi(vec![Value::I32(3)])).unwrap()
+                == Some(TrustType::TRUST_TYPE_MIT)
+        );
+    }
+
+    #[test]
+    fn test_trust_attributes() {
+        assert!(
+            TrustAttributes::from_value_opt(&Value::I32(0x48)).unwrap()
+                == Some(
+                    TrustAttributes::TRUST_ATTRIBUTE_FOREST_TRANSITIVE
+                        | TrustAttributes::TRUST_ATTRIBUTE_TREAT_AS_EXTERNAL
+                )
+        );
+
+        // unknown bits are ignored, including the sign bit
+        assert!(
+            TrustAttributes::from_value_opt(&Value::I32(i32::MIN | 0x04)).unwrap()
+                == Some(TrustAttributes::TRUST_ATTRIBUTE_QUARANTINED_DOMAIN)
+        );
+        assert!(TrustAttributes::from_value_opt(&Value::U8(1)).is_err());
+    }
+}
@@ -2,7 +2,7 @@ use crate::cache::Value;
 
 use crate::ntds::Error;
 
-use super::FromValue;
+use super::{first_value_of, FromValue};
 
 impl FromValue for u32 {
     fn from_value_opt(value: &Value) -> Result<Option<Self>, Error>
@@ -24,6 +24,7 @@ impl FromValue for u32 {
                     why,
                 })?)),
             Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
             _ => Err(Error::InvalidValueDetected(value.to_string(), "u32 (or one of u8, u16, i16 or i32)")),
         }
     }
@@ -2,7 +2,7 @@ use crate::cache::Value;
 
 use crate::{ntds::Error, win32_types::UserAccountControl};
 
-use super::FromValue;
+use super::{first_value_of, FromValue};
 
 impl FromValue for UserAccountControl {
     fn from_value_opt(value: &Value) -> Result<Option<Self>, Error>
@@ -14,6 +14,7 @@ impl FromValue for UserAccountControl {
                 u32::from_ne_bytes(val.to_ne_bytes()),
             ))),
             Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
             _ => Err(Error::InvalidValueDetected(value.to_string(), "UserAccountControl (i32)")),
         }
     }
@@ -0,0 +1,31 @@
+use crate::cache::Value;
+
+use crate::ntds::Error;
+
+use super::FromValue;
+
+/// converts multi-valued attributes. Single values are converted into a
+/// vector with only one element.
+impl<T> FromValue for Vec<T>
+where
+    T: FromValue,
+{
+    fn from_value_opt(value: &Value) -> Result<Option<Self>, Error>
+    where
+        Self: Sized,
+    {
+        match value {
+            Value::Null(()) => Ok(None),
+            Value::Multi(values) => {
+                let mut result = Vec::with_capacity(values.len());
+                for value in values {
+                    if let Some(v) = T::from_value_opt(value)? {
+                        result.push(v);
+                    }
+                }
+                Ok(Some(result))
+            }
+            value => Ok(T::from_value_opt(value)?.map(|v| vec![v])),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use getset::Getters;
+use sddl::{AccessMask, Ace, AceHeaderFlags};
+use strum::Display;
+
+use super::{Guid, Sid};
+
+#[derive(Display, Eq, PartialEq, Clone, Copy)]
+pub enum AccessControlType {
+    #[strum(serialize = "allow")]
+    Allow,
+
+    #[strum(serialize = "deny")]
+    Deny,
+}
+
+/// simplified view of an access allowed or access denied ACE
+///
+/// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-dtyp/628ebb1d-c509-4ea0-a10f-77ef97ca4586>
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub struct AccessControlEntry {
+    access_type: AccessControlType,
+    flags: AceHeaderFlags,
+    mask: AccessMask,
+    object_type: Option<Guid>,
+    inherited_object_type: Option<Guid>,
+    trustee: Sid,
+    well_known_name: Option<&'static str>,
+}
+
+impl AccessControlEntry {
+    /// converts an [`Ace`]. Returns `None` for all ACE types which neither
+    /// allow nor deny access (e.g. audit ACEs)
+    pub fn from_ace(ace: &Ace) -> Option<Self> {
+        let (access_type, header, object_type, inherited_object_type, sid) = match ace {
+            Ace::ACCESS_ALLOWED_ACE { header, sid, .. }
+            | Ace::ACCESS_ALLOWED_CALLBACK_ACE { header, sid, .. } => {
+                (AccessControlType::Allow, header, &None, &None, sid)
+            }
+            Ace::ACCESS_DENIED_ACE { header, sid, .. }
+            | Ace::ACCESS_DENIED_CALLBACK_ACE { header, sid, .. } => {
+                (AccessControlType::Deny, header, &None, &None, sid)
+            }
+            Ace::ACCESS_ALLOWED_OBJECT_ACE {
+                header,
+                object_type,
+                inherited_object_type,
+                sid,
+                ..
+            }
+            | Ace::ACCESS_ALLOWED_CALLBACK_OBJECT_ACE {
+                header,
+                object_type,
+                inherited_object_type,
+                sid,
+                ..
+            } => (
+                AccessControlType::Allow,
+                header,
+                object_type,
+                inherited_object_type,
+                sid,
+            ),
+            Ace::ACCESS_DENIED_OBJECT_ACE {
+                header,
+                object_type,
+                inherited_object_type,
+                sid,
+                ..
+            }
+            | Ace::ACCESS_DENIED_CALLBACK_OBJECT_ACE {
+                header,
+                object_type,
+                inherited_object_type,
+                sid,
+                ..
+            } => (
+                AccessControlType::Deny,
+                header,
+                object_type,
+                inherited_object_type,
+                sid,
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            access_type,
+            flags: *header.ace_flags(),
+            mask: *header.mask(),
+            object_type: object_type.as_ref().map(Guid::from),
+            inherited_object_type: inherited_object_type.as_ref().map(Guid::from),
+            trustee: Sid::from(sid),
+            well_known_name: *sid.well_known_name(),
+        })
+    }
+
+    /// `true` if this ACE has been inherited from some parent object
+    pub fn is_inherited(&self) -> bool {
+        self.flags.contains(AceHeaderFlags::INHERITED_ACE)
+    }
+
+    /// `true` if this ACE will be inherited by child objects
+    pub fn is_inheritable(&self) -> bool {
+        self.flags
+            .intersects(AceHeaderFlags::CONTAINER_INHERIT_ACE | AceHeaderFlags::OBJECT_INHERIT_ACE)
+    }
+
+    /// `true` if this ACE does not apply to the object it is attached to
+    pub fn is_inherit_only(&self) -> bool {
+        self.flags.contains(AceHeaderFlags::INHERIT_ONLY_ACE)
+    }
+
+    /// returns the names of the access rights granted or denied by this ACE
+    pub fn rights(&self) -> Vec<&'static str> {
+        const RIGHTS: [(AccessMask, &str); 17] = [
+            (AccessMask::GENERIC_ALL, "GenericAll"),
+            (AccessMask::GENERIC_WRITE, "GenericWrite"),
+            (AccessMask::GENERIC_READ, "GenericRead"),
+            (AccessMask::GENERIC_EXECUTE, "GenericExecute"),
+            (AccessMask::WRITE_OWNER, "WriteOwner"),
+            (AccessMask::WRITE_DACL, "WriteDacl"),
+            (AccessMask::READ_CONTROL, "ReadControl"),
+            (AccessMask::DELETE, "Delete"),
+            (AccessMask::CONTROL_ACCESS, "ExtendedRight"),
+            (AccessMask::LIST_OBJECT, "ListObject"),
+            (AccessMask::DELETE_TREE, "DeleteTree"),
+            (AccessMask::WRITE_PROPERTY, "WriteProperty"),
+            (AccessMask::READ_PROPERTY, "ReadProperty"),
+            (AccessMask::SELF_WRITE, "Self"),
+            (AccessMask::LIST_CHILDREN, "ListChildren"),
+            (AccessMask::DELETE_CHILD, "DeleteChild"),
+            (AccessMask::CREATE_CHILD, "CreateChild"),
+        ];
+
+        // 0x000f01ff is the combination of all standard and DS specific rights
+        if self
+            .mask
+            .contains(AccessMask::from_bits_retain(0x000f_01ff))
+        {
+            return vec!["GenericAll"];
+        }
+
+        RIGHTS
+            .iter()
+            .filter(|(right, _)| self.mask.contains(*right))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sddl::{AccessMask, Ace, AceHeaderFlags};
+
+    use super::{AccessControlEntry, AccessControlType};
+
+    fn sid(sid: &str) -> sddl::Sid {
+        sddl::Sid::try_from(sid).unwrap()
+    }
+
+    #[test]
+    fn test_access_control_entry() {
+        let ace = AccessControlEntry::from_ace(&Ace::access_allowed(
+            AceHeaderFlags::empty(),
+            AccessMask::READ_PROPERTY | AccessMask::WRITE_PROPERTY,
+            sid("S-1-5-21-1-2-3-1104"),
+        ))
+        .unwrap();
+        assert!(*ace.access_type() == AccessControlType::Allow);
+        assert_eq!(ace.rights(), vec!["WriteProperty", "ReadProperty"]);
+        assert_eq!(ace.trustee().to_string(), "S-1-5-21-1-2-3-1104");
+        assert!(ace.object_type().is_none());
+        assert!(!ace.is_inheritable());
+        assert!(!ace.is_inherited());
+    }
+
+    #[test]
+    fn test_object_access_control_entry() {
+        let user_class = sddl::Guid::try_from("bf967aba-0de6-11d0-a285-00aa003049e2").unwrap();
+        let ace = AccessControlEntry::from_ace(&Ace::access_denied_object(
+            AceHeaderFlags::CONTAINER_INHERIT_ACE | AceHeaderFlags::INHERIT_ONLY_ACE,
+            AccessMask::CREATE_CHILD,
+            Some(user_class),
+            None,
+            sid("S-1-5-21-1-2-3-1105"),
+        ))
+        .unwrap();
+        assert!(*ace.access_type() == AccessControlType::Deny);
+        assert_eq!(ace.rights(), vec!["CreateChild"]);
+        assert_eq!(
+            ace.object_type().as_ref().unwrap().to_string(),
+            "bf967aba-0de6-11d0-a285-00aa003049e2"
+        );
+        assert!(ace.inherited_object_type().is_none());
+        assert!(ace.is_inheritable());
+        assert!(ace.is_inherit_only());
+    }
+
+    #[test]
+    fn test_generic_all() {
+        let ace = AccessControlEntry::from_ace(&Ace::access_allowed(
+            AceHeaderFlags::INHERITED_ACE,
+            AccessMask::from_bits_retain(0x000f_01ff),
+            sid("S-1-5-32-544"),
+        ))
+        .unwrap();
+        assert_eq!(ace.rights(), vec!["GenericAll"]);
+        assert!(ace.is_inherited());
+        assert_eq!(*ace.well_known_name(), Some("BUILTIN\\Administrators"));
+    }
+
+    #[test]
+    fn test_audit_entries_are_ignored() {
+        let ace = Ace::audit(
+            AceHeaderFlags::SUCCESSFUL_ACCESS_ACE_FLAG,
+            AccessMask::WRITE_DACL,
+            sid("S-1-1-0"),
+        );
+        assert!(AccessControlEntry::from_ace(&ace).is_none());
+    }
+}
@@ -0,0 +1,170 @@
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+
+/// Client Authentication
+pub const EKU_CLIENT_AUTHENTICATION: &str = "1.3.6.1.5.5.7.3.2";
+
+/// PKINIT Client Authentication
+pub const EKU_PKINIT_CLIENT_AUTHENTICATION: &str = "1.3.6.1.5.2.3.4";
+
+/// Smart Card Logon
+pub const EKU_SMART_CARD_LOGON: &str = "1.3.6.1.4.1.311.20.2.2";
+
+/// Any Purpose
+pub const EKU_ANY_PURPOSE: &str = "2.5.29.37.0";
+
+/// Certificate Request Agent (enrollment agent)
+pub const EKU_CERTIFICATE_REQUEST_AGENT: &str = "1.3.6.1.4.1.311.20.2.1";
+
+/// names of common extended key usages
+const EKU_NAMES: [(&str, &str); 14] = [
+    (EKU_CLIENT_AUTHENTICATION, "Client Authentication"),
+    (
+        EKU_PKINIT_CLIENT_AUTHENTICATION,
+        "PKINIT Client Authentication",
+    ),
+    (EKU_SMART_CARD_LOGON, "Smart Card Logon"),
+    (EKU_ANY_PURPOSE, "Any Purpose"),
+    (EKU_CERTIFICATE_REQUEST_AGENT, "Certificate Request Agent"),
+    ("1.3.6.1.5.5.7.3.1", "Server Authentication"),
+    ("1.3.6.1.5.5.7.3.3", "Code Signing"),
+    ("1.3.6.1.5.5.7.3.4", "Secure Email"),
+    ("1.3.6.1.5.5.7.3.8", "Time Stamping"),
+    ("1.3.6.1.5.5.7.3.9", "OCSP Signing"),
+    ("1.3.6.1.4.1.311.10.3.4", "Encrypting File System"),
+    ("1.3.6.1.4.1.311.10.3.4.1", "File Recovery"),
+    ("1.3.6.1.4.1.311.21.5", "Private Key Archival"),
+    ("1.3.6.1.4.1.311.21.6", "Key Recovery Agent"),
+];
+
+/// returns the name of an extended key usage, or the OID itself if the
+/// name is unknown
+pub fn eku_name(oid: &str) -> &str {
+    EKU_NAMES
+        .iter()
+        .find(|(o, _)| *o == oid)
+        .map(|(_, name)| *name)
+        .unwrap_or(oid)
+}
+
+/// `true` if a certificate with these extended key usages can be used to
+/// authenticate against Active Directory. A certificate without any
+/// extended key usage can be used for any purpose
+pub fn allows_client_authentication(ekus: &[String]) -> bool {
+    ekus.is_empty()
+        || ekus.iter().any(|eku| {
+            [
+                EKU_CLIENT_AUTHENTICATION,
+                EKU_PKINIT_CLIENT_AUTHENTICATION,
+                EKU_SMART_CARD_LOGON,
+                EKU_ANY_PURPOSE,
+            ]
+            .contains(&eku.as_str())
+        })
+}
+
+bitflags! {
+
+    /// `msPKI-Certificate-Name-Flag`
+    ///
+    /// Source: https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-crtd/1192823c-d839-4bc3-9b6b-fa8c53507ae1
+    #[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+    pub struct CertificateNameFlags : u32 {
+
+        /// the subject is supplied in the request
+        const CT_FLAG_ENROLLEE_SUPPLIES_SUBJECT = 0x0000_0001;
+
+        const CT_FLAG_OLD_CERT_SUPPLIES_SUBJECT_AND_ALT_NAME = 0x0000_0008;
+
+        /// the subject alternative name is supplied in the request
+        const CT_FLAG_ENROLLEE_SUPPLIES_SUBJECT_ALT_NAME = 0x0001_0000;
+
+        const CT_FLAG_SUBJECT_ALT_REQUIRE_DOMAIN_DNS = 0x0040_0000;
+
+        const CT_FLAG_SUBJECT_ALT_REQUIRE_SPN = 0x0080_0000;
+
+        const CT_FLAG_SUBJECT_ALT_REQUIRE_DIRECTORY_GUID = 0x0100_0000;
+
+        const CT_FLAG_SUBJECT_ALT_REQUIRE_UPN = 0x0200_0000;
+
+        const CT_FLAG_SUBJECT_ALT_REQUIRE_EMAIL = 0x0400_0000;
+
+        const CT_FLAG_SUBJECT_ALT_REQUIRE_DNS = 0x0800_0000;
+
+        const CT_FLAG_SUBJECT_REQUIRE_DNS_AS_CN = 0x1000_0000;
+
+        const CT_FLAG_SUBJECT_REQUIRE_EMAIL = 0x2000_0000;
+
+        const CT_FLAG_SUBJECT_REQUIRE_COMMON_NAME = 0x4000_0000;
+
+        const CT_FLAG_SUBJECT_REQUIRE_DIRECTORY_PATH = 0x8000_0000;
+    }
+}
+
+bitflags! {
+
+    /// `msPKI-Enrollment-Flag`
+    ///
+    /// Source: https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-crtd/ec71fd43-61c2-407b-83c9-b52272dec8a1
+    #[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+    pub struct EnrollmentFlags : u32 {
+
+        const CT_FLAG_INCLUDE_SYMMETRIC_ALGORITHMS = 0x0000_0001;
+
+        /// requests must be approved by a certificate manager
+        const CT_FLAG_PEND_ALL_REQUESTS = 0x0000_0002;
+
+        const CT_FLAG_PUBLISH_TO_KRA_CONTAINER = 0x0000_0004;
+
+        const CT_FLAG_PUBLISH_TO_DS = 0x0000_0008;
+
+        const CT_FLAG_AUTO_ENROLLMENT_CHECK_USER_DS_CERTIFICATE = 0x0000_0010;
+
+        const CT_FLAG_AUTO_ENROLLMENT = 0x0000_0020;
+
+        const CT_FLAG_PREVIOUS_APPROVAL_VALIDATE_REENROLLMENT = 0x0000_0040;
+
+        const CT_FLAG_USER_INTERACTION_REQUIRED = 0x0000_0100;
+
+        const CT_FLAG_REMOVE_INVALID_CERTIFICATE_FROM_PERSONAL_STORE = 0x0000_0400;
+
+        const CT_FLAG_ALLOW_ENROLL_ON_BEHALF_OF = 0x0000_0800;
+
+        const CT_FLAG_ADD_OCSP_NOCHECK = 0x0000_1000;
+
+        const CT_FLAG_ENABLE_KEY_REUSE_ON_NT_TOKEN_KEYSET_STORAGE_FULL = 0x0000_2000;
+
+        const CT_FLAG_NOREVOCATIONINFOINISSUEDCERTS = 0x0000_4000;
+
+        const CT_FLAG_INCLUDE_BASIC_CONSTRAINTS_FOR_EE_CERTS = 0x0000_8000;
+
+        const CT_FLAG_ALLOW_PREVIOUS_APPROVAL_KEYBASEDRENEWAL_VALIDATE_REENROLLMENT = 0x0001_0000;
+
+        const CT_FLAG_ISSUANCE_POLICIES_FROM_REQUEST = 0x0002_0000;
+
+        const CT_FLAG_SKIP_AUTO_RENEWAL = 0x0004_0000;
+
+        /// the szOID_NTDS_CA_SECURITY_EXT extension (which contains the
+        /// SID of the requester) is not added to certificates
+        const CT_FLAG_NO_SECURITY_EXTENSION = 0x0008_0000;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{allows_client_authentication, eku_name, EKU_CLIENT_AUTHENTICATION};
+
+    #[test]
+    fn test_client_authentication() {
+        assert!(allows_client_authentication(&[]));
+        assert!(allows_client_authentication(&[
+            "1.3.6.1.5.5.7.3.1".to_string(),
+            EKU_CLIENT_AUTHENTICATION.to_string()
+        ]));
+        assert!(!allows_client_authentication(&[
+            "1.3.6.1.5.5.7.3.1".to_string()
+        ]));
+        assert_eq!(eku_name(EKU_CLIENT_AUTHENTICATION), "Client Authentication");
+        assert_eq!(eku_name("1.2.3.4"), "1.2.3.4");
+    }
+}
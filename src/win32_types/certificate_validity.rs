@@ -0,0 +1,138 @@
+use anyhow::{bail, ensure, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use getset::Getters;
+
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_VERSION: u8 = 0xa0;
+const TAG_UTC_TIME: u8 = 0x17;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+
+/// the validity period of a DER encoded X.509 certificate, as stored in
+/// `userCertificate` or `cACertificate`
+#[derive(Getters, Clone, Eq, PartialEq)]
+#[getset(get = "pub")]
+pub struct CertificateValidity {
+    not_before: DateTime<Utc>,
+    not_after: DateTime<Utc>,
+}
+
+impl TryFrom<&[u8]> for CertificateValidity {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let (certificate, _) = read_tlv(value, TAG_SEQUENCE)?;
+        let (tbs_certificate, _) = read_tlv(certificate, TAG_SEQUENCE)?;
+
+        let mut fields = tbs_certificate;
+        if fields.first() == Some(&TAG_VERSION) {
+            fields = read_tlv(fields, TAG_VERSION)?.1;
+        }
+
+        // skip serialNumber, signature and issuer
+        for _ in 0..3 {
+            fields = read_any_tlv(fields)?.2;
+        }
+
+        let (validity, _) = read_tlv(fields, TAG_SEQUENCE)?;
+        let (not_before, rest) = read_time(validity)?;
+        let (not_after, _) = read_time(rest)?;
+        Ok(Self {
+            not_before,
+            not_after,
+        })
+    }
+}
+
+/// reads a single DER element and returns its tag, its content and the
+/// remaining bytes
+fn read_any_tlv(data: &[u8]) -> Result<(u8, &[u8], &[u8])> {
+    ensure!(data.len() >= 2, "unexpected end of DER data");
+    let tag = data[0];
+    let (length, offset) = match data[1] {
+        length if length < 0x80 => (length as usize, 2),
+        length => {
+            let count = (length & 0x7f) as usize;
+            ensure!(
+                (1..=4).contains(&count) && data.len() >= 2 + count,
+                "invalid DER length"
+            );
+            let length = data[2..2 + count]
+                .iter()
+                .fold(0usize, |length, b| (length << 8) | *b as usize);
+            (length, 2 + count)
+        }
+    };
+    ensure!(data.len() >= offset + length, "unexpected end of DER data");
+    Ok((
+        tag,
+        &data[offset..offset + length],
+        &data[offset + length..],
+    ))
+}
+
+fn read_tlv(data: &[u8], expected_tag: u8) -> Result<(&[u8], &[u8])> {
+    let (tag, content, rest) = read_any_tlv(data)?;
+    ensure!(
+        tag == expected_tag,
+        "expected DER tag {expected_tag:#04x}, found {tag:#04x}"
+    );
+    Ok((content, rest))
+}
+
+fn read_time(data: &[u8]) -> Result<(DateTime<Utc>, &[u8])> {
+    let (tag, content, rest) = read_any_tlv(data)?;
+    let value = std::str::from_utf8(content)?;
+    let value = match tag {
+        TAG_UTC_TIME => {
+            // RFC 5280, 4.1.2.5.1
+            let year: u32 = value.get(0..2).unwrap_or_default().parse()?;
+            let century = if year >= 50 { "19" } else { "20" };
+            format!("{century}{value}")
+        }
+        TAG_GENERALIZED_TIME => value.to_string(),
+        _ => bail!("expected a DER time value, found tag {tag:#04x}"),
+    };
+    let ts = NaiveDateTime::parse_from_str(&value, "%Y%m%d%H%M%SZ")?;
+    Ok((ts.and_utc(), rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CertificateValidity;
+
+    #[test]
+    fn test_certificate_validity() {
+        let validity = [
+            &[0x30, 0x20, 0x17, 0x0d][..],
+            b"240501133700Z",
+            &[0x18, 0x0f],
+            b"20500101000000Z",
+        ]
+        .concat();
+        let tbs_certificate = [
+            &[0xa0, 0x03, 0x02, 0x01, 0x02][..],
+            &[0x02, 0x01, 0x01],
+            &[0x30, 0x00],
+            &[0x30, 0x00],
+            &validity,
+            &[0x30, 0x00],
+        ]
+        .concat();
+        let certificate = [
+            &[0x30, 0x82, 0x00, tbs_certificate.len() as u8 + 2, 0x30][..],
+            &[tbs_certificate.len() as u8],
+            &tbs_certificate,
+        ]
+        .concat();
+
+        let validity = CertificateValidity::try_from(&certificate[..]).unwrap();
+        assert_eq!(
+            validity.not_before().to_rfc3339(),
+            "2024-05-01T13:37:00+00:00"
+        );
+        assert_eq!(
+            validity.not_after().to_rfc3339(),
+            "2050-01-01T00:00:00+00:00"
+        );
+    }
+}
@@ -0,0 +1,330 @@
+use std::io::{Cursor, Read};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use anyhow::{ensure, Result};
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
+use getset::Getters;
+
+use super::WindowsFileTime;
+
+/// number of 100ns intervals per hour
+const INTERVALS_PER_HOUR: u64 = 36_000_000_000;
+
+const DNS_TYPE_ZERO: u16 = 0x0000;
+const DNS_TYPE_A: u16 = 0x0001;
+const DNS_TYPE_NS: u16 = 0x0002;
+const DNS_TYPE_CNAME: u16 = 0x0005;
+const DNS_TYPE_SOA: u16 = 0x0006;
+const DNS_TYPE_PTR: u16 = 0x000c;
+const DNS_TYPE_MX: u16 = 0x000f;
+const DNS_TYPE_TXT: u16 = 0x0010;
+const DNS_TYPE_AAAA: u16 = 0x001c;
+const DNS_TYPE_SRV: u16 = 0x0021;
+
+/// data of a [`DnsRecord`], depending on the record type
+#[derive(Eq, PartialEq)]
+pub enum DnsRecordData {
+    /// the record has been deleted (`DNS_TYPE_ZERO`). Contains the time of
+    /// the deletion
+    Tombstone(Option<WindowsFileTime>),
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Ns(String),
+    Cname(String),
+    Ptr(String),
+    Mx {
+        preference: u16,
+        exchange: String,
+    },
+    Srv {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: String,
+    },
+    Txt(Vec<String>),
+    Soa {
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum_ttl: u32,
+        primary_server: String,
+        zone_admin: String,
+    },
+    Unknown(u16, Vec<u8>),
+}
+
+/// a single value of `dnsRecord` (a `DnsRecord` structure)
+///
+/// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-dnsp/6912b338-5472-4f59-b912-0edb536b6ed8>
+#[derive(Getters, Eq, PartialEq)]
+#[getset(get = "pub")]
+pub struct DnsRecord {
+    rank: u8,
+    flags: u16,
+    serial: u32,
+    ttl: u32,
+
+    /// time of the last refresh of a dynamic record, or `None` for static
+    /// records
+    timestamp: Option<WindowsFileTime>,
+    data: DnsRecordData,
+}
+
+impl TryFrom<&[u8]> for DnsRecord {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let mut rdr = Cursor::new(value);
+        let data_length = rdr.read_u16::<LittleEndian>()?;
+        let record_type = rdr.read_u16::<LittleEndian>()?;
+        let version = rdr.read_u8()?;
+        ensure!(version == 5, "unsupported DnsRecord version: {version}");
+        let rank = rdr.read_u8()?;
+        let flags = rdr.read_u16::<LittleEndian>()?;
+        let serial = rdr.read_u32::<LittleEndian>()?;
+        let ttl = rdr.read_u32::<BigEndian>()?;
+        let _reserved = rdr.read_u32::<LittleEndian>()?;
+        let timestamp = match rdr.read_u32::<LittleEndian>()? {
+            0 => None,
+            hours => match u64::from(hours).checked_mul(INTERVALS_PER_HOUR) {
+                Some(intervals) => Some(WindowsFileTime::from(intervals)),
+                None => {
+                    log::warn!("invalid DNS record timestamp: {hours} hours");
+                    None
+                }
+            },
+        };
+
+        let mut data = vec![0; data_length.into()];
+        rdr.read_exact(&mut data)?;
+
+        Ok(Self {
+            rank,
+            flags,
+            serial,
+            ttl,
+            timestamp,
+            data: DnsRecordData::parse(record_type, data)?,
+        })
+    }
+}
+
+impl DnsRecordData {
+    fn parse(record_type: u16, data: Vec<u8>) -> Result<Self> {
+        let mut rdr = Cursor::new(&data[..]);
+        Ok(match record_type {
+            DNS_TYPE_ZERO => Self::Tombstone(
+                rdr.read_u64::<LittleEndian>()
+                    .ok()
+                    .map(WindowsFileTime::from),
+            ),
+            DNS_TYPE_A => Self::A(Ipv4Addr::from(rdr.read_u32::<BigEndian>()?)),
+            DNS_TYPE_AAAA => Self::Aaaa(Ipv6Addr::from(rdr.read_u128::<BigEndian>()?)),
+            DNS_TYPE_NS => Self::Ns(read_count_name(&mut rdr)?),
+            DNS_TYPE_CNAME => Self::Cname(read_count_name(&mut rdr)?),
+            DNS_TYPE_PTR => Self::Ptr(read_count_name(&mut rdr)?),
+            DNS_TYPE_MX => Self::Mx {
+                preference: rdr.read_u16::<BigEndian>()?,
+                exchange: read_count_name(&mut rdr)?,
+            },
+            DNS_TYPE_SRV => Self::Srv {
+                priority: rdr.read_u16::<BigEndian>()?,
+                weight: rdr.read_u16::<BigEndian>()?,
+                port: rdr.read_u16::<BigEndian>()?,
+                target: read_count_name(&mut rdr)?,
+            },
+            DNS_TYPE_TXT => {
+                let mut strings = Vec::new();
+                while (rdr.position() as usize) < data.len() {
+                    strings.push(read_name(&mut rdr)?);
+                }
+                Self::Txt(strings)
+            }
+            DNS_TYPE_SOA => Self::Soa {
+                serial: rdr.read_u32::<BigEndian>()?,
+                refresh: rdr.read_u32::<BigEndian>()?,
+                retry: rdr.read_u32::<BigEndian>()?,
+                expire: rdr.read_u32::<BigEndian>()?,
+                minimum_ttl: rdr.read_u32::<BigEndian>()?,
+                primary_server: read_count_name(&mut rdr)?,
+                zone_admin: read_count_name(&mut rdr)?,
+            },
+            t => Self::Unknown(t, data),
+        })
+    }
+}
+
+/// reads a `DNS_RPC_NAME`, which is a string with a leading length byte
+fn read_name(rdr: &mut Cursor<&[u8]>) -> Result<String> {
+    let length = rdr.read_u8()?;
+    let mut name = vec![0; length.into()];
+    rdr.read_exact(&mut name)?;
+    Ok(String::from_utf8_lossy(&name).to_string())
+}
+
+/// reads a `DNS_COUNT_NAME` and returns it as fully qualified name
+fn read_count_name(rdr: &mut Cursor<&[u8]>) -> Result<String> {
+    let _length = rdr.read_u8()?;
+    let label_count = rdr.read_u8()?;
+    let mut labels = Vec::with_capacity(label_count.into());
+    for _ in 0..label_count {
+        let label = read_name(rdr)?;
+        if label.is_empty() {
+            break;
+        }
+        labels.push(label);
+    }
+    Ok(format!("{}.", labels.join(".")))
+}
+
+impl DnsRecord {
+    /// returns the mnemonic of the record type, as used in zone files
+    pub fn record_type(&self) -> String {
+        match &self.data {
+            DnsRecordData::Tombstone(_) => "ZERO".to_string(),
+            DnsRecordData::A(_) => "A".to_string(),
+            DnsRecordData::Aaaa(_) => "AAAA".to_string(),
+            DnsRecordData::Ns(_) => "NS".to_string(),
+            DnsRecordData::Cname(_) => "CNAME".to_string(),
+            DnsRecordData::Ptr(_) => "PTR".to_string(),
+            DnsRecordData::Mx { .. } => "MX".to_string(),
+            DnsRecordData::Srv { .. } => "SRV".to_string(),
+            DnsRecordData::Txt(_) => "TXT".to_string(),
+            DnsRecordData::Soa { .. } => "SOA".to_string(),
+            DnsRecordData::Unknown(t, _) => format!("TYPE{t}"),
+        }
+    }
+
+    /// returns the data of this record, in the presentation format of
+    /// RFC 1035
+    pub fn rdata(&self) -> String {
+        match &self.data {
+            DnsRecordData::Tombstone(_) => String::new(),
+            DnsRecordData::A(addr) => addr.to_string(),
+            DnsRecordData::Aaaa(addr) => addr.to_string(),
+            DnsRecordData::Ns(name) | DnsRecordData::Cname(name) | DnsRecordData::Ptr(name) => {
+                name.clone()
+            }
+            DnsRecordData::Mx {
+                preference,
+                exchange,
+            } => format!("{preference} {exchange}"),
+            DnsRecordData::Srv {
+                priority,
+                weight,
+                port,
+                target,
+            } => format!("{priority} {weight} {port} {target}"),
+            DnsRecordData::Txt(strings) => strings
+                .iter()
+                .map(|s| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")))
+                .collect::<Vec<_>>()
+                .join(" "),
+            DnsRecordData::Soa {
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum_ttl,
+                primary_server,
+                zone_admin,
+            } => format!(
+                "{primary_server} {zone_admin} {serial} {refresh} {retry} {expire} {minimum_ttl}"
+            ),
+            DnsRecordData::Unknown(_, data) => format!("\\# {} {}", data.len(), hex::encode(data)),
+        }
+    }
+
+    pub fn is_tombstone(&self) -> bool {
+        matches!(self.data, DnsRecordData::Tombstone(_))
+    }
+
+    /// returns a line of a zone file, which describes this record
+    pub fn to_zone_file_line(&self, owner: &str) -> String {
+        let line = format!(
+            "{owner}\t{}\tIN\t{}\t{}",
+            self.ttl,
+            self.record_type(),
+            self.rdata()
+        );
+        if self.is_tombstone() {
+            format!("; {line}")
+        } else {
+            line
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DnsRecord, DnsRecordData};
+
+    fn record(record_type: u16, timestamp: u32, data: &[u8]) -> Vec<u8> {
+        let mut result = Vec::from((data.len() as u16).to_le_bytes());
+        result.extend_from_slice(&record_type.to_le_bytes());
+        result.extend_from_slice(&[5, 0xf0, 0, 0]);
+        result.extend_from_slice(&1u32.to_le_bytes());
+        result.extend_from_slice(&600u32.to_be_bytes());
+        result.extend_from_slice(&0u32.to_le_bytes());
+        result.extend_from_slice(&timestamp.to_le_bytes());
+        result.extend_from_slice(data);
+        result
+    }
+
+    fn count_name(labels: &[&str]) -> Vec<u8> {
+        let mut result = vec![0, labels.len() as u8 + 1];
+        for label in labels {
+            result.push(label.len() as u8);
+            result.extend_from_slice(label.as_bytes());
+        }
+        result.push(0);
+        result[0] = (result.len() - 2) as u8;
+        result
+    }
+
+    #[test]
+    fn test_invalid_timestamp() {
+        let blob = record(0x0001, u32::MAX, &[10, 0, 0, 1]);
+        let record = DnsRecord::try_from(&blob[..]).unwrap();
+        assert!(record.timestamp().is_none());
+        assert_eq!(record.rdata(), "10.0.0.1");
+    }
+
+    #[test]
+    fn test_parse_a_record() {
+        let blob = record(0x0001, 3_600_000, &[10, 0, 0, 1]);
+        let record = DnsRecord::try_from(&blob[..]).unwrap();
+        assert_eq!(record.record_type(), "A");
+        assert_eq!(record.rdata(), "10.0.0.1");
+        assert_eq!(*record.ttl(), 600);
+        assert!(record.timestamp().is_some());
+        assert_eq!(
+            record.to_zone_file_line("wpad.example.com."),
+            "wpad.example.com.\t600\tIN\tA\t10.0.0.1"
+        );
+    }
+
+    #[test]
+    fn test_parse_srv_record() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&0u16.to_be_bytes());
+        data.extend_from_slice(&100u16.to_be_bytes());
+        data.extend_from_slice(&389u16.to_be_bytes());
+        data.extend(count_name(&["dc01", "example", "com"]));
+
+        let blob = record(0x0021, 0, &data);
+        let record = DnsRecord::try_from(&blob[..]).unwrap();
+        assert!(record.timestamp().is_none());
+        assert_eq!(record.rdata(), "0 100 389 dc01.example.com.");
+    }
+
+    #[test]
+    fn test_parse_tombstone() {
+        let blob = record(0x0000, 0, &0x01d9_0000_0000_0000u64.to_le_bytes());
+        let record = DnsRecord::try_from(&blob[..]).unwrap();
+        assert!(matches!(record.data(), DnsRecordData::Tombstone(Some(_))));
+        assert!(record.to_zone_file_line("@").starts_with("; "));
+    }
+}
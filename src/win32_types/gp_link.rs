@@ -0,0 +1,103 @@
+use getset::Getters;
+
+/// the link is disabled
+const LINK_DISABLED: u32 = 0x1;
+
+/// the link is enforced (no override)
+const LINK_ENFORCED: u32 = 0x2;
+
+/// a single link to a group policy object, which is stored in `gPLink`
+///
+/// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-gpol/08090b22-bc16-49f4-8e10-f27a8fb16d18>
+#[derive(Getters, Clone, PartialEq, Eq, Debug)]
+#[getset(get = "pub")]
+pub struct GpLinkEntry {
+    /// distinguished name of the group policy container
+    gpo_dn: String,
+
+    /// link order as shown by the Group Policy Management Console, where 1
+    /// is the link with the highest precedence
+    link_order: usize,
+    options: u32,
+}
+
+impl GpLinkEntry {
+    /// parses a `gPLink` value, e.g.
+    /// `[LDAP://cn={31B2F340-016D-11D2-945F-00C04FB984F9},cn=policies,cn=system,DC=example,DC=com;0]`
+    pub fn parse(value: &str) -> Vec<Self> {
+        let links: Vec<_> = value
+            .split(['[', ']'])
+            .filter(|s| !s.trim().is_empty())
+            .filter_map(|link| {
+                let (path, options) = link.rsplit_once(';')?;
+                let gpo_dn = match path.get(..7) {
+                    Some(prefix) if prefix.eq_ignore_ascii_case("LDAP://") => &path[7..],
+                    _ => path,
+                };
+                Some((gpo_dn.to_string(), options.trim().parse().unwrap_or(0)))
+            })
+            .collect();
+
+        // the last link in the list has the highest precedence
+        let count = links.len();
+        links
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (gpo_dn, options))| Self {
+                gpo_dn,
+                link_order: count - idx,
+                options,
+            })
+            .collect()
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.options & LINK_DISABLED != 0
+    }
+
+    pub fn is_enforced(&self) -> bool {
+        self.options & LINK_ENFORCED != 0
+    }
+
+    /// returns the common name of the group policy container, which is the
+    /// GUID of the GPO
+    pub fn gpo_cn(&self) -> Option<&str> {
+        let rdn = self.gpo_dn.split(',').next()?;
+        let (attribute, value) = rdn.split_once('=')?;
+        if attribute.trim().eq_ignore_ascii_case("cn") {
+            Some(value.trim())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GpLinkEntry;
+
+    #[test]
+    fn test_parse_gp_link() {
+        let links = GpLinkEntry::parse(
+            "[LDAP://cn={31B2F340-016D-11D2-945F-00C04FB984F9},cn=policies,cn=system,DC=example,DC=com;0]\
+             [LDAP://CN={6AC1786C-016F-11D2-945F-00C04FB984F9},CN=Policies,CN=System,DC=example,DC=com;3]",
+        );
+        assert_eq!(links.len(), 2);
+        assert_eq!(
+            links[0].gpo_cn(),
+            Some("{31B2F340-016D-11D2-945F-00C04FB984F9}")
+        );
+        assert_eq!(*links[0].link_order(), 2);
+        assert!(!links[0].is_enforced());
+        assert!(!links[0].is_disabled());
+
+        assert_eq!(*links[1].link_order(), 1);
+        assert!(links[1].is_enforced());
+        assert!(links[1].is_disabled());
+    }
+
+    #[test]
+    fn test_parse_empty_gp_link() {
+        assert!(GpLinkEntry::parse(" ").is_empty());
+    }
+}
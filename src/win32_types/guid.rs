@@ -5,7 +5,7 @@ use uuid::Uuid;
 
 use crate::{cache::Value, value::FromValue};
 
-#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Hash)]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Clone, Hash)]
 pub struct Guid(Uuid);
 
 impl FromValue for Guid {
@@ -15,6 +15,7 @@ impl FromValue for Guid {
     {
         match value {
             Value::Null(_) => Ok(None),
+            Value::Multi(values) => crate::value::first_value_of(values),
             Value::Binary(v) | Value::LargeBinary(v) | Value::Guid(v) => {
                 Ok(Some(Self(Uuid::from_slice_le(&v[..])?)))
             }
@@ -34,6 +35,22 @@ impl FromStr for Guid {
     }
 }
 
+impl TryFrom<&[u8]> for Guid {
+    type Error = uuid::Error;
+
+    /// parses a GUID in its binary (mixed-endian) representation
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        Ok(Self(Uuid::from_slice_le(value)?))
+    }
+}
+
+impl From<&sddl::Guid> for Guid {
+    fn from(guid: &sddl::Guid) -> Self {
+        // both types wrap a [`Uuid`], so this conversion cannot fail
+        Self(Uuid::from_str(&guid.to_string()).expect("invalid GUID representation"))
+    }
+}
+
 impl Display for Guid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
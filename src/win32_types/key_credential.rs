@@ -0,0 +1,343 @@
+use std::io::{Cursor, Read};
+
+use anyhow::{bail, ensure, Result};
+use base64::prelude::*;
+use byteorder::{LittleEndian, ReadBytesExt};
+use getset::Getters;
+use strum::Display;
+
+use super::{Guid, WindowsFileTime};
+
+const KEY_CREDENTIAL_LINK_VERSION_2: u32 = 0x0000_0200;
+const BCRYPT_RSAPUBLIC_MAGIC: u32 = 0x3141_5352;
+
+#[derive(Display, Clone, Copy, Eq, PartialEq)]
+pub enum KeyUsage {
+    #[strum(serialize = "AdminKey")]
+    AdminKey,
+
+    /// Next Generation Credential, which is used by Windows Hello
+    #[strum(serialize = "NGC")]
+    Ngc,
+
+    #[strum(serialize = "STK")]
+    TransportKey,
+
+    #[strum(serialize = "BitlockerRecovery")]
+    BitlockerRecovery,
+
+    #[strum(serialize = "Other")]
+    Other,
+
+    #[strum(serialize = "FIDO")]
+    Fido,
+
+    #[strum(serialize = "FEK")]
+    FileEncryptionKey,
+
+    #[strum(serialize = "DPAPI")]
+    Dpapi,
+
+    #[strum(serialize = "Unknown({0})")]
+    Unknown(u8),
+}
+
+impl From<u8> for KeyUsage {
+    fn from(value: u8) -> Self {
+        match value {
+            0x00 => Self::AdminKey,
+            0x01 => Self::Ngc,
+            0x02 => Self::TransportKey,
+            0x03 => Self::BitlockerRecovery,
+            0x04 => Self::Other,
+            0x07 => Self::Fido,
+            0x08 => Self::FileEncryptionKey,
+            0x09 => Self::Dpapi,
+            v => Self::Unknown(v),
+        }
+    }
+}
+
+#[derive(Display, Clone, Copy, Eq, PartialEq)]
+pub enum KeySource {
+    #[strum(serialize = "AD")]
+    ActiveDirectory,
+
+    #[strum(serialize = "AzureAD")]
+    AzureActiveDirectory,
+
+    #[strum(serialize = "Unknown({0})")]
+    Unknown(u8),
+}
+
+impl From<u8> for KeySource {
+    fn from(value: u8) -> Self {
+        match value {
+            0x00 => Self::ActiveDirectory,
+            0x01 => Self::AzureActiveDirectory,
+            v => Self::Unknown(v),
+        }
+    }
+}
+
+/// public key which is stored in a [`KeyCredential`]
+pub enum PublicKey {
+    Rsa { modulus: Vec<u8>, exponent: Vec<u8> },
+    Der(Vec<u8>),
+    Unknown,
+}
+
+impl PublicKey {
+    fn parse(material: Vec<u8>) -> Self {
+        match Self::parse_rsa(&material) {
+            Ok(key) => key,
+            Err(_) if material.first() == Some(&0x30) => Self::Der(material),
+            Err(_) => Self::Unknown,
+        }
+    }
+
+    /// parses a `BCRYPT_RSAKEY_BLOB`
+    fn parse_rsa(material: &[u8]) -> Result<Self> {
+        let mut rdr = Cursor::new(material);
+        ensure!(
+            rdr.read_u32::<LittleEndian>()? == BCRYPT_RSAPUBLIC_MAGIC,
+            "no RSA public key"
+        );
+        let _bit_length = rdr.read_u32::<LittleEndian>()?;
+        let exponent_length = rdr.read_u32::<LittleEndian>()?;
+        let modulus_length = rdr.read_u32::<LittleEndian>()?;
+        let _prime1_length = rdr.read_u32::<LittleEndian>()?;
+        let _prime2_length = rdr.read_u32::<LittleEndian>()?;
+
+        let exponent = read_bytes(&mut rdr, exponent_length as usize)?;
+        let modulus = read_bytes(&mut rdr, modulus_length as usize)?;
+        Ok(Self::Rsa { modulus, exponent })
+    }
+
+    /// returns the size of the RSA modulus in bits, if this is a RSA key
+    pub fn key_size(&self) -> Option<usize> {
+        match self {
+            Self::Rsa { modulus, .. } => {
+                let leading_zeros = modulus.iter().take_while(|b| **b == 0).count();
+                match modulus.get(leading_zeros) {
+                    Some(first) => Some(
+                        (modulus.len() - leading_zeros - 1) * 8
+                            + (8 - first.leading_zeros() as usize),
+                    ),
+                    None => Some(0),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// exports the key as PEM encoded `SubjectPublicKeyInfo`
+    pub fn to_pem(&self) -> Option<String> {
+        let der = match self {
+            Self::Rsa { modulus, exponent } => {
+                // OID 1.2.840.113549.1.1.1 (rsaEncryption) with NULL parameters
+                const RSA_ALGORITHM: [u8; 15] = [
+                    0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01,
+                    0x05, 0x00,
+                ];
+                let rsa_public_key = der_tlv(
+                    0x30,
+                    &[der_integer(modulus), der_integer(exponent)].concat(),
+                );
+                let bit_string = der_tlv(0x03, &[&[0u8][..], &rsa_public_key].concat());
+                der_tlv(0x30, &[&RSA_ALGORITHM[..], &bit_string].concat())
+            }
+            Self::Der(der) => der.clone(),
+            Self::Unknown => return None,
+        };
+
+        let encoded = BASE64_STANDARD.encode(der);
+        let lines: Vec<_> = encoded
+            .as_bytes()
+            .chunks(64)
+            .map(|c| std::str::from_utf8(c).unwrap())
+            .collect();
+        Some(format!(
+            "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+            lines.join("\n")
+        ))
+    }
+}
+
+/// reads `length` bytes, after checking that the remaining data is long
+/// enough. The length is read from the blob, so it must not be used to
+/// allocate a buffer before it has been validated
+fn read_bytes(rdr: &mut Cursor<&[u8]>, length: usize) -> Result<Vec<u8>> {
+    let remaining = rdr.get_ref().len().saturating_sub(rdr.position() as usize);
+    ensure!(
+        length <= remaining,
+        "invalid length {length}, only {remaining} bytes are remaining"
+    );
+    let mut data = vec![0; length];
+    rdr.read_exact(&mut data)?;
+    Ok(data)
+}
+
+fn der_tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut result = vec![tag];
+    let length = content.len();
+    if length < 0x80 {
+        result.push(length as u8);
+    } else {
+        let bytes: Vec<u8> = length
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|b| *b == 0)
+            .collect();
+        result.push(0x80 | bytes.len() as u8);
+        result.extend(bytes);
+    }
+    result.extend_from_slice(content);
+    result
+}
+
+fn der_integer(value: &[u8]) -> Vec<u8> {
+    let value: Vec<u8> = value.iter().copied().skip_while(|b| *b == 0).collect();
+    if value.first().map_or(true, |b| b & 0x80 != 0) {
+        der_tlv(0x02, &[&[0u8][..], &value].concat())
+    } else {
+        der_tlv(0x02, &value)
+    }
+}
+
+/// a single entry of `msDS-KeyCredentialLink` (a `KEYCREDENTIALLINK_BLOB`)
+///
+/// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-adts/de61eb56-b75f-4743-b8af-e9be154b47af>
+#[derive(Getters)]
+#[getset(get = "pub")]
+pub struct KeyCredential {
+    key_id: Option<Vec<u8>>,
+    key_hash: Option<Vec<u8>>,
+    public_key: Option<PublicKey>,
+    key_usage: Option<KeyUsage>,
+    key_source: Option<KeySource>,
+    device_id: Option<Guid>,
+    custom_key_information: Option<Vec<u8>>,
+    last_logon_time: Option<WindowsFileTime>,
+    creation_time: Option<WindowsFileTime>,
+}
+
+impl TryFrom<&[u8]> for KeyCredential {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let mut rdr = Cursor::new(value);
+        let version = rdr.read_u32::<LittleEndian>()?;
+        if version != KEY_CREDENTIAL_LINK_VERSION_2 {
+            bail!("unsupported KEYCREDENTIALLINK_BLOB version: 0x{version:08x}");
+        }
+
+        let mut me = Self {
+            key_id: None,
+            key_hash: None,
+            public_key: None,
+            key_usage: None,
+            key_source: None,
+            device_id: None,
+            custom_key_information: None,
+            last_logon_time: None,
+            creation_time: None,
+        };
+
+        while (rdr.position() as usize) < value.len() {
+            let length = rdr.read_u16::<LittleEndian>()?;
+            let identifier = rdr.read_u8()?;
+            let data = read_bytes(&mut rdr, length.into())?;
+
+            match identifier {
+                0x01 => me.key_id = Some(data),
+                0x02 => me.key_hash = Some(data),
+                0x03 => me.public_key = Some(PublicKey::parse(data)),
+                0x04 => me.key_usage = data.first().map(|v| KeyUsage::from(*v)),
+                0x05 => me.key_source = data.first().map(|v| KeySource::from(*v)),
+                0x06 => me.device_id = Some(Guid::try_from(&data[..])?),
+                0x07 => me.custom_key_information = Some(data),
+                0x08 => me.last_logon_time = Some(Self::filetime(&data)?),
+                0x09 => me.creation_time = Some(Self::filetime(&data)?),
+                id => log::warn!("unknown KEYCREDENTIALLINK_ENTRY identifier: {id}"),
+            }
+        }
+
+        Ok(me)
+    }
+}
+
+impl KeyCredential {
+    fn filetime(data: &[u8]) -> Result<WindowsFileTime> {
+        Ok(WindowsFileTime::from(
+            Cursor::new(data).read_u64::<LittleEndian>()?,
+        ))
+    }
+
+    /// uses some heuristics to decide if this key credential has been
+    /// created by the provisioning of Windows Hello for Business. Tools
+    /// which are used to add shadow credentials don't create a device id or
+    /// only write a minimal custom key information (version and flags)
+    pub fn is_windows_hello_key(&self) -> bool {
+        self.key_usage == Some(KeyUsage::Ngc)
+            && self.device_id.is_some()
+            && self
+                .custom_key_information
+                .as_ref()
+                .is_some_and(|cki| cki.len() > 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyCredential, KeySource, KeyUsage, PublicKey};
+
+    fn entry(identifier: u8, data: &[u8]) -> Vec<u8> {
+        let mut result = Vec::from((data.len() as u16).to_le_bytes());
+        result.push(identifier);
+        result.extend_from_slice(data);
+        result
+    }
+
+    #[test]
+    fn test_parse_key_credential() {
+        let mut rsa_key = Vec::new();
+        for v in [0x3141_5352u32, 16, 3, 2, 0, 0] {
+            rsa_key.extend_from_slice(&v.to_le_bytes());
+        }
+        rsa_key.extend_from_slice(&[0x01, 0x00, 0x01, 0xc3, 0x5a]);
+
+        let blob = [
+            Vec::from(0x200u32.to_le_bytes()),
+            entry(0x01, &[0xaa; 32]),
+            entry(0x03, &rsa_key),
+            entry(0x04, &[0x01]),
+            entry(0x05, &[0x00]),
+            entry(0x07, &[0x01, 0x00]),
+            entry(0x09, &0x01d9_0000_0000_0000u64.to_le_bytes()),
+        ]
+        .concat();
+
+        let key = KeyCredential::try_from(&blob[..]).unwrap();
+        assert!(key.key_usage() == &Some(KeyUsage::Ngc));
+        assert!(key.key_source() == &Some(KeySource::ActiveDirectory));
+        assert_eq!(key.public_key().as_ref().unwrap().key_size(), Some(16));
+        assert!(key.public_key().as_ref().unwrap().to_pem().is_some());
+        assert!(key.creation_time().is_some());
+        assert!(!key.is_windows_hello_key());
+    }
+
+    #[test]
+    fn test_invalid_lengths() {
+        let mut rsa_key = Vec::new();
+        for v in [0x3141_5352u32, 2048, 3, 0xffff_ffff, 0, 0] {
+            rsa_key.extend_from_slice(&v.to_le_bytes());
+        }
+        rsa_key.extend_from_slice(&[0x01, 0x00, 0x01]);
+        assert!(PublicKey::parse_rsa(&rsa_key).is_err());
+
+        let mut blob = [Vec::from(0x200u32.to_le_bytes()), entry(0x01, &[0xaa; 32])].concat();
+        blob.truncate(blob.len() - 1);
+        assert!(KeyCredential::try_from(&blob[..]).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+use std::io::Cursor;
+
+use anyhow::{ensure, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use getset::Getters;
+
+use super::{Guid, TruncatedWindowsFileTime};
+
+/// minimum size of the value of `link_metadata`
+const MIN_SIZE: usize = 44;
+
+/// replication metadata of a single value of a linked attribute, as stored
+/// in the `link_metadata` column of the link table
+/// (`VALUE_META_DATA_EXT_V1`, optionally followed by the local USN)
+#[derive(Getters, Clone, Eq, PartialEq)]
+#[getset(get = "pub")]
+pub struct LinkValueMetaData {
+    /// time when the value has been added for the first time
+    time_created: TruncatedWindowsFileTime,
+    version: u32,
+
+    /// time of the last originating change, e.g. when the value has been
+    /// removed or added again
+    time_changed: TruncatedWindowsFileTime,
+
+    /// invocation ID of the domain controller where the last change
+    /// originated
+    originating_dsa: Guid,
+    originating_usn: i64,
+    local_usn: Option<i64>,
+}
+
+impl TryFrom<&[u8]> for LinkValueMetaData {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        ensure!(
+            value.len() >= MIN_SIZE,
+            "the link value metadata is too short ({} bytes)",
+            value.len()
+        );
+        let mut rdr = Cursor::new(value);
+        let time_created = TruncatedWindowsFileTime::try_from(rdr.read_u64::<LittleEndian>()?)?;
+        let version = rdr.read_u32::<LittleEndian>()?;
+        let time_changed = TruncatedWindowsFileTime::try_from(rdr.read_u64::<LittleEndian>()?)?;
+        let position = rdr.position() as usize;
+        let originating_dsa = Guid::try_from(&value[position..position + 16])?;
+        rdr.set_position((position + 16) as u64);
+        let originating_usn = rdr.read_i64::<LittleEndian>()?;
+        let local_usn = rdr.read_i64::<LittleEndian>().ok();
+        Ok(Self {
+            time_created,
+            version,
+            time_changed,
+            originating_dsa,
+            originating_usn,
+            local_usn,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::win32_types::UnixTimestamp;
+
+    use super::LinkValueMetaData;
+
+    #[test]
+    fn test_link_value_metadata() {
+        let mut value = Vec::new();
+        value.extend_from_slice(&13390472401u64.to_le_bytes());
+        value.extend_from_slice(&3u32.to_le_bytes());
+        value.extend_from_slice(&13390472461u64.to_le_bytes());
+        value.extend_from_slice(&[0x11; 16]);
+        value.extend_from_slice(&4711i64.to_le_bytes());
+
+        let metadata = LinkValueMetaData::try_from(&value[..]).unwrap();
+        assert_eq!(*metadata.version(), 3);
+        assert_eq!(
+            metadata.time_changed().timestamp() - metadata.time_created().timestamp(),
+            60
+        );
+        assert_eq!(*metadata.originating_usn(), 4711);
+        assert_eq!(*metadata.local_usn(), None);
+
+        value.extend_from_slice(&4712i64.to_le_bytes());
+        let metadata = LinkValueMetaData::try_from(&value[..]).unwrap();
+        assert_eq!(*metadata.local_usn(), Some(4712));
+
+        assert!(LinkValueMetaData::try_from(&value[..40]).is_err());
+    }
+}
@@ -5,6 +5,17 @@ mod timestamp;
 mod rdn;
 mod guid;
 mod security_descriptor;
+mod access_control_entry;
+mod supported_encryption_types;
+mod key_credential;
+mod trust;
+mod gp_link;
+mod dns_record;
+mod schedule;
+mod certificate_template;
+mod certificate_validity;
+mod replication_metadata;
+mod link_value_metadata;
 
 pub use sam_account_type::*;
 pub use user_account_control::*;
@@ -12,4 +23,15 @@ pub use sid::*;
 pub use timestamp::*;
 pub use rdn::*;
 pub use guid::*;
-pub use security_descriptor::*;
\ No newline at end of file
+pub use security_descriptor::*;
+pub use access_control_entry::*;
+pub use supported_encryption_types::*;
+pub use key_credential::*;
+pub use trust::*;
+pub use gp_link::*;
+pub use dns_record::*;
+pub use schedule::*;
+pub use certificate_template::*;
+pub use certificate_validity::*;
+pub use replication_metadata::*;
+pub use link_value_metadata::*;
\ No newline at end of file
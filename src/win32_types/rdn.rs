@@ -9,7 +9,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::cache::Value;
 use crate::ntds;
-use crate::value::FromValue;
+use crate::value::{first_value_of, FromValue};
 
 use super::Guid;
 
@@ -62,6 +62,7 @@ impl FromValue for Rdn {
                 }))
             }
             Value::Null(()) => Ok(None),
+            Value::Multi(values) => first_value_of(values),
             Value::Long(_) => {
                 log::warn!("no support for LONG columns yet, generating a random value");
                 Ok(Some(Self{
@@ -149,3 +150,61 @@ impl<'de> Visitor<'de> for NameWithGuidVisitor {
         Rdn::try_from(v).or(Err(E::custom(format!("invalid object name: '{v}'"))))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::cache::Value;
+    use crate::value::FromValue;
+    use crate::win32_types::Guid;
+
+    use super::Rdn;
+
+    const GUID: &str = "4d5b8a1e-2b2a-4a49-9d2f-1f0d2b3c4e5f";
+
+    fn rdn(value: &str) -> crate::ntds::Result<Option<Rdn>> {
+        Rdn::from_value_opt(&Value::Text(Box::new(value.to_string())))
+    }
+
+    #[test]
+    fn test_plain_name() {
+        let rdn = rdn("Alice").unwrap().unwrap();
+        assert_eq!(rdn.name(), "Alice");
+        assert!(rdn.deleted_from_container().is_none());
+        assert!(rdn.conflicting_objects().is_empty());
+    }
+
+    #[test]
+    fn test_deleted_name() {
+        let rdn = rdn(&format!("Alice\nDEL:{GUID}")).unwrap().unwrap();
+        assert_eq!(rdn.name(), "Alice");
+        assert!(*rdn.deleted_from_container() == Some(Guid::from_str(GUID).unwrap()));
+        assert!(rdn.conflicting_objects().is_empty());
+        assert_eq!(rdn.to_string(), "Alice (DELETED)");
+    }
+
+    #[test]
+    fn test_conflicting_name() {
+        let rdn = rdn(&format!("Alice\nCNF:{GUID}")).unwrap().unwrap();
+        assert_eq!(rdn.name(), "Alice");
+        assert!(rdn.deleted_from_container().is_none());
+        assert!(*rdn.conflicting_objects() == vec![Guid::from_str(GUID).unwrap()]);
+    }
+
+    #[test]
+    fn test_deleted_conflicting_name() {
+        let rdn = rdn(&format!("Alice\nCNF:{GUID}\nDEL:{GUID}"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(rdn.name(), "Alice");
+        assert!(rdn.deleted_from_container().is_some());
+        assert_eq!(rdn.conflicting_objects().len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_names() {
+        assert!(rdn(&format!("Alice\nDEL:{GUID}\nDEL:{GUID}")).is_err());
+        assert!(rdn("Alice\nCNF:not-a-guid").is_err());
+    }
+}
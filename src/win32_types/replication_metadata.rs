@@ -0,0 +1,117 @@
+use std::io::Cursor;
+
+use anyhow::{ensure, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use getset::Getters;
+
+use super::{Guid, TruncatedWindowsFileTime};
+
+/// size of a single `PROPERTY_META_DATA` entry
+const ENTRY_SIZE: usize = 48;
+
+/// replication metadata of a single attribute (`PROPERTY_META_DATA`)
+#[derive(Getters, Clone, Eq, PartialEq)]
+#[getset(get = "pub")]
+pub struct PropertyMetaData {
+    attribute_id: u32,
+    version: u32,
+
+    /// time of the last originating change
+    time_changed: TruncatedWindowsFileTime,
+
+    /// invocation ID of the domain controller where the last change
+    /// originated
+    originating_dsa: Guid,
+    originating_usn: i64,
+    local_usn: i64,
+}
+
+/// the value of `replPropertyMetaData`, which stores when and where every
+/// replicated attribute of an object has last been changed
+/// (`PROPERTY_META_DATA_VECTOR`)
+///
+/// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-drsr/22bca2bd-8d4e-4aa1-be25-e4b9e4ba8ca3>
+#[derive(Clone, Eq, PartialEq)]
+pub struct ReplicationMetaData(Vec<PropertyMetaData>);
+
+impl TryFrom<&[u8]> for ReplicationMetaData {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let mut rdr = Cursor::new(value);
+        let version = rdr.read_u32::<LittleEndian>()?;
+        ensure!(version == 1, "unsupported metadata version: {version}");
+        let _reserved = rdr.read_u32::<LittleEndian>()?;
+        let count = rdr.read_u32::<LittleEndian>()? as usize;
+        let _reserved = rdr.read_u32::<LittleEndian>()?;
+        ensure!(
+            value.len() >= 16 + count * ENTRY_SIZE,
+            "the metadata vector is too short"
+        );
+
+        let mut entries = Vec::with_capacity(count);
+        for _ in 0..count {
+            let attribute_id = rdr.read_u32::<LittleEndian>()?;
+            let version = rdr.read_u32::<LittleEndian>()?;
+            let time_changed = TruncatedWindowsFileTime::try_from(rdr.read_u64::<LittleEndian>()?)?;
+            let position = rdr.position() as usize;
+            let originating_dsa = Guid::try_from(&value[position..position + 16])?;
+            rdr.set_position((position + 16) as u64);
+            entries.push(PropertyMetaData {
+                attribute_id,
+                version,
+                time_changed,
+                originating_dsa,
+                originating_usn: rdr.read_i64::<LittleEndian>()?,
+                local_usn: rdr.read_i64::<LittleEndian>()?,
+            });
+        }
+        Ok(Self(entries))
+    }
+}
+
+impl ReplicationMetaData {
+    pub fn entries(&self) -> &[PropertyMetaData] {
+        &self.0
+    }
+
+    /// returns the metadata of the attribute with the given id
+    pub fn of(&self, attribute_id: u32) -> Option<&PropertyMetaData> {
+        self.0.iter().find(|e| e.attribute_id == attribute_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReplicationMetaData;
+
+    #[test]
+    fn test_parse_metadata() {
+        let mut value = Vec::new();
+        for v in [1u32, 0, 1, 0, 0x20030, 2] {
+            value.extend_from_slice(&v.to_le_bytes());
+        }
+        value.extend_from_slice(&13390472401u64.to_le_bytes());
+        value.extend_from_slice(&[
+            0x78, 0x56, 0x34, 0x12, 0x34, 0x12, 0x34, 0x12, 0x12, 0x34, 0x12, 0x34, 0x56, 0x78,
+            0x9a, 0xbc,
+        ]);
+        value.extend_from_slice(&4711i64.to_le_bytes());
+        value.extend_from_slice(&4712i64.to_le_bytes());
+
+        let metadata = ReplicationMetaData::try_from(&value[..]).unwrap();
+        assert_eq!(metadata.entries().len(), 1);
+
+        let is_deleted = metadata.of(0x20030).unwrap();
+        assert_eq!(*is_deleted.version(), 2);
+        assert_eq!(
+            is_deleted.originating_dsa().to_string(),
+            "12345678-1234-1234-1234-123456789abc"
+        );
+        assert_eq!(*is_deleted.originating_usn(), 4711);
+        assert_eq!(*is_deleted.local_usn(), 4712);
+        assert!(metadata.of(0x90001).is_none());
+
+        assert!(ReplicationMetaData::try_from(&value[..60]).is_err());
+    }
+}
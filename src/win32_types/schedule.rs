@@ -0,0 +1,96 @@
+use std::fmt::Display;
+use std::io::Cursor;
+
+use anyhow::{ensure, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+
+/// number of hours per week, each of which has one byte in a schedule
+const HOURS_PER_WEEK: usize = 7 * 24;
+
+/// `SCHEDULE_INTERVAL`, which is the only supported type of schedule
+const SCHEDULE_INTERVAL: u32 = 0;
+
+/// a replication schedule (`SCHEDULE`), as used by `nTDSConnection` and
+/// `siteLink` objects. For every hour of the week, it stores in which
+/// quarters of an hour replication is allowed
+///
+/// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-adts/ad3b2d3b-f28d-4c5e-8ab2-d2b53da7bd06>
+#[derive(Eq, PartialEq)]
+pub struct ReplicationSchedule {
+    hours: Vec<u8>,
+}
+
+impl TryFrom<&[u8]> for ReplicationSchedule {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        let mut rdr = Cursor::new(value);
+        let _size = rdr.read_u32::<LittleEndian>()?;
+        let _bandwidth = rdr.read_u32::<LittleEndian>()?;
+        let number_of_schedules = rdr.read_u32::<LittleEndian>()?;
+        ensure!(number_of_schedules >= 1, "the schedule has no intervals");
+
+        let schedule_type = rdr.read_u32::<LittleEndian>()?;
+        ensure!(
+            schedule_type == SCHEDULE_INTERVAL,
+            "unsupported schedule type: {schedule_type}"
+        );
+        let offset = rdr.read_u32::<LittleEndian>()? as usize;
+        ensure!(
+            value.len() >= offset + HOURS_PER_WEEK,
+            "the schedule is too short"
+        );
+
+        Ok(Self {
+            hours: value[offset..offset + HOURS_PER_WEEK].to_vec(),
+        })
+    }
+}
+
+impl ReplicationSchedule {
+    /// returns the number of hours per week in which replication is allowed
+    /// (in at least one quarter of an hour)
+    pub fn active_hours(&self) -> usize {
+        self.hours.iter().filter(|h| *h & 0x0f != 0).count()
+    }
+}
+
+impl Display for ReplicationSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.active_hours() {
+            0 => write!(f, "never"),
+            HOURS_PER_WEEK if self.hours.iter().all(|h| *h & 0x0f == 0x0f) => write!(f, "always"),
+            hours => write!(f, "{hours} of {HOURS_PER_WEEK} hours per week"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReplicationSchedule;
+
+    fn schedule(hours: &[u8]) -> Vec<u8> {
+        let mut result = Vec::new();
+        for v in [188u32, 0, 1, 0, 20] {
+            result.extend_from_slice(&v.to_le_bytes());
+        }
+        result.extend_from_slice(hours);
+        result
+    }
+
+    #[test]
+    fn test_parse_schedule() {
+        let always = schedule(&[0x0f; 168]);
+        let schedule_always = ReplicationSchedule::try_from(&always[..]).unwrap();
+        assert_eq!(schedule_always.to_string(), "always");
+
+        let mut hours = [0u8; 168];
+        hours[..12].fill(0x01);
+        let partial = schedule(&hours);
+        let schedule_partial = ReplicationSchedule::try_from(&partial[..]).unwrap();
+        assert_eq!(schedule_partial.active_hours(), 12);
+        assert_eq!(schedule_partial.to_string(), "12 of 168 hours per week");
+
+        assert!(ReplicationSchedule::try_from(&always[..100]).is_err());
+    }
+}
@@ -2,15 +2,40 @@ use std::fmt::Display;
 
 use crate::value::FromValue;
 
+use super::{AccessControlEntry, Sid};
+
 pub struct SecurityDescriptor(sddl::SecurityDescriptor);
 
+impl SecurityDescriptor {
+    pub fn owner(&self) -> Option<Sid> {
+        self.0.owner().as_ref().map(Sid::from)
+    }
+
+    /// returns all access allowed and access denied entries of the DACL
+    pub fn dacl_entries(&self) -> Vec<AccessControlEntry> {
+        self.0
+            .dacl()
+            .as_ref()
+            .map(|dacl| {
+                dacl.ace_list()
+                    .iter()
+                    .filter_map(AccessControlEntry::from_ace)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
 impl FromValue for SecurityDescriptor {
     fn from_value_opt(value: &crate::cache::Value) -> crate::ntds::Result<Option<Self>>
     where
         Self: Sized {
         match value {
             crate::cache::Value::Null(_) => Ok(None),
-            crate::cache::Value::Binary(vec) | crate::cache::Value::LargeBinary(vec) => 
+            crate::cache::Value::Multi(values) => crate::value::first_value_of(values),
+            crate::cache::Value::Binary(vec)
+            | crate::cache::Value::LargeBinary(vec)
+            | crate::cache::Value::Long(vec) =>
             {
                 Ok(Some(Self(sddl::SecurityDescriptor::from_bytes(&vec[..])?)))
             }
@@ -8,7 +8,7 @@ mod sid_visitor;
 
 ///
 /// https://devblogs.microsoft.com/oldnewthing/20040315-00/?p=40253
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Hash)]
 pub struct Sid {
     revision: u8,
     authority: u64,
@@ -26,6 +26,73 @@ impl Sid {
             numbers,
         }
     }
+
+    pub fn authority(&self) -> u64 {
+        self.authority
+    }
+
+    pub fn sub_authorities(&self) -> &[u32] {
+        &self.numbers[..]
+    }
+
+    /// returns `true` if this SID belongs to a domain (`S-1-5-21-...`)
+    /// and `false` if it is a well-known or builtin SID
+    pub fn is_domain_sid(&self) -> bool {
+        self.authority == 5 && self.numbers.len() == 5 && self.numbers[0] == 21
+    }
+
+    /// returns the SID of the domain this SID belongs to, or `None` if this
+    /// is not a domain SID
+    pub fn domain_sid(&self) -> Option<Self> {
+        if self.is_domain_sid() {
+            Some(Self::new(
+                self.revision,
+                self.authority,
+                self.numbers[..4].to_vec(),
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// creates the SID of an account in the domain with this SID
+    pub fn with_rid(&self, rid: u32) -> Self {
+        let mut numbers = self.numbers.clone();
+        numbers.push(rid);
+        Self::new(self.revision, self.authority, numbers)
+    }
+
+    /// returns `true` if this is a builtin SID (`S-1-5-32-...`)
+    pub fn is_builtin_sid(&self) -> bool {
+        self.authority == 5 && self.numbers.len() == 2 && self.numbers[0] == 32
+    }
+
+    /// returns `true` for `S-1-5-18` (Local System)
+    pub fn is_local_system(&self) -> bool {
+        self.authority == 5 && self.numbers == [18]
+    }
+
+    /// returns `true` if this SID refers to one of the highly privileged
+    /// default accounts or groups, e.g. Domain Admins or Administrators
+    pub fn is_privileged(&self) -> bool {
+        const DOMAIN_RIDS: [u32; 10] = [500, 502, 512, 516, 518, 519, 520, 521, 526, 527];
+        const BUILTIN_RIDS: [u32; 5] = [544, 548, 549, 550, 551];
+
+        (self.is_domain_sid() && DOMAIN_RIDS.contains(self.get_rid()))
+            || (self.is_builtin_sid() && BUILTIN_RIDS.contains(self.get_rid()))
+    }
+}
+
+impl From<&sddl::Sid> for Sid {
+    fn from(sid: &sddl::Sid) -> Self {
+        let mut authority = [0u8; 8];
+        authority[2..].copy_from_slice(sid.identifier_authority().value());
+        Self {
+            revision: *sid.revision(),
+            authority: u64::from_be_bytes(authority),
+            numbers: sid.sub_authority().clone(),
+        }
+    }
 }
 
 impl TryFrom<&Vec<u8>> for Sid {
@@ -102,4 +169,36 @@ mod tests {
             vec![21, 2_623_811_015, 3_361_044_348, 30_300_820, 1013]
         );
     }
+
+    #[test]
+    fn test_privileged() {
+        let admins: Sid = serde_json::from_str(r#""S-1-5-21-1-2-3-512""#).unwrap();
+        let user: Sid = serde_json::from_str(r#""S-1-5-21-1-2-3-1013""#).unwrap();
+        let builtin_admins: Sid = serde_json::from_str(r#""S-1-5-32-544""#).unwrap();
+        assert!(admins.is_privileged());
+        assert!(!user.is_privileged());
+        assert!(builtin_admins.is_privileged());
+        assert!(user.domain_sid() == admins.domain_sid());
+        assert!(builtin_admins.domain_sid().is_none());
+    }
+
+    #[test]
+    fn test_sid_history_checks() {
+        let domain: Sid = serde_json::from_str(r#""S-1-5-21-1-2-3""#).unwrap();
+        let other_domain: Sid = serde_json::from_str(r#""S-1-5-21-4-5-6""#).unwrap();
+
+        let migrated_user = other_domain.with_rid(1104);
+        assert_eq!(migrated_user.to_string(), "S-1-5-21-4-5-6-1104");
+        assert!(migrated_user.domain_sid() != Some(domain.clone()));
+        assert!(!migrated_user.is_privileged());
+
+        // a SID of the own domain in sIDHistory, e.g. injected by mimikatz
+        let injected = domain.with_rid(519);
+        assert!(injected.domain_sid() == Some(domain));
+        assert!(injected.is_privileged());
+
+        let local_system: Sid = serde_json::from_str(r#""S-1-5-18""#).unwrap();
+        assert!(local_system.is_local_system());
+        assert!(!local_system.is_domain_sid());
+    }
 }
@@ -0,0 +1,37 @@
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+
+bitflags! {
+
+    /// Source: https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-kile/6cfc7b50-11ed-4b4d-846d-6f08f0812919
+    #[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+    pub struct SupportedEncryptionTypes : u32 {
+
+        const DES_CBC_CRC = 0x0000_0001;
+
+        const DES_CBC_MD5 = 0x0000_0002;
+
+        const RC4_HMAC = 0x0000_0004;
+
+        const AES128_CTS_HMAC_SHA1_96 = 0x0000_0008;
+
+        const AES256_CTS_HMAC_SHA1_96 = 0x0000_0010;
+
+        /// Flexible Authentication Secure Tunneling (FAST) is supported
+        const FAST_SUPPORTED = 0x0001_0000;
+
+        const COMPOUND_IDENTITY_SUPPORTED = 0x0002_0000;
+
+        const CLAIMS_SUPPORTED = 0x0004_0000;
+
+        const RESOURCE_SID_COMPRESSION_DISABLED = 0x0008_0000;
+    }
+}
+
+impl SupportedEncryptionTypes {
+    /// returns `true` if no AES encryption type is supported, which means
+    /// that RC4 (or even DES) will be used for service tickets
+    pub fn lacks_aes(&self) -> bool {
+        !self.intersects(Self::AES128_CTS_HMAC_SHA1_96 | Self::AES256_CTS_HMAC_SHA1_96)
+    }
+}
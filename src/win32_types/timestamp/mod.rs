@@ -51,9 +51,16 @@ macro_rules! impl_timestamp {
                 match value {
                     $crate::cache::Value::Currency(val) => {
                         let val = *val as u64;
-                        Ok(Some($type::from(val)))
+                        match $type::try_from(val) {
+                            Ok(ts) => Ok(Some(ts)),
+                            Err(_) => Err($crate::ntds::Error::InvalidValueDetected(
+                                value.to_string(),
+                                stringify!($type),
+                            )),
+                        }
                     },
                     $crate::cache::Value::Null(()) => Ok(None),
+                    $crate::cache::Value::Multi(values) => $crate::value::first_value_of(values),
                     _ => Err($crate::ntds::Error::InvalidValueDetected(
                         value.to_string(),
                         stringify!($type),
@@ -1,10 +1,11 @@
+use anyhow::anyhow;
 use chrono::{DateTime, Duration, Utc};
 use lazy_static::lazy_static;
 use libesedb::systemtime_from_filetime;
 
 use crate::impl_timestamp;
 
-#[derive(Eq, PartialEq)]
+#[derive(Eq, PartialEq, Clone)]
 pub struct TruncatedWindowsFileTime(DateTime<Utc>);
 
 impl_timestamp!(TruncatedWindowsFileTime);
@@ -13,9 +14,16 @@ lazy_static!{
     static ref BASE_TIME: DateTime<Utc> = systemtime_from_filetime(0).into();
 }
 
-impl From<u64> for TruncatedWindowsFileTime {
-    fn from(value: u64) -> Self {
-        Self(*BASE_TIME + Duration::seconds(value.try_into().unwrap()))
+impl TryFrom<u64> for TruncatedWindowsFileTime {
+    type Error = anyhow::Error;
+
+    /// converts the number of seconds since 1601-01-01
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        let seconds = i64::try_from(value)?;
+        Duration::try_seconds(seconds)
+            .and_then(|duration| BASE_TIME.checked_add_signed(duration))
+            .map(Self)
+            .ok_or_else(|| anyhow!("timestamp is out of range: {value}"))
     }
 }
 impl From<DateTime<Utc>> for TruncatedWindowsFileTime {
@@ -36,7 +44,14 @@ mod tests {
     #[test]
     fn convert_from_ntds() {
         let ds_record_time = 13390472401u64;
-        let ts = TruncatedWindowsFileTime::from(ds_record_time);
+        let ts = TruncatedWindowsFileTime::try_from(ds_record_time).unwrap();
         assert_eq!(ts.0.to_rfc3339(), "2025-04-30T07:40:01+00:00");
     }
+
+    #[test]
+    fn convert_out_of_range() {
+        assert!(TruncatedWindowsFileTime::try_from(u64::MAX).is_err());
+        assert!(TruncatedWindowsFileTime::try_from(i64::MAX as u64).is_err());
+        assert!(TruncatedWindowsFileTime::try_from(i64::MAX as u64 / 1000).is_err());
+    }
 }
\ No newline at end of file
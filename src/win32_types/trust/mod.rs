@@ -0,0 +1,123 @@
+use bitflags::bitflags;
+use num_derive::FromPrimitive;
+use serde::{Deserialize, Serialize};
+
+/// Source: https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-adts/5026a939-44ba-47b2-99cf-386a9e674b04
+#[derive(FromPrimitive, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum TrustDirection {
+    TRUST_DIRECTION_DISABLED = 0,
+    TRUST_DIRECTION_INBOUND = 1,
+    TRUST_DIRECTION_OUTBOUND = 2,
+    TRUST_DIRECTION_BIDIRECTIONAL = 3,
+}
+
+/// Source: https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-adts/36565693-b5e4-4f37-b0a8-c1b12138e18e
+#[derive(FromPrimitive, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum TrustType {
+    /// the trusted domain is a Windows domain not running Active Directory
+    TRUST_TYPE_DOWNLEVEL = 1,
+
+    /// the trusted domain is a Windows domain running Active Directory
+    TRUST_TYPE_UPLEVEL = 2,
+
+    /// the trusted domain is running a non-Windows, RFC4120-compliant
+    /// Kerberos distribution
+    TRUST_TYPE_MIT = 3,
+    TRUST_TYPE_DCE = 4,
+    TRUST_TYPE_AAD = 5,
+}
+
+bitflags! {
+
+    /// Source: https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-adts/e9a2d23c-c31e-4a6f-88a0-6646fdb51a3c
+    #[derive(PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+    pub struct TrustAttributes : u32 {
+
+        const TRUST_ATTRIBUTE_NON_TRANSITIVE = 0x0000_0001;
+
+        const TRUST_ATTRIBUTE_UPLEVEL_ONLY = 0x0000_0002;
+
+        /// SID filtering is enabled for this trust
+        const TRUST_ATTRIBUTE_QUARANTINED_DOMAIN = 0x0000_0004;
+
+        const TRUST_ATTRIBUTE_FOREST_TRANSITIVE = 0x0000_0008;
+
+        /// selective authentication is enabled for this trust
+        const TRUST_ATTRIBUTE_CROSS_ORGANIZATION = 0x0000_0010;
+
+        const TRUST_ATTRIBUTE_WITHIN_FOREST = 0x0000_0020;
+
+        /// SID filtering is relaxed, so that SIDs of the trusted forest
+        /// are accepted, like in an external trust
+        const TRUST_ATTRIBUTE_TREAT_AS_EXTERNAL = 0x0000_0040;
+
+        const TRUST_ATTRIBUTE_USES_RC4_ENCRYPTION = 0x0000_0080;
+
+        const TRUST_ATTRIBUTE_CROSS_ORGANIZATION_NO_TGT_DELEGATION = 0x0000_0200;
+
+        const TRUST_ATTRIBUTE_PIM_TRUST = 0x0000_0400;
+
+        /// TGTs of the trusted forest may be delegated to services in this forest
+        const TRUST_ATTRIBUTE_CROSS_ORGANIZATION_ENABLE_TGT_DELEGATION = 0x0000_0800;
+
+        const TRUST_ATTRIBUTE_DISABLE_AUTH_TARGET_VALIDATION = 0x0000_1000;
+    }
+}
+
+impl TrustAttributes {
+    /// trusts inside of a forest never filter SIDs, forest trusts do so
+    /// unless they are treated as external trusts, and external trusts
+    /// only if they are quarantined
+    pub fn sid_filtering(&self) -> bool {
+        self.contains(Self::TRUST_ATTRIBUTE_QUARANTINED_DOMAIN)
+            || (self.contains(Self::TRUST_ATTRIBUTE_FOREST_TRANSITIVE)
+                && !self.contains(Self::TRUST_ATTRIBUTE_TREAT_AS_EXTERNAL))
+    }
+
+    pub fn selective_authentication(&self) -> bool {
+        self.contains(Self::TRUST_ATTRIBUTE_CROSS_ORGANIZATION)
+    }
+
+    pub fn tgt_delegation(&self) -> bool {
+        self.contains(Self::TRUST_ATTRIBUTE_CROSS_ORGANIZATION_ENABLE_TGT_DELEGATION)
+            && !self.contains(Self::TRUST_ATTRIBUTE_CROSS_ORGANIZATION_NO_TGT_DELEGATION)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrustAttributes;
+
+    #[test]
+    fn test_sid_filtering() {
+        assert!(!TrustAttributes::empty().sid_filtering());
+        assert!(!TrustAttributes::TRUST_ATTRIBUTE_WITHIN_FOREST.sid_filtering());
+        assert!(TrustAttributes::TRUST_ATTRIBUTE_QUARANTINED_DOMAIN.sid_filtering());
+        assert!(TrustAttributes::TRUST_ATTRIBUTE_FOREST_TRANSITIVE.sid_filtering());
+        assert!(!(TrustAttributes::TRUST_ATTRIBUTE_FOREST_TRANSITIVE
+            | TrustAttributes::TRUST_ATTRIBUTE_TREAT_AS_EXTERNAL)
+            .sid_filtering());
+    }
+
+    #[test]
+    fn test_tgt_delegation() {
+        assert!(!TrustAttributes::empty().tgt_delegation());
+        assert!(
+            TrustAttributes::TRUST_ATTRIBUTE_CROSS_ORGANIZATION_ENABLE_TGT_DELEGATION
+                .tgt_delegation()
+        );
+        assert!(
+            !(TrustAttributes::TRUST_ATTRIBUTE_CROSS_ORGANIZATION_ENABLE_TGT_DELEGATION
+                | TrustAttributes::TRUST_ATTRIBUTE_CROSS_ORGANIZATION_NO_TGT_DELEGATION)
+                .tgt_delegation()
+        );
+    }
+
+    #[test]
+    fn test_selective_authentication() {
+        assert!(TrustAttributes::TRUST_ATTRIBUTE_CROSS_ORGANIZATION.selective_authentication());
+        assert!(!TrustAttributes::TRUST_ATTRIBUTE_FOREST_TRANSITIVE.selective_authentication());
+    }
+}
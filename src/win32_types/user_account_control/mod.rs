@@ -86,5 +86,9 @@ bitflags! {
         /// account to assume a client identity and authenticate as that user
         /// to other remote servers on the network.
         const ADS_UF_TRUSTED_TO_AUTHENTICATE_FOR_DELEGATION = 0x0100_0000;
+
+        /// The account is a computer account for a read-only domain
+        /// controller (RODC).
+        const ADS_UF_PARTIAL_SECRETS_ACCOUNT = 0x0400_0000;
     }
 }